@@ -0,0 +1,97 @@
+//! Throughput of each compiler stage on `examples/valid/sample_program.ms`,
+//! then of lexing and parsing a generated 5000-line program with the token
+//! vector from `tokenize` and with the lazy `LexerIter`.
+//!
+//! Uses the standard library only (`harness = false`), so it runs on stable
+//! with `cargo bench`. See `BENCHMARKS.md` for the baseline numbers.
+
+use rust_compiler::codegen::assembly_gen::AssemblyGenerator;
+use rust_compiler::codegen::generator::CodeGenerator;
+use rust_compiler::lexer::lexer_core::{LexerIter, tokenize};
+use rust_compiler::parser::parser_core::parse;
+use rust_compiler::semantics::SemanticAnalyzer;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SAMPLE: &str = "examples/valid/sample_program.ms";
+const ITERATIONS: u32 = 2000;
+const LARGE_LINES: usize = 5000;
+const LARGE_ITERATIONS: u32 = 100;
+
+/// Runs `stage` `iterations` times after a short warm-up
+fn measure<T>(iterations: u32, mut stage: impl FnMut() -> T) -> Duration {
+    for _ in 0..iterations / 10 {
+        black_box(stage());
+    }
+    let start = Instant::now();
+    for _ in 0..iterations {
+        black_box(stage());
+    }
+    start.elapsed() / iterations
+}
+
+fn report(stage: &str, per_run: Duration, units: usize, unit_name: &str) {
+    let per_second = units as f64 / per_run.as_secs_f64();
+    println!(
+        "{:<10} {:>10.2?}/run {:>14.0} {}/s",
+        stage, per_run, per_second, unit_name
+    );
+}
+
+fn main() {
+    let source = std::fs::read_to_string(SAMPLE).expect("sample program is missing");
+    let (tokens, _) = tokenize(&source);
+    let program = parse(tokens.clone(), &source).expect("sample program does not parse");
+    let quadruples = CodeGenerator::new().generate_code(&program).unwrap();
+    let statements = program.declarations.len() + program.statements.len();
+
+    println!("{} ({} bytes, {} tokens)", SAMPLE, source.len(), tokens.len());
+
+    let time = measure(ITERATIONS, || tokenize(&source));
+    report("lex", time, tokens.len(), "tokens");
+
+    let time = measure(ITERATIONS, || parse(tokens.clone(), &source));
+    report("parse", time, tokens.len(), "tokens");
+
+    let time = measure(ITERATIONS, || {
+        let mut analyzer = SemanticAnalyzer::new(&source);
+        analyzer.analyze(&program);
+        analyzer
+    });
+    report("analyze", time, statements, "top-level nodes");
+
+    let time = measure(ITERATIONS, || CodeGenerator::new().generate_code(&program));
+    report("quadruples", time, quadruples.quadruples.len(), "quadruples");
+
+    let time = measure(ITERATIONS, || {
+        AssemblyGenerator::new(quadruples.clone()).generate_to_string()
+    });
+    report("assembly", time, quadruples.quadruples.len(), "quadruples");
+
+    let large = large_program();
+    let (tokens, _) = tokenize(&large);
+    println!(
+        "\ngenerated program ({} lines, {} bytes, {} tokens)",
+        LARGE_LINES,
+        large.len(),
+        tokens.len()
+    );
+
+    let time = measure(LARGE_ITERATIONS, || parse(tokenize(&large).0, &large));
+    report("collected", time, tokens.len(), "tokens");
+
+    let time = measure(LARGE_ITERATIONS, || {
+        parse(LexerIter::new(&large).filter_map(Result::ok), &large)
+    });
+    report("lazy", time, tokens.len(), "tokens");
+}
+
+/// A valid program with `LARGE_LINES` assignment lines
+fn large_program() -> String {
+    let mut source = String::from("MainPrgm large;\nVar\nlet x, y: Int;\nBeginPg\n{\n");
+    for line in 0..LARGE_LINES {
+        source.push_str(&format!("    x := (x + {}) * y - {};\n", line % 100, line % 7));
+    }
+    source.push_str("}\nEndPg;\n");
+    source
+}
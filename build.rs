@@ -1,3 +1,33 @@
+use std::env;
+use std::process::Command;
+
 fn main() {
     lalrpop::process_root().unwrap();
+
+    // Toolchain and feature information shown by `msrc --version`
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .and_then(|version| {
+            // "rustc 1.78.0 (9b00956e5 2024-04-29)" -> "rustc 1.78.0"
+            let mut parts = version.split_whitespace();
+            Some(format!("{} {}", parts.next()?, parts.next()?))
+        })
+        .unwrap_or_else(|| "rustc unknown".to_string());
+    println!("cargo:rustc-env=MSRC_RUSTC_VERSION={}", rustc_version);
+
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(str::to_lowercase))
+        .filter(|feature| feature != "default")
+        .collect();
+    features.sort();
+    let features = if features.is_empty() {
+        "none".to_string()
+    } else {
+        features.join(" ")
+    };
+    println!("cargo:rustc-env=MSRC_FEATURES={}", features);
 }
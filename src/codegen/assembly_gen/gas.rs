@@ -0,0 +1,158 @@
+//! Rewrites the NASM output for the GNU assembler.
+//!
+//! Instructions keep their Intel syntax through `.intel_syntax noprefix`, so
+//! only directives, comments, memory operand sizes, label references and the
+//! NASM-specific local labels change.
+
+use regex::{Captures, Regex};
+
+/// Registers that can start a memory operand; any other name is a label.
+const REGISTERS: [&str; 16] = [
+    "rax", "rbx", "rcx", "rdx", "rsi", "rdi", "rbp", "rsp", "r8", "r9", "r10", "r11", "r12", "r13",
+    "r14", "r15",
+];
+
+/// Translate the NASM text produced by `generate_nasm` into GAS syntax.
+pub(super) fn from_nasm(nasm: &str) -> String {
+    let data = Regex::new(r"^([\w.]+): (dq|db|resq|resb) (.*)$").unwrap();
+    let label_operand = Regex::new(r"\[(\w+)\]").unwrap();
+    let sized_operand = Regex::new(r"\b(qword|dword|word|byte) \[").unwrap();
+    let character = Regex::new(r"'(.)'").unwrap();
+    let local_label = Regex::new(r"(^|[\s,\[])\.(\w+)").unwrap();
+
+    let mut output = String::new();
+    // NASM scopes `.name` labels to the last label without a dot
+    let mut scope = String::new();
+
+    for line in nasm.lines() {
+        let indent = &line[..line.len() - line.trim_start().len()];
+        let (code, comment) = split_comment(line.trim_start());
+
+        let translated = if let Some(directive) = translate_directive(code) {
+            directive
+        } else if let Some(captures) = data.captures(code) {
+            translate_data(&captures).join(&format!("\n{}", indent))
+        } else if let Some(line) = code.strip_prefix("%line ") {
+            let line = line.split('+').next().unwrap_or(line);
+            format!("# line {}", line)
+        } else {
+            if let Some(label) = code.strip_suffix(':')
+                && !label.starts_with('.')
+            {
+                scope = label.to_string();
+            }
+            let code = local_label.replace_all(code, |captures: &Captures| {
+                format!("{}.L{}_{}", &captures[1], scope, &captures[2])
+            });
+            let code = label_operand.replace_all(&code, |captures: &Captures| {
+                if REGISTERS.contains(&&captures[1]) {
+                    captures[0].to_string()
+                } else {
+                    format!("[rip + {}]", &captures[1])
+                }
+            });
+            let code = sized_operand.replace_all(&code, "$1 ptr [");
+            character
+                .replace_all(&code, |captures: &Captures| {
+                    captures[1].as_bytes()[0].to_string()
+                })
+                .into_owned()
+        };
+
+        output.push_str(indent);
+        output.push_str(&translated);
+        if let Some(comment) = comment {
+            if !translated.is_empty() {
+                output.push(' ');
+            }
+            output.push_str(&format!("# {}", comment));
+        }
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Split `line` at a `;` comment that is not inside a string or character.
+fn split_comment(line: &str) -> (&str, Option<&str>) {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, ';') => {
+                return (
+                    line[..index].trim_end(),
+                    Some(line[index + 1..].trim_start()),
+                );
+            }
+            _ => {}
+        }
+    }
+    (line, None)
+}
+
+fn translate_directive(code: &str) -> Option<String> {
+    let directive = match code {
+        "bits 64" => ".intel_syntax noprefix".to_string(),
+        // Label operands are made RIP-relative one by one
+        "default rel" => String::new(),
+        _ => {
+            if let Some(section) = code.strip_prefix("section ") {
+                section.to_string()
+            } else if let Some(symbol) = code.strip_prefix("global ") {
+                format!(".globl {}", symbol)
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(directive)
+}
+
+/// `name: dq 1` and friends, as a label and the GAS data directives after it.
+fn translate_data(captures: &Captures) -> Vec<String> {
+    let (name, kind, operands) = (&captures[1], &captures[2], &captures[3]);
+    let mut directives = match kind {
+        "dq" => vec![format!(".quad {}", operands)],
+        "resq" => vec![format!(
+            ".space {}",
+            operands.parse::<usize>().unwrap_or(0) * 8
+        )],
+        "resb" => vec![format!(".space {}", operands)],
+        _ => string_directives(operands),
+    };
+    directives[0] = format!("{}: {}", name, directives[0]);
+    directives
+}
+
+/// NASM `db "text", 10, 0` operands as `.ascii "text"` and `.byte 10, 0`.
+fn string_directives(operands: &str) -> Vec<String> {
+    let mut directives = Vec::new();
+    let mut bytes: Vec<&str> = Vec::new();
+    let mut rest = operands.trim();
+    while !rest.is_empty() {
+        if let Some(quoted) = rest.strip_prefix('"') {
+            if !bytes.is_empty() {
+                directives.push(format!(".byte {}", bytes.join(", ")));
+                bytes.clear();
+            }
+            let end = quoted.find('"').unwrap_or(quoted.len());
+            // Unlike NASM, GAS reads backslash escapes in strings
+            directives.push(format!(
+                ".ascii \"{}\"",
+                quoted[..end].replace('\\', "\\\\")
+            ));
+            rest = quoted.get(end + 1..).unwrap_or("");
+        } else {
+            let end = rest.find(',').unwrap_or(rest.len());
+            bytes.push(rest[..end].trim());
+            rest = &rest[end..];
+        }
+        rest = rest.trim_start_matches([',', ' ']);
+    }
+    if !bytes.is_empty() {
+        directives.push(format!(".byte {}", bytes.join(", ")));
+    }
+    directives
+}
@@ -0,0 +1,422 @@
+use super::{AssemblyGenerator, runtime};
+use crate::codegen::quadruple::{Operand, Operation, Quadruple};
+use crate::parser::ast::Type;
+
+impl AssemblyGenerator {
+    fn emit(&mut self, instruction: impl Into<String>) {
+        self.instructions.push(instruction.into());
+    }
+
+    pub(super) fn quad_to_instructions(&mut self, quad: &Quadruple) {
+        // Map the following instructions back to the MiniSoft source line
+        if let Some(line) = quad.source_line()
+            && self.current_line != Some(line)
+        {
+            self.emit(format!("%line {}+0", line));
+            self.current_line = Some(line);
+        }
+        self.emit(format!("; {}", quad));
+
+        match &quad.operation {
+            Operation::DeclareVariable(typ) => self.declare_variable(typ, quad),
+            Operation::DeclareArray(typ, size) => {
+                if let Operand::Variable(name) = &quad.result {
+                    self.variable_types.insert(name.clone(), typ.clone());
+                    self.bss_section.push(format!("v_{}: resq {}", name, size));
+                }
+            }
+            Operation::Assign => {
+                // Temporaries take the type of the value copied into them
+                let target_type = match &quad.result {
+                    Operand::TempVariable(_) => self.operand_type(&quad.operand1),
+                    result => self.operand_type(result),
+                };
+                self.load_operand_as(&quad.operand1, &target_type);
+                self.store_result(&quad.result, target_type);
+            }
+            Operation::ArrayStore => {
+                if let Operand::Variable(name) = &quad.result {
+                    let element_type = self.operand_type(&quad.result);
+                    self.load_operand_as(&quad.operand2, &Type::Int);
+                    self.emit("mov rcx, rax");
+                    self.load_operand_as(&quad.operand1, &element_type);
+                    self.emit(format!("lea rbx, [v_{}]", name));
+                    self.emit("mov [rbx + rcx * 8], rax");
+                }
+            }
+            Operation::ArrayLoad => {
+                if let Operand::Variable(name) = &quad.operand1 {
+                    let element_type = self.operand_type(&quad.operand1);
+                    self.load_operand_as(&quad.operand2, &Type::Int);
+                    self.emit("mov rcx, rax");
+                    self.emit(format!("lea rbx, [v_{}]", name));
+                    self.emit("mov rax, [rbx + rcx * 8]");
+                    self.store_result(&quad.result, element_type);
+                }
+            }
+            Operation::MemCopy(size) => {
+                // Every element is a qword, so one movsq per element
+                if let (Operand::Variable(source), Operand::Variable(target)) =
+                    (&quad.operand1, &quad.result)
+                {
+                    self.emit(format!("lea rsi, [v_{}]", source));
+                    self.emit(format!("lea rdi, [v_{}]", target));
+                    self.emit(format!("mov rcx, {}", size));
+                    self.emit("cld");
+                    self.emit("rep movsq");
+                }
+            }
+            Operation::Add | Operation::Subtract | Operation::Multiply | Operation::Divide => {
+                self.gen_arithmetic(quad);
+            }
+            Operation::Modulo => {
+                // idiv leaves the remainder in rdx
+                self.load_operand_as(&quad.operand2, &Type::Int);
+                self.emit("mov rcx, rax");
+                self.load_operand_as(&quad.operand1, &Type::Int);
+                self.emit("cqo");
+                self.emit("idiv rcx");
+                self.emit("mov rax, rdx");
+                self.store_result(&quad.result, Type::Int);
+            }
+            Operation::Negate => {
+                let value_type = self.operand_type(&quad.operand1);
+                self.load_operand_as(&quad.operand1, &value_type);
+                if value_type == Type::Float {
+                    // Flipping the sign bit also negates zero and NaN
+                    self.emit("btc rax, 63");
+                } else {
+                    self.emit("neg rax");
+                }
+                self.store_result(&quad.result, value_type);
+            }
+            Operation::Equal
+            | Operation::NotEqual
+            | Operation::LessThan
+            | Operation::GreaterThan
+            | Operation::LessEqual
+            | Operation::GreaterEqual => self.gen_comparison(quad),
+            Operation::And | Operation::Or => {
+                self.load_operand_as(&quad.operand2, &Type::Int);
+                self.emit("mov rcx, rax");
+                self.load_operand_as(&quad.operand1, &Type::Int);
+                self.emit("test rax, rax");
+                self.emit("setne al");
+                self.emit("test rcx, rcx");
+                self.emit("setne cl");
+                if quad.operation == Operation::And {
+                    self.emit("and al, cl");
+                } else {
+                    self.emit("or al, cl");
+                }
+                self.emit("movzx rax, al");
+                self.store_result(&quad.result, Type::Int);
+            }
+            Operation::Not => {
+                self.load_operand_as(&quad.operand1, &Type::Int);
+                self.emit("test rax, rax");
+                self.emit("sete al");
+                self.emit("movzx rax, al");
+                self.store_result(&quad.result, Type::Int);
+            }
+            Operation::Label { id, .. } => {
+                let label = self.label(*id);
+                self.emit(format!("{}:", label));
+                if self.profile {
+                    self.emit(format!("mov rdi, {}", self.profile_blocks.len()));
+                    self.emit("call profile_enter");
+                    self.profile_blocks.push(label);
+                }
+            }
+            Operation::Jump { id, .. } => {
+                let label = self.label(*id);
+                self.emit(format!("jmp {}", label));
+            }
+            Operation::JumpIfTrue { id, .. } => {
+                self.load_operand_as(&quad.operand1, &Type::Int);
+                self.emit("cmp rax, 0");
+                let label = self.label(*id);
+                self.emit(format!("jne {}", label));
+            }
+            Operation::JumpIfFalse { id, .. } => {
+                self.load_operand_as(&quad.operand1, &Type::Int);
+                self.emit("cmp rax, 0");
+                let label = self.label(*id);
+                self.emit(format!("je {}", label));
+            }
+            Operation::Input => {
+                let target_type = self.operand_type(&quad.result);
+                match target_type {
+                    Type::Int => self.emit("call read_int"),
+                    Type::Float => {
+                        self.emit("call read_float");
+                        self.emit("movq rax, xmm0");
+                    }
+                    Type::String => {
+                        // Every input statement reads into a buffer of its own
+                        let buffer = format!("input_{}", self.input_buffers);
+                        self.input_buffers += 1;
+                        self.bss_section.push(format!(
+                            "{}: resb {}",
+                            buffer,
+                            runtime::STRING_BUFFER_SIZE
+                        ));
+                        self.emit(format!("lea rdi, [{}]", buffer));
+                        self.emit("call read_string");
+                    }
+                }
+                self.store_result(&quad.result, target_type);
+            }
+            Operation::Output => {
+                let value_type = self.operand_type(&quad.operand1);
+                self.load_operand_as(&quad.operand1, &value_type);
+                let (argument, helper) = match value_type {
+                    Type::Int => ("mov rdi, rax", "print_int"),
+                    Type::Float => ("movq xmm0, rax", "print_float"),
+                    Type::String => ("mov rdi, rax", "print_string"),
+                };
+                self.emit(argument);
+                self.emit(format!("call {}", helper));
+            }
+            Operation::Call(name) => {
+                self.emit(format!("call {}", name));
+                if quad.result != Operand::Empty {
+                    self.store_result(&quad.result, Type::Int);
+                }
+            }
+            Operation::Return => {
+                if quad.operand1 != Operand::Empty {
+                    self.load_operand_as(&quad.operand1, &Type::Int);
+                }
+                self.emit("mov rsp, rbp");
+                self.emit("pop rbp");
+                self.emit("ret");
+            }
+            Operation::InlineAsm(lines) => {
+                for line in lines {
+                    self.emit(line.clone());
+                }
+            }
+            Operation::Nop => self.emit("nop"),
+            Operation::Phi(_) => panic!(
+                "phi-node reached the assembly generator: call QuadrupleProgram::from_ssa_form() first"
+            ),
+        }
+    }
+
+    fn declare_variable(&mut self, typ: &Type, quad: &Quadruple) {
+        let Operand::Variable(name) = &quad.result else {
+            return;
+        };
+        self.variable_types.insert(name.clone(), typ.clone());
+
+        // Literal initializers are baked into .data, anything else is computed at runtime
+        let initial = match (&quad.operand1, typ) {
+            (Operand::IntLiteral(value), Type::Int) => Some(value.to_string()),
+            (Operand::IntLiteral(value), Type::Float) => {
+                Some(format!("0x{:016X}", (*value as f64).to_bits()))
+            }
+            (Operand::FloatLiteral(value), Type::Float) => {
+                Some(format!("0x{:016X}", value.to_bits()))
+            }
+            (Operand::FloatLiteral(value), Type::Int) => Some((*value as i64).to_string()),
+            (Operand::StringLiteral(value), Type::String) => {
+                Some(format!("str_{}", self.string_label(value)))
+            }
+            _ => None,
+        };
+
+        match initial {
+            Some(value) => self.data_section.push(format!("v_{}: dq {}", name, value)),
+            None => {
+                self.bss_section.push(format!("v_{}: resq 1", name));
+                if quad.operand1 != Operand::Empty {
+                    self.load_operand_as(&quad.operand1, typ);
+                    self.store_result(&quad.result, typ.clone());
+                }
+            }
+        }
+    }
+
+    fn gen_arithmetic(&mut self, quad: &Quadruple) {
+        let value_type = self.common_type(&quad.operand1, &quad.operand2);
+        self.load_operand_as(&quad.operand2, &value_type);
+        self.emit("mov rcx, rax");
+        self.load_operand_as(&quad.operand1, &value_type);
+
+        if value_type == Type::Float {
+            let instruction = match quad.operation {
+                Operation::Add => "addsd",
+                Operation::Subtract => "subsd",
+                Operation::Multiply => "mulsd",
+                _ => "divsd",
+            };
+            self.emit("movq xmm0, rax");
+            self.emit("movq xmm1, rcx");
+            self.emit(format!("{} xmm0, xmm1", instruction));
+            self.emit("movq rax, xmm0");
+        } else {
+            match quad.operation {
+                Operation::Add => self.emit("add rax, rcx"),
+                Operation::Subtract => self.emit("sub rax, rcx"),
+                Operation::Multiply => self.emit("imul rax, rcx"),
+                _ => {
+                    self.emit("cqo");
+                    self.emit("idiv rcx");
+                }
+            }
+        }
+
+        self.store_result(&quad.result, value_type);
+    }
+
+    fn gen_comparison(&mut self, quad: &Quadruple) {
+        let value_type = self.common_type(&quad.operand1, &quad.operand2);
+        self.load_operand_as(&quad.operand2, &value_type);
+        self.emit("mov rcx, rax");
+        self.load_operand_as(&quad.operand1, &value_type);
+
+        // ucomisd sets the flags like an unsigned compare
+        let set_instruction = if value_type == Type::Float {
+            self.emit("movq xmm0, rax");
+            self.emit("movq xmm1, rcx");
+            self.emit("ucomisd xmm0, xmm1");
+            match quad.operation {
+                Operation::Equal => "sete",
+                Operation::NotEqual => "setne",
+                Operation::LessThan => "setb",
+                Operation::GreaterThan => "seta",
+                Operation::LessEqual => "setbe",
+                _ => "setae",
+            }
+        } else {
+            self.emit("cmp rax, rcx");
+            match quad.operation {
+                Operation::Equal => "sete",
+                Operation::NotEqual => "setne",
+                Operation::LessThan => "setl",
+                Operation::GreaterThan => "setg",
+                Operation::LessEqual => "setle",
+                _ => "setge",
+            }
+        };
+
+        self.emit(format!("{} al", set_instruction));
+        self.emit("movzx rax, al");
+        self.store_result(&quad.result, Type::Int);
+    }
+
+    /// Assembly name of label `id`: `L5`, or `for_loop_start_L5` if it is named.
+    fn label(&self, id: usize) -> String {
+        match self.label_names.get(&id) {
+            Some(name) => format!("{}_L{}", name, id),
+            None => format!("L{}", id),
+        }
+    }
+
+    /// Index of the `str_N` label holding `value`, adding it if needed.
+    pub(super) fn string_label(&mut self, value: &str) -> usize {
+        match self.string_literals.iter().position(|s| s == value) {
+            Some(index) => index,
+            None => {
+                self.string_literals.push(value.to_string());
+                self.string_literals.len() - 1
+            }
+        }
+    }
+
+    fn operand_type(&self, operand: &Operand) -> Type {
+        match operand {
+            Operand::IntLiteral(_) | Operand::Empty => Type::Int,
+            Operand::FloatLiteral(_) => Type::Float,
+            Operand::StringLiteral(_) => Type::String,
+            Operand::Variable(name) | Operand::ArrayElement(name, _) => {
+                self.variable_types.get(name).cloned().unwrap_or_default()
+            }
+            Operand::TempVariable(name) => self.temp_types.get(name).cloned().unwrap_or_default(),
+        }
+    }
+
+    fn common_type(&self, left: &Operand, right: &Operand) -> Type {
+        if self.operand_type(left) == Type::Float || self.operand_type(right) == Type::Float {
+            Type::Float
+        } else {
+            Type::Int
+        }
+    }
+
+    /// Load `operand` into `rax`, converting between Int and Float if needed.
+    fn load_operand_as(&mut self, operand: &Operand, target: &Type) {
+        let source = self.operand_type(operand);
+
+        match operand {
+            Operand::IntLiteral(value) => {
+                if *target == Type::Float {
+                    self.emit(format!("mov rax, 0x{:016X}", (*value as f64).to_bits()));
+                    return;
+                }
+                self.emit(format!("mov rax, {}", value));
+            }
+            Operand::FloatLiteral(value) => {
+                if *target == Type::Int {
+                    self.emit(format!("mov rax, {}", *value as i64));
+                    return;
+                }
+                self.emit(format!("mov rax, 0x{:016X}", value.to_bits()));
+            }
+            Operand::StringLiteral(value) => {
+                let label = self.string_label(value);
+                self.emit(format!("lea rax, [str_{}]", label));
+            }
+            Operand::Variable(name) => self.emit(format!("mov rax, [v_{}]", name)),
+            Operand::TempVariable(name) => {
+                self.emit(format!("mov rax, {}", temp_address(name)));
+            }
+            Operand::ArrayElement(name, index) => {
+                // r8 keeps rcx free for callers holding the other operand there
+                self.load_operand_as(index, &Type::Int);
+                self.emit("mov r8, rax");
+                self.emit(format!("lea rbx, [v_{}]", name));
+                self.emit("mov rax, [rbx + r8 * 8]");
+            }
+            Operand::Empty => self.emit("xor eax, eax"),
+        }
+
+        match (source, target) {
+            (Type::Int, Type::Float) => {
+                self.emit("cvtsi2sd xmm0, rax");
+                self.emit("movq rax, xmm0");
+            }
+            (Type::Float, Type::Int) => {
+                self.emit("movq xmm0, rax");
+                self.emit("cvttsd2si rax, xmm0");
+            }
+            _ => {}
+        }
+    }
+
+    /// Store `rax` into `result`, recording the type of temporaries.
+    fn store_result(&mut self, result: &Operand, typ: Type) {
+        match result {
+            Operand::Variable(name) => self.emit(format!("mov [v_{}], rax", name)),
+            Operand::TempVariable(name) => {
+                self.emit(format!("mov {}, rax", temp_address(name)));
+                self.temp_types.insert(name.clone(), typ);
+            }
+            Operand::ArrayElement(name, index) => {
+                self.emit("mov rdx, rax");
+                self.load_operand_as(index, &Type::Int);
+                self.emit("mov rcx, rax");
+                self.emit(format!("lea rbx, [v_{}]", name));
+                self.emit("mov [rbx + rcx * 8], rdx");
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Stack slot of a temporary: `t1` lives at `[rbp - 8]`, `t2` at `[rbp - 16]`, ...
+fn temp_address(name: &str) -> String {
+    let index: usize = name.trim_start_matches('t').parse().unwrap_or(0);
+    format!("qword [rbp - {}]", index * 8)
+}
@@ -0,0 +1,599 @@
+//! Translates a quadruple program into a textual LLVM IR module.
+//!
+//! The module mirrors the NASM backend: every variable is an `i64` global
+//! (floats keep their IEEE 754 bits, strings their address), arrays are
+//! `[N x i64]` globals and temporaries are `i64` stack slots. Everything runs
+//! in `main`, with `printf`, `fgets` and `sscanf` from libc for I/O.
+
+use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::parser::ast::Type;
+use std::collections::{BTreeSet, HashMap};
+
+/// Generate the LLVM IR module for `program`.
+pub(super) fn generate(program: &QuadrupleProgram) -> String {
+    let mut builder = Builder::default();
+    for quad in &program.quadruples {
+        if let Operation::Label {
+            id,
+            name: Some(name),
+        } = &quad.operation
+        {
+            builder.label_names.insert(*id, name.clone());
+        }
+    }
+
+    for quad in &program.quadruples {
+        builder.quad_to_ir(quad);
+    }
+    if !builder.terminated {
+        builder.emit("ret i32 0");
+    }
+
+    builder.module(program)
+}
+
+#[derive(Default)]
+struct Builder {
+    globals: Vec<String>,
+    body: Vec<String>,
+    string_literals: Vec<String>,
+    /// Functions called by the program, besides the libc ones used for I/O
+    declarations: BTreeSet<String>,
+    variable_types: HashMap<String, Type>,
+    temp_types: HashMap<String, Type>,
+    label_names: HashMap<usize, String>,
+    next_value: usize,
+    next_block: usize,
+    input_buffers: usize,
+    /// Whether the current basic block already ends with a terminator
+    terminated: bool,
+}
+
+impl Builder {
+    fn module(&self, program: &QuadrupleProgram) -> String {
+        let mut output = String::new();
+        output.push_str("; ModuleID = 'minisoft'\n");
+        output.push_str("source_filename = \"minisoft\"\n\n");
+
+        for global in &self.globals {
+            output.push_str(&format!("{}\n", global));
+        }
+        let formats = [
+            ("fmt_int", "%lld"),
+            ("fmt_float", "%.6f"),
+            ("fmt_string", "%s"),
+            ("scan_int", "%lld"),
+            ("scan_float", "%lf"),
+            ("scan_string", "%255[^\n]"),
+        ];
+        for (name, text) in formats {
+            output.push_str(&format!("@{} = {}\n", name, string_constant(text)));
+        }
+        for (index, literal) in self.string_literals.iter().enumerate() {
+            output.push_str(&format!("@str_{} = {}\n", index, string_constant(literal)));
+        }
+
+        output.push_str("@line_buffer = internal global [256 x i8] zeroinitializer\n");
+        output.push_str("@stdin = external global ptr\n");
+
+        output.push_str("\ndeclare i32 @printf(ptr, ...)\n");
+        output.push_str("declare i32 @snprintf(ptr, i64, ptr, ...)\n");
+        output.push_str("declare i32 @sscanf(ptr, ptr, ...)\n");
+        output.push_str("declare ptr @fgets(ptr, i32, ptr)\n");
+        for declaration in &self.declarations {
+            output.push_str(&format!("{}\n", declaration));
+        }
+
+        output.push_str("\ndefine i32 @main() {\nentry:\n");
+        for index in 1..=program.temp_count() {
+            output.push_str(&format!("  %t{} = alloca i64\n", index));
+        }
+        for line in &self.body {
+            if line.ends_with(':') {
+                output.push_str(&format!("{}\n", line));
+            } else {
+                output.push_str(&format!("  {}\n", line));
+            }
+        }
+        output.push_str("}\n");
+        output.push_str(PRINT_FLOAT);
+        output.push_str(READ_LINE);
+
+        output
+    }
+
+    fn emit(&mut self, instruction: impl Into<String>) {
+        // Code after a branch starts a new, unreachable block
+        if self.terminated {
+            self.next_block += 1;
+            self.body.push(format!("dead_{}:", self.next_block));
+            self.terminated = false;
+        }
+        self.body.push(instruction.into());
+    }
+
+    /// Emit `instruction` as the definition of a new SSA value and return its name.
+    fn value(&mut self, instruction: impl AsRef<str>) -> String {
+        self.next_value += 1;
+        let name = format!("%v{}", self.next_value);
+        self.emit(format!("{} = {}", name, instruction.as_ref()));
+        name
+    }
+
+    fn branch(&mut self, instruction: String) {
+        self.emit(instruction);
+        self.terminated = true;
+    }
+
+    fn quad_to_ir(&mut self, quad: &Quadruple) {
+        if !matches!(quad.operation, Operation::Label { .. }) {
+            self.emit(format!("; {}", quad));
+        }
+
+        match &quad.operation {
+            Operation::DeclareVariable(typ) => self.declare_variable(typ, quad),
+            Operation::DeclareArray(typ, size) => {
+                if let Operand::Variable(name) = &quad.result {
+                    self.variable_types.insert(name.clone(), typ.clone());
+                    self.globals.push(format!(
+                        "@v_{} = global [{} x i64] zeroinitializer",
+                        name, size
+                    ));
+                }
+            }
+            Operation::Assign => {
+                // Temporaries take the type of the value copied into them
+                let target_type = match &quad.result {
+                    Operand::TempVariable(_) => self.operand_type(&quad.operand1),
+                    result => self.operand_type(result),
+                };
+                let value = self.load(&quad.operand1, &target_type);
+                self.store(&quad.result, &value, target_type);
+            }
+            Operation::ArrayStore => {
+                if let Operand::Variable(name) = &quad.result {
+                    let element_type = self.operand_type(&quad.result);
+                    let element =
+                        Operand::ArrayElement(name.clone(), Box::new(quad.operand2.clone()));
+                    let value = self.load(&quad.operand1, &element_type);
+                    self.store(&element, &value, element_type);
+                }
+            }
+            Operation::ArrayLoad => {
+                if let Operand::Variable(name) = &quad.operand1 {
+                    let element_type = self.operand_type(&quad.operand1);
+                    let element =
+                        Operand::ArrayElement(name.clone(), Box::new(quad.operand2.clone()));
+                    let value = self.load(&element, &element_type);
+                    self.store(&quad.result, &value, element_type);
+                }
+            }
+            Operation::MemCopy(size) => {
+                if let (Operand::Variable(source), Operand::Variable(target)) =
+                    (&quad.operand1, &quad.result)
+                {
+                    self.declarations.insert(
+                        "declare void @llvm.memcpy.p0.p0.i64(ptr, ptr, i64, i1)".to_string(),
+                    );
+                    self.emit(format!(
+                        "call void @llvm.memcpy.p0.p0.i64(ptr @v_{}, ptr @v_{}, i64 {}, i1 false)",
+                        target,
+                        source,
+                        size * 8
+                    ));
+                }
+            }
+            Operation::Add | Operation::Subtract | Operation::Multiply | Operation::Divide => {
+                let value_type = self.common_type(&quad.operand1, &quad.operand2);
+                let left = self.load(&quad.operand1, &value_type);
+                let right = self.load(&quad.operand2, &value_type);
+                let instruction = match (&quad.operation, &value_type) {
+                    (Operation::Add, Type::Float) => "fadd double",
+                    (Operation::Subtract, Type::Float) => "fsub double",
+                    (Operation::Multiply, Type::Float) => "fmul double",
+                    (_, Type::Float) => "fdiv double",
+                    (Operation::Add, _) => "add i64",
+                    (Operation::Subtract, _) => "sub i64",
+                    (Operation::Multiply, _) => "mul i64",
+                    _ => "sdiv i64",
+                };
+                let result = self.value(format!("{} {}, {}", instruction, left, right));
+                self.store(&quad.result, &result, value_type);
+            }
+            Operation::Modulo => {
+                let left = self.load(&quad.operand1, &Type::Int);
+                let right = self.load(&quad.operand2, &Type::Int);
+                let result = self.value(format!("srem i64 {}, {}", left, right));
+                self.store(&quad.result, &result, Type::Int);
+            }
+            Operation::Negate => {
+                let value_type = self.operand_type(&quad.operand1);
+                let value = self.load(&quad.operand1, &value_type);
+                let result = if value_type == Type::Float {
+                    self.value(format!("fneg double {}", value))
+                } else {
+                    self.value(format!("sub i64 0, {}", value))
+                };
+                self.store(&quad.result, &result, value_type);
+            }
+            Operation::Equal
+            | Operation::NotEqual
+            | Operation::LessThan
+            | Operation::GreaterThan
+            | Operation::LessEqual
+            | Operation::GreaterEqual => {
+                let value_type = self.common_type(&quad.operand1, &quad.operand2);
+                let left = self.load(&quad.operand1, &value_type);
+                let right = self.load(&quad.operand2, &value_type);
+                // Float predicates match the flags ucomisd leaves for the NASM backend
+                let predicate = match (&quad.operation, &value_type) {
+                    (Operation::Equal, Type::Float) => "fcmp ueq double",
+                    (Operation::NotEqual, Type::Float) => "fcmp one double",
+                    (Operation::LessThan, Type::Float) => "fcmp ult double",
+                    (Operation::GreaterThan, Type::Float) => "fcmp ogt double",
+                    (Operation::LessEqual, Type::Float) => "fcmp ule double",
+                    (_, Type::Float) => "fcmp oge double",
+                    (Operation::Equal, _) => "icmp eq i64",
+                    (Operation::NotEqual, _) => "icmp ne i64",
+                    (Operation::LessThan, _) => "icmp slt i64",
+                    (Operation::GreaterThan, _) => "icmp sgt i64",
+                    (Operation::LessEqual, _) => "icmp sle i64",
+                    _ => "icmp sge i64",
+                };
+                let condition = self.value(format!("{} {}, {}", predicate, left, right));
+                let result = self.value(format!("zext i1 {} to i64", condition));
+                self.store(&quad.result, &result, Type::Int);
+            }
+            Operation::And | Operation::Or => {
+                let left = self.truth(&quad.operand1);
+                let right = self.truth(&quad.operand2);
+                let instruction = if quad.operation == Operation::And {
+                    "and"
+                } else {
+                    "or"
+                };
+                let condition = self.value(format!("{} i1 {}, {}", instruction, left, right));
+                let result = self.value(format!("zext i1 {} to i64", condition));
+                self.store(&quad.result, &result, Type::Int);
+            }
+            Operation::Not => {
+                let value = self.load(&quad.operand1, &Type::Int);
+                let condition = self.value(format!("icmp eq i64 {}, 0", value));
+                let result = self.value(format!("zext i1 {} to i64", condition));
+                self.store(&quad.result, &result, Type::Int);
+            }
+            Operation::Label { id, .. } => {
+                let label = self.label(*id);
+                if !self.terminated {
+                    self.body.push(format!("br label %{}", label));
+                }
+                self.body.push(format!("{}:", label));
+                self.terminated = false;
+            }
+            Operation::Jump { id, .. } => {
+                let label = self.label(*id);
+                self.branch(format!("br label %{}", label));
+            }
+            Operation::JumpIfTrue { id, .. } | Operation::JumpIfFalse { id, .. } => {
+                let condition = self.truth(&quad.operand1);
+                let label = self.label(*id);
+                self.next_block += 1;
+                let next = format!("next_{}", self.next_block);
+                let (taken, fallthrough) = match quad.operation {
+                    Operation::JumpIfTrue { .. } => (&label, &next),
+                    _ => (&next, &label),
+                };
+                self.branch(format!(
+                    "br i1 {}, label %{}, label %{}",
+                    condition, taken, fallthrough
+                ));
+                self.body.push(format!("{}:", next));
+                self.terminated = false;
+            }
+            Operation::Input => {
+                // Like the NASM helpers, every input reads one whole line
+                let target_type = self.operand_type(&quad.result);
+                let line = self.value("call ptr @read_line()");
+                match target_type {
+                    Type::String => {
+                        let buffer = format!("@input_{}", self.input_buffers);
+                        self.input_buffers += 1;
+                        self.globals
+                            .push(format!("{} = global [256 x i8] zeroinitializer", buffer));
+                        self.emit(format!("store i8 0, ptr {}", buffer));
+                        self.value(format!(
+                            "call i32 (ptr, ptr, ...) @sscanf(ptr {}, ptr @scan_string, ptr {})",
+                            line, buffer
+                        ));
+                        let address = self.value(format!("ptrtoint ptr {} to i64", buffer));
+                        self.store(&quad.result, &address, Type::String);
+                    }
+                    _ => {
+                        // sscanf writes the i64 or double straight into the slot,
+                        // which stays 0 when the line holds no number
+                        let format = match target_type {
+                            Type::Float => "@scan_float",
+                            _ => "@scan_int",
+                        };
+                        self.store(&quad.result, "0", Type::Int);
+                        if let Operand::TempVariable(name) = &quad.result {
+                            self.temp_types.insert(name.clone(), target_type);
+                        }
+                        let address = self.address(&quad.result);
+                        self.value(format!(
+                            "call i32 (ptr, ptr, ...) @sscanf(ptr {}, ptr {}, ptr {})",
+                            line, format, address
+                        ));
+                    }
+                }
+            }
+            Operation::Output => {
+                let value_type = self.operand_type(&quad.operand1);
+                let value = self.load(&quad.operand1, &value_type);
+                let arguments = match value_type {
+                    Type::Int => format!("ptr @fmt_int, i64 {}", value),
+                    Type::Float => {
+                        self.emit(format!("call void @print_float(double {})", value));
+                        return;
+                    }
+                    Type::String => {
+                        let pointer = self.value(format!("inttoptr i64 {} to ptr", value));
+                        format!("ptr @fmt_string, ptr {}", pointer)
+                    }
+                };
+                self.value(format!("call i32 (ptr, ...) @printf({})", arguments));
+            }
+            Operation::Call(name) => {
+                self.declarations.insert(format!("declare i64 @{}()", name));
+                let result = self.value(format!("call i64 @{}()", name));
+                if quad.result != Operand::Empty {
+                    self.store(&quad.result, &result, Type::Int);
+                }
+            }
+            Operation::Return => {
+                let value = self.load(&quad.operand1, &Type::Int);
+                let code = self.value(format!("trunc i64 {} to i32", value));
+                self.branch(format!("ret i32 {}", code));
+            }
+            // LLVM IR has no instruction that does nothing
+            Operation::Nop => {}
+            Operation::InlineAsm(lines) => {
+                let text = lines
+                    .iter()
+                    .map(|line| line.replace('$', "$$"))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                self.emit(format!(
+                    "call void asm sideeffect inteldialect \"{}\", \"\"()",
+                    escape(&text)
+                ));
+            }
+            Operation::Phi(_) => panic!(
+                "phi-node reached the assembly generator: call QuadrupleProgram::from_ssa_form() first"
+            ),
+        }
+    }
+
+    fn declare_variable(&mut self, typ: &Type, quad: &Quadruple) {
+        let Operand::Variable(name) = &quad.result else {
+            return;
+        };
+        self.variable_types.insert(name.clone(), typ.clone());
+
+        // Literal initializers become the global's initial value
+        let initial = match (&quad.operand1, typ) {
+            (Operand::Empty, _) => Some("0".to_string()),
+            (Operand::IntLiteral(value), Type::Float) => Some(double_bits(*value as f64)),
+            (Operand::IntLiteral(value), _) => Some(value.to_string()),
+            (Operand::FloatLiteral(value), Type::Float) => Some(double_bits(*value)),
+            (Operand::FloatLiteral(value), _) => Some((*value as i64).to_string()),
+            (Operand::StringLiteral(value), Type::String) => Some(format!(
+                "ptrtoint (ptr @str_{} to i64)",
+                self.string_label(value)
+            )),
+            _ => None,
+        };
+
+        match initial {
+            Some(value) => self
+                .globals
+                .push(format!("@v_{} = global i64 {}", name, value)),
+            None => {
+                self.globals.push(format!("@v_{} = global i64 0", name));
+                let value = self.load(&quad.operand1, typ);
+                self.store(&quad.result, &value, typ.clone());
+            }
+        }
+    }
+
+    /// Load `operand` as an `i64` (Int, String) or a `double` (Float) value.
+    fn load(&mut self, operand: &Operand, target: &Type) -> String {
+        let source = self.operand_type(operand);
+
+        let raw = match operand {
+            Operand::IntLiteral(value) => {
+                return match target {
+                    Type::Float => double(*value as f64),
+                    _ => value.to_string(),
+                };
+            }
+            Operand::FloatLiteral(value) => {
+                return match target {
+                    Type::Float => double(*value),
+                    _ => (*value as i64).to_string(),
+                };
+            }
+            Operand::StringLiteral(value) => {
+                let label = self.string_label(value);
+                return self.value(format!("ptrtoint ptr @str_{} to i64", label));
+            }
+            Operand::Empty => return "0".to_string(),
+            Operand::Variable(_) | Operand::TempVariable(_) | Operand::ArrayElement(..) => {
+                let address = self.address(operand);
+                self.value(format!("load i64, ptr {}", address))
+            }
+        };
+
+        match (source, target) {
+            (Type::Float, Type::Float) => self.value(format!("bitcast i64 {} to double", raw)),
+            (Type::Float, _) => {
+                let value = self.value(format!("bitcast i64 {} to double", raw));
+                self.value(format!("fptosi double {} to i64", value))
+            }
+            (Type::Int, Type::Float) => self.value(format!("sitofp i64 {} to double", raw)),
+            _ => raw,
+        }
+    }
+
+    /// Whether `operand` is non-zero, as an `i1`.
+    fn truth(&mut self, operand: &Operand) -> String {
+        let value = self.load(operand, &Type::Int);
+        self.value(format!("icmp ne i64 {}, 0", value))
+    }
+
+    /// Store `value` of type `typ` into `result`, recording the type of temporaries.
+    fn store(&mut self, result: &Operand, value: &str, typ: Type) {
+        let value = if typ == Type::Float {
+            self.value(format!("bitcast double {} to i64", value))
+        } else {
+            value.to_string()
+        };
+        if let Operand::TempVariable(name) = result {
+            self.temp_types.insert(name.clone(), typ);
+        }
+        if matches!(
+            result,
+            Operand::Variable(_) | Operand::TempVariable(_) | Operand::ArrayElement(..)
+        ) {
+            let address = self.address(result);
+            self.emit(format!("store i64 {}, ptr {}", value, address));
+        }
+    }
+
+    /// Pointer to the `i64` slot of a variable, temporary or array element.
+    fn address(&mut self, operand: &Operand) -> String {
+        match operand {
+            Operand::Variable(name) => format!("@v_{}", name),
+            Operand::TempVariable(name) => format!("%{}", name),
+            Operand::ArrayElement(name, index) => {
+                let index = self.load(index, &Type::Int);
+                self.value(format!("getelementptr i64, ptr @v_{}, i64 {}", name, index))
+            }
+            _ => unreachable!("{:?} has no address", operand),
+        }
+    }
+
+    fn operand_type(&self, operand: &Operand) -> Type {
+        match operand {
+            Operand::IntLiteral(_) | Operand::Empty => Type::Int,
+            Operand::FloatLiteral(_) => Type::Float,
+            Operand::StringLiteral(_) => Type::String,
+            Operand::Variable(name) | Operand::ArrayElement(name, _) => {
+                self.variable_types.get(name).cloned().unwrap_or_default()
+            }
+            Operand::TempVariable(name) => self.temp_types.get(name).cloned().unwrap_or_default(),
+        }
+    }
+
+    fn common_type(&self, left: &Operand, right: &Operand) -> Type {
+        if self.operand_type(left) == Type::Float || self.operand_type(right) == Type::Float {
+            Type::Float
+        } else {
+            Type::Int
+        }
+    }
+
+    fn label(&self, id: usize) -> String {
+        match self.label_names.get(&id) {
+            Some(name) => format!("{}_L{}", name, id),
+            None => format!("L{}", id),
+        }
+    }
+
+    fn string_label(&mut self, value: &str) -> usize {
+        match self.string_literals.iter().position(|s| s == value) {
+            Some(index) => index,
+            None => {
+                self.string_literals.push(value.to_string());
+                self.string_literals.len() - 1
+            }
+        }
+    }
+}
+
+/// Prints a double like the NASM `print_float`: rounded to six decimals,
+/// without trailing zeros but keeping at least one decimal.
+const PRINT_FLOAT: &str = r#"
+define internal void @print_float(double %value) {
+entry:
+  %buffer = alloca [512 x i8]
+  %written = call i32 (ptr, i64, ptr, ...) @snprintf(ptr %buffer, i64 512, ptr @fmt_float, double %value)
+  %length = sext i32 %written to i64
+  br label %trim
+
+trim:
+  %end = phi i64 [ %length, %entry ], [ %shorter, %drop ]
+  %last = sub i64 %end, 1
+  %last_pointer = getelementptr i8, ptr %buffer, i64 %last
+  %last_char = load i8, ptr %last_pointer
+  %is_zero = icmp eq i8 %last_char, 48
+  %before = sub i64 %end, 2
+  %before_pointer = getelementptr i8, ptr %buffer, i64 %before
+  %before_char = load i8, ptr %before_pointer
+  %after_decimal = icmp ne i8 %before_char, 46
+  %can_drop = and i1 %is_zero, %after_decimal
+  br i1 %can_drop, label %drop, label %print
+
+drop:
+  %shorter = sub i64 %end, 1
+  br label %trim
+
+print:
+  %end_pointer = getelementptr i8, ptr %buffer, i64 %end
+  store i8 0, ptr %end_pointer
+  %printed = call i32 (ptr, ...) @printf(ptr @fmt_string, ptr %buffer)
+  ret void
+}
+"#;
+
+/// Reads one line from stdin into `@line_buffer`, which is empty at the end of input.
+const READ_LINE: &str = r#"
+define internal ptr @read_line() {
+entry:
+  store i8 0, ptr @line_buffer
+  %stream = load ptr, ptr @stdin
+  %line = call ptr @fgets(ptr @line_buffer, i32 256, ptr %stream)
+  ret ptr @line_buffer
+}
+"#;
+
+/// The bits of a double as an `i64` constant, to initialize a global slot.
+fn double_bits(value: f64) -> String {
+    (value.to_bits() as i64).to_string()
+}
+
+/// A double constant in the hexadecimal form LLVM reads back exactly.
+fn double(value: f64) -> String {
+    format!("0x{:016X}", value.to_bits())
+}
+
+/// `value` as a NUL-terminated private constant.
+fn string_constant(value: &str) -> String {
+    format!(
+        "private unnamed_addr constant [{} x i8] c\"{}\\00\"",
+        value.len() + 1,
+        escape(value)
+    )
+}
+
+/// Escape `value` for an LLVM string literal.
+fn escape(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'"' | b'\\' => format!("\\{:02X}", byte),
+            0x20..0x7f => (byte as char).to_string(),
+            _ => format!("\\{:02X}", byte),
+        })
+        .collect()
+}
@@ -0,0 +1,248 @@
+mod gas;
+mod instructions;
+mod llvm_ir;
+mod runtime;
+
+use crate::codegen::quadruple::{Operation, QuadrupleProgram};
+use crate::parser::ast::Type;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Output language of [`AssemblyGenerator`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Target {
+    /// x86-64 NASM assembly
+    #[default]
+    Nasm,
+    /// The same program for the GNU assembler, in `.intel_syntax noprefix`
+    Gas,
+    /// A textual LLVM IR module with a `main` function that calls libc for I/O
+    LlvmIr,
+}
+
+impl Target {
+    /// File extension used for output files of this target
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Target::Nasm => "asm",
+            Target::Gas => "s",
+            Target::LlvmIr => "ll",
+        }
+    }
+}
+
+/// Translates a quadruple program into x86-64 NASM assembly for Linux.
+///
+/// User variables live in `.data`/`.bss` under a `v_` prefix so they never
+/// collide with register names or NASM keywords. Temporaries live in the
+/// stack frame set up by `_start` at `[rbp - 8 * n]`. Every value is 8 bytes
+/// wide: integers are plain 64-bit words, floats are IEEE 754 doubles and
+/// strings are pointers to NUL-terminated bytes. [`Target`] selects GNU
+/// assembler syntax or LLVM IR instead.
+///
+/// The `DECL_*` quadruples are the source of truth for the data section and
+/// for variable types. Every variable is declared by one before it is used,
+/// so no symbol table is needed, and programs built without a source file
+/// compile the same way.
+pub struct AssemblyGenerator {
+    program: QuadrupleProgram,
+    data_section: Vec<String>,
+    bss_section: Vec<String>,
+    instructions: Vec<String>,
+    string_literals: Vec<String>,
+    /// Number of `input_N` buffers reserved for reading strings
+    input_buffers: usize,
+    /// Type of each variable, from its `DECL_*` quadruple
+    variable_types: HashMap<String, Type>,
+    temp_types: HashMap<String, Type>,
+    profile: bool,
+    /// Names of the profiled blocks, indexed like `prof_cycles`
+    profile_blocks: Vec<String>,
+    /// Source line of the last `%line` directive
+    current_line: Option<usize>,
+    /// Names given to labels, by label id
+    label_names: HashMap<usize, String>,
+    target: Target,
+}
+
+impl AssemblyGenerator {
+    pub fn new(program: QuadrupleProgram) -> Self {
+        AssemblyGenerator {
+            program,
+            data_section: Vec::new(),
+            bss_section: Vec::new(),
+            instructions: Vec::new(),
+            string_literals: Vec::new(),
+            input_buffers: 0,
+            variable_types: HashMap::new(),
+            temp_types: HashMap::new(),
+            profile: false,
+            profile_blocks: Vec::new(),
+            current_line: None,
+            label_names: HashMap::new(),
+            target: Target::default(),
+        }
+    }
+
+    /// Count the cycles spent in each labeled block with `rdtsc` and print
+    /// them to stderr when the program exits. [`Target::LlvmIr`] ignores it.
+    pub fn set_profile(&mut self, profile: bool) {
+        self.profile = profile;
+    }
+
+    /// Generate code for `target` instead of NASM.
+    pub fn set_target(&mut self, target: Target) {
+        self.target = target;
+    }
+
+    /// Generate the assembly and write it to `output_path`.
+    pub fn generate(&mut self, output_path: &Path) -> io::Result<()> {
+        let assembly = self.generate_to_string();
+        fs::write(output_path, assembly)
+    }
+
+    /// Generate the full assembly text without touching the filesystem.
+    pub fn generate_to_string(&mut self) -> String {
+        match self.target {
+            Target::Nasm => self.generate_nasm(),
+            Target::Gas => gas::from_nasm(&self.generate_nasm()),
+            Target::LlvmIr => llvm_ir::generate(&self.program),
+        }
+    }
+
+    fn generate_nasm(&mut self) -> String {
+        self.data_section.clear();
+        self.bss_section.clear();
+        self.instructions.clear();
+        self.string_literals.clear();
+        self.input_buffers = 0;
+        self.variable_types.clear();
+        self.temp_types.clear();
+        self.profile_blocks.clear();
+        self.current_line = None;
+        self.label_names = self
+            .program
+            .quadruples
+            .iter()
+            .filter_map(|quad| match &quad.operation {
+                Operation::Label {
+                    id,
+                    name: Some(name),
+                } => Some((*id, name.clone())),
+                _ => None,
+            })
+            .collect();
+
+        if self.profile {
+            // Code before the first label is charged to the "entry" block
+            self.profile_blocks.push("entry".to_string());
+            self.instructions.extend(
+                [
+                    "rdtsc",
+                    "shl rdx, 32",
+                    "or rax, rdx",
+                    "mov [prof_start], rax",
+                ]
+                .map(String::from),
+            );
+        }
+
+        let quadruples = self.program.quadruples.clone();
+        for quad in &quadruples {
+            self.quad_to_instructions(quad);
+        }
+
+        if self.profile {
+            self.instructions.extend(
+                [
+                    "mov rdi, 0",
+                    "call profile_enter",
+                    "call profile_report",
+                ]
+                .map(String::from),
+            );
+            self.profile_data();
+        }
+
+        let mut output = String::new();
+
+        // File header: 64-bit mode, RIP-relative addressing for every label
+        // reference (so the output also links as a PIE) and the entry point
+        output.push_str("bits 64\n");
+        output.push_str("default rel\n");
+        output.push_str("global _start\n\n");
+
+        output.push_str("section .data\n");
+        for line in &self.data_section {
+            output.push_str(&format!("    {}\n", line));
+        }
+        for (index, literal) in self.string_literals.iter().enumerate() {
+            output.push_str(&format!(
+                "    str_{}: db {}\n",
+                index,
+                runtime::nasm_string_bytes(literal)
+            ));
+        }
+
+        output.push_str("\nsection .bss\n");
+        for line in &self.bss_section {
+            output.push_str(&format!("    {}\n", line));
+        }
+
+        output.push_str("\nsection .text\n");
+        output.push_str("_start:\n");
+        output.push_str("    push rbp\n");
+        output.push_str("    mov rbp, rsp\n");
+        let frame_size = self.frame_size();
+        if frame_size > 0 {
+            output.push_str(&format!("    sub rsp, {}\n", frame_size));
+        }
+
+        for line in &self.instructions {
+            if line.ends_with(':') {
+                output.push_str(&format!("{}\n", line));
+            } else {
+                output.push_str(&format!("    {}\n", line));
+            }
+        }
+
+        // There is no caller to return to, so end the process with exit(0)
+        output.push_str("    mov eax, 60\n");
+        output.push_str("    xor edi, edi\n");
+        output.push_str("    syscall\n");
+
+        output.push_str(runtime::utility_functions());
+        if self.profile {
+            output.push_str(runtime::profiling_functions());
+        }
+
+        output
+    }
+
+    /// Counters and the name table read by `profile_report`.
+    fn profile_data(&mut self) {
+        let names: Vec<String> = self
+            .profile_blocks
+            .clone()
+            .iter()
+            .map(|name| format!("str_{}", self.string_label(&format!("{}: ", name))))
+            .collect();
+        self.data_section.push("prof_start: dq 0".to_string());
+        self.data_section.push("prof_current: dq 0".to_string());
+        self.data_section
+            .push(format!("prof_count: dq {}", self.profile_blocks.len()));
+        self.data_section
+            .push(format!("prof_names: dq {}", names.join(", ")));
+        self.bss_section
+            .push(format!("prof_cycles: resq {}", self.profile_blocks.len()));
+    }
+
+    /// Stack space for all temporaries. `_start` pushes `rbp` first, so the
+    /// frame is 8 bytes past a multiple of 16 to keep `rsp` 16-byte aligned
+    /// at every call, as the System V ABI requires.
+    fn frame_size(&self) -> usize {
+        (self.program.temp_count() * 8 + 8).div_ceil(16) * 16 - 8
+    }
+}
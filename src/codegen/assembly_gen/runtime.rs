@@ -0,0 +1,364 @@
+/// Bytes reserved for each `input` into a String, including the final NUL
+pub(super) const STRING_BUFFER_SIZE: usize = 256;
+
+/// Runtime helpers appended after the program body.
+///
+/// The helpers follow the System V AMD64 calling convention: integer and
+/// pointer arguments come in `rdi` (then `rsi`), doubles in `xmm0`, and
+/// results go back in `rax`, or `xmm0` for `read_float`. They only clobber
+/// caller-saved registers. I/O goes straight through Linux syscalls, so the
+/// output does not depend on libc.
+pub(super) fn utility_functions() -> &'static str {
+    r#"
+; print_int(value): write a signed 64-bit integer to stdout
+print_int:
+    mov rsi, 1
+    jmp write_int
+
+; write_int(value, fd): write a signed 64-bit integer to a file descriptor
+write_int:
+    push rbp
+    mov rbp, rsp
+    sub rsp, 32
+    mov rax, rdi
+    mov r9, rsi
+    lea rsi, [rbp - 1]
+    xor r8, r8
+    test rax, rax
+    jns .convert
+    neg rax
+    mov r8, 1
+.convert:
+    mov rcx, 10
+.digit:
+    xor rdx, rdx
+    div rcx
+    add dl, '0'
+    mov [rsi], dl
+    dec rsi
+    test rax, rax
+    jnz .digit
+    test r8, r8
+    jz .write
+    mov byte [rsi], '-'
+    dec rsi
+.write:
+    inc rsi
+    mov rdx, rbp
+    sub rdx, rsi
+    mov rax, 1
+    mov rdi, r9
+    syscall
+    mov rsp, rbp
+    pop rbp
+    ret
+
+; print_float(value): write a double to stdout with up to six decimals,
+; keeping at least one so that floats always show a decimal point
+print_float:
+    push rbp
+    mov rbp, rsp
+    sub rsp, 48
+    movq [rbp - 40], xmm0
+    movq rax, xmm0
+    test rax, rax
+    jns .split
+    mov byte [rbp - 1], '-'
+    mov rax, 1
+    mov rdi, 1
+    lea rsi, [rbp - 1]
+    mov rdx, 1
+    syscall
+    mov rax, 0x7fffffffffffffff
+    movq xmm1, rax
+    movq xmm0, [rbp - 40]
+    andpd xmm0, xmm1
+.split:
+    cvttsd2si rax, xmm0
+    cvtsi2sd xmm1, rax
+    subsd xmm0, xmm1
+    mov rcx, 1000000
+    cvtsi2sd xmm1, rcx
+    mulsd xmm0, xmm1
+    cvtsd2si rdx, xmm0
+    cmp rdx, rcx
+    jl .integer
+    inc rax
+    sub rdx, rcx
+.integer:
+    mov [rbp - 16], rdx
+    mov rdi, rax
+    call print_int
+    mov byte [rbp - 32], '.'
+    mov rax, [rbp - 16]
+    lea rsi, [rbp - 26]
+    mov rcx, 10
+    mov r8, 6
+.fraction:
+    xor rdx, rdx
+    div rcx
+    add dl, '0'
+    mov [rsi], dl
+    dec rsi
+    dec r8
+    jnz .fraction
+    lea rdx, [rbp - 26]
+    lea rax, [rbp - 31]
+.trim:
+    cmp rdx, rax
+    je .write
+    cmp byte [rdx], '0'
+    jne .write
+    dec rdx
+    jmp .trim
+.write:
+    lea rsi, [rbp - 32]
+    sub rdx, rsi
+    inc rdx
+    mov rax, 1
+    mov rdi, 1
+    syscall
+    mov rsp, rbp
+    pop rbp
+    ret
+
+; print_string(pointer): write a NUL-terminated string to stdout
+print_string:
+    push rbp
+    mov rbp, rsp
+    mov rsi, rdi
+    test rsi, rsi
+    jz .done
+    xor rdx, rdx
+.length:
+    cmp byte [rsi + rdx], 0
+    je .write
+    inc rdx
+    jmp .length
+.write:
+    mov rax, 1
+    mov rdi, 1
+    syscall
+.done:
+    pop rbp
+    ret
+
+; read_int(): read one line from stdin and parse it as a signed integer
+read_int:
+    push rbp
+    mov rbp, rsp
+    sub rsp, 32
+    mov qword [rbp - 8], 0
+    mov qword [rbp - 16], 0
+.next:
+    mov rax, 0
+    mov rdi, 0
+    lea rsi, [rbp - 24]
+    mov rdx, 1
+    syscall
+    cmp rax, 1
+    jne .finish
+    movzx rcx, byte [rbp - 24]
+    cmp rcx, 10
+    je .finish
+    cmp rcx, '-'
+    jne .digit
+    mov qword [rbp - 16], 1
+    jmp .next
+.digit:
+    sub rcx, '0'
+    cmp rcx, 9
+    ja .next
+    mov rax, [rbp - 8]
+    imul rax, rax, 10
+    add rax, rcx
+    mov [rbp - 8], rax
+    jmp .next
+.finish:
+    mov rax, [rbp - 8]
+    cmp qword [rbp - 16], 0
+    je .done
+    neg rax
+.done:
+    mov rsp, rbp
+    pop rbp
+    ret
+
+; read_string(buffer): read one line from stdin into buffer as a
+; NUL-terminated string of at most 255 bytes, and return buffer
+read_string:
+    push rbp
+    mov rbp, rsp
+    mov r9, rdi
+    xor r8, r8
+.next:
+    cmp r8, 255
+    jae .finish
+    mov rax, 0
+    mov rdi, 0
+    mov rsi, r9
+    add rsi, r8
+    mov rdx, 1
+    syscall
+    cmp rax, 1
+    jne .finish
+    cmp byte [r9 + r8], 10
+    je .finish
+    inc r8
+    jmp .next
+.finish:
+    mov rax, r9
+    mov byte [rax + r8], 0
+    pop rbp
+    ret
+
+; read_float(): read one line from stdin and parse it as a double, e.g.
+; "-12.75". All digits go into one integer, which is then divided by
+; 10^(digits after the point) so the result is rounded only once.
+read_float:
+    push rbp
+    mov rbp, rsp
+    sub rsp, 32
+    mov qword [rbp - 8], 0
+    mov qword [rbp - 16], 0
+    mov qword [rbp - 32], -1
+.next:
+    mov rax, 0
+    mov rdi, 0
+    lea rsi, [rbp - 24]
+    mov rdx, 1
+    syscall
+    cmp rax, 1
+    jne .finish
+    movzx rcx, byte [rbp - 24]
+    cmp rcx, 10
+    je .finish
+    cmp rcx, '-'
+    jne .point
+    mov qword [rbp - 16], 1
+    jmp .next
+.point:
+    cmp rcx, '.'
+    jne .digit
+    mov qword [rbp - 32], 0
+    jmp .next
+.digit:
+    sub rcx, '0'
+    cmp rcx, 9
+    ja .next
+    mov rax, [rbp - 8]
+    imul rax, rax, 10
+    add rax, rcx
+    mov [rbp - 8], rax
+    cmp qword [rbp - 32], 0
+    jl .next
+    inc qword [rbp - 32]
+    jmp .next
+.finish:
+    mov rax, [rbp - 8]
+    cvtsi2sd xmm0, rax
+    mov rax, 0x3FF0000000000000
+    movq xmm1, rax
+    mov rax, 0x4024000000000000
+    movq xmm2, rax
+    mov rcx, [rbp - 32]
+.scale:
+    cmp rcx, 0
+    jle .divide
+    mulsd xmm1, xmm2
+    dec rcx
+    jmp .scale
+.divide:
+    divsd xmm0, xmm1
+    movq rax, xmm0
+    cmp qword [rbp - 16], 0
+    je .done
+    btc rax, 63
+.done:
+    movq xmm0, rax
+    mov rsp, rbp
+    pop rbp
+    ret
+"#
+}
+
+/// Helpers for `--profile`, which expect `prof_start`, `prof_current`,
+/// `prof_cycles`, `prof_names` and `prof_count` in the data sections.
+pub(super) fn profiling_functions() -> &'static str {
+    r#"
+; profile_enter(block): charge the cycles since the last call to the
+; current block, then make `block` the current one
+profile_enter:
+    rdtsc
+    shl rdx, 32
+    or rax, rdx
+    mov rcx, rax
+    sub rax, [prof_start]
+    mov rdx, [prof_current]
+    lea rsi, [prof_cycles]
+    add [rsi + rdx * 8], rax
+    mov [prof_start], rcx
+    mov [prof_current], rdi
+    ret
+
+; profile_report(): write "<block>: <cycles>" for every block to stderr
+profile_report:
+    push r12
+    xor r12, r12
+.block:
+    cmp r12, [prof_count]
+    jge .done
+    lea rsi, [prof_names]
+    mov rsi, [rsi + r12 * 8]
+    xor rdx, rdx
+.length:
+    cmp byte [rsi + rdx], 0
+    je .write
+    inc rdx
+    jmp .length
+.write:
+    mov rax, 1
+    mov rdi, 2
+    syscall
+    lea rsi, [prof_cycles]
+    mov rdi, [rsi + r12 * 8]
+    mov rsi, 2
+    call write_int
+    push 10
+    mov rax, 1
+    mov rdi, 2
+    mov rsi, rsp
+    mov rdx, 1
+    syscall
+    add rsp, 8
+    inc r12
+    jmp .block
+.done:
+    pop r12
+    ret
+"#
+}
+
+/// Render `value` as the operand list of a NASM `db`, NUL-terminated.
+pub(super) fn nasm_string_bytes(value: &str) -> String {
+    let mut parts = Vec::new();
+    let mut run = String::new();
+
+    for byte in value.bytes() {
+        if (0x20..0x7f).contains(&byte) && byte != b'"' {
+            run.push(byte as char);
+        } else {
+            if !run.is_empty() {
+                parts.push(format!("\"{}\"", run));
+                run.clear();
+            }
+            parts.push(byte.to_string());
+        }
+    }
+    if !run.is_empty() {
+        parts.push(format!("\"{}\"", run));
+    }
+    parts.push("0".to_string());
+
+    parts.join(", ")
+}
@@ -1,31 +1,209 @@
+use std::collections::HashMap;
+
+use crate::codegen::optimizer::fold_binary_op;
 use crate::codegen::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::semantics::source_map::SourceMap;
 use crate::parser::ast::{
-    Expression, ExpressionKind, LiteralKind, Operator, Program, Statement, StatementKind,
-    UnaryOperator,
+    Declaration, DeclarationKind, Expression, ExpressionKind, FormatSegment, LiteralKind,
+    Operator, Program, Span, Statement, StatementKind, UnaryOperator, format_segments,
 };
 
 pub struct CodeGenerator {
     pub program: QuadrupleProgram,
+    /// Declared arrays and their sizes, for whole-array assignments
+    array_sizes: HashMap<String, usize>,
+    /// Source of the program, to tag quadruples with their line
+    source_map: Option<SourceMap>,
+    /// Passes applied by `QuadrupleProgram::optimize` once generation is done
+    optimization_level: u8,
+    /// Exit label of each enclosing loop, innermost last, for `break`
+    break_labels: Vec<LoopLabel>,
+    /// Label before the step (for) or condition (do-while) of each enclosing
+    /// loop, innermost last, for `continue`
+    continue_labels: Vec<LoopLabel>,
 }
 
+/// Label id and name for `break` or `continue`. The id is only allocated once
+/// a statement jumps to it, so loops without one generate the same code as before.
+type LoopLabel = (Option<usize>, &'static str);
+
 impl CodeGenerator {
     pub fn new() -> Self {
         CodeGenerator {
             program: QuadrupleProgram::new(),
+            array_sizes: HashMap::new(),
+            source_map: None,
+            optimization_level: 0,
+            break_labels: Vec::new(),
+            continue_labels: Vec::new(),
+        }
+    }
+
+    /// Generator that optimizes the quadruples at `level` (0 for none)
+    pub fn new_with_optimization(level: u8) -> Self {
+        CodeGenerator {
+            optimization_level: level,
+            ..Self::new()
         }
     }
 
+    /// Tag every generated quadruple with its line in `source`
+    pub fn with_source(mut self, source: &str) -> Self {
+        self.source_map = Some(SourceMap::new(source));
+        self
+    }
+
+    /// Translate a program that passed semantic analysis into quadruples.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_compiler::codegen::Operation;
+    /// use rust_compiler::codegen::generator::CodeGenerator;
+    /// use rust_compiler::parser::parser_core::parse_source;
+    ///
+    /// let program = parse_source("MainPrgm demo; Var let x: Int; BeginPg { x := x + 2; } EndPg;")
+    ///     .unwrap();
+    /// let quadruples = CodeGenerator::new().generate_code(&program).unwrap();
+    /// assert!(quadruples.quadruples.iter().any(|quad| quad.operation == Operation::Add));
+    /// println!("{}", quadruples.dump_annotated());
+    /// ```
     pub fn generate_code(&mut self, ast: &Program) -> Option<QuadrupleProgram> {
+        // A generator can be reused: each program starts again from t1 and L1
+        self.program.reset();
+        self.array_sizes.clear();
+
+        // Declarations come first so storage exists before any statement uses it
+        for declaration in &ast.declarations {
+            self.generate_declaration(declaration);
+        }
+
         // Process each statement in the program
         for statement in &ast.statements {
             self.generate_statement(statement);
         }
+        self.program.optimize(self.optimization_level);
         Some(self.program.clone())
     }
 
-    fn generate_statement(&mut self, statement: &Statement) {
+    pub(crate) fn generate_declaration(&mut self, declaration: &Declaration) {
+        let first = self.program.quadruples.len();
+        self.generate_declaration_kind(declaration);
+        self.tag_source_location(first, &declaration.span);
+    }
+
+    pub(crate) fn generate_statement(&mut self, statement: &Statement) {
+        let first = self.program.quadruples.len();
+        self.generate_statement_kind(statement);
+        self.tag_source_location(first, &statement.span);
+    }
+
+    /// Tag the quadruples generated since `first` that nested statements left untagged
+    fn tag_source_location(&mut self, first: usize, span: &Span) {
+        let Some(source_map) = &self.source_map else {
+            return;
+        };
+        let location = (source_map.get_line(span), source_map.get_column(span));
+        for quad in &mut self.program.quadruples[first..] {
+            quad.source_location.get_or_insert(location);
+        }
+    }
+
+    fn generate_declaration_kind(&mut self, declaration: &Declaration) {
+        match &declaration.node {
+            DeclarationKind::Variable(names, typ) => {
+                for name in names {
+                    self.program.add(Quadruple::new(
+                        Operation::DeclareVariable(typ.clone()),
+                        Operand::Empty,
+                        Operand::Empty,
+                        Operand::Variable(name.node.clone()),
+                    ));
+                }
+            }
+            DeclarationKind::Array(names, typ, size) => {
+                for name in names {
+                    self.array_sizes.insert(name.node.clone(), *size);
+                    self.program.add(Quadruple::new(
+                        Operation::DeclareArray(typ.clone(), *size),
+                        Operand::Empty,
+                        Operand::Empty,
+                        Operand::Variable(name.node.clone()),
+                    ));
+                }
+            }
+            DeclarationKind::VariableWithInit(names, typ, expr) => {
+                // Evaluate the initializer once and share it between all names
+                let init_val = self.generate_expression(expr);
+                for name in names {
+                    self.program.add(Quadruple::new(
+                        Operation::DeclareVariable(typ.clone()),
+                        init_val.clone(),
+                        Operand::Empty,
+                        Operand::Variable(name.node.clone()),
+                    ));
+                }
+            }
+            DeclarationKind::ArrayWithInit(names, typ, size, exprs) => {
+                // Evaluate the initializers once, in order, and share them between all names
+                let values: Vec<Operand> =
+                    exprs.iter().map(|expr| self.generate_expression(expr)).collect();
+                for name in names {
+                    self.array_sizes.insert(name.node.clone(), *size);
+                    self.program.add(Quadruple::new(
+                        Operation::DeclareArray(typ.clone(), *size),
+                        Operand::Empty,
+                        Operand::Empty,
+                        Operand::Variable(name.node.clone()),
+                    ));
+
+                    // Store each initializer into its slot
+                    for (index, value) in values.iter().enumerate() {
+                        self.program.add(Quadruple::new(
+                            Operation::ArrayStore,
+                            value.clone(),
+                            Operand::IntLiteral(index as i32),
+                            Operand::Variable(name.node.clone()),
+                        ));
+                    }
+                }
+            }
+            DeclarationKind::Constant(name, typ, literal) => {
+                let value = match &literal.node {
+                    LiteralKind::Int(value) => Operand::IntLiteral(*value),
+                    LiteralKind::Float(value) => Operand::FloatLiteral(f64::from(*value)),
+                    LiteralKind::String(value) => Operand::StringLiteral(value.clone()),
+                };
+                self.program.add(Quadruple::new(
+                    Operation::DeclareVariable(typ.clone()),
+                    value,
+                    Operand::Empty,
+                    Operand::Variable(name.node.clone()),
+                ));
+            }
+            // Inlined by ModuleLoader before code generation
+            DeclarationKind::Import(_) => {}
+        }
+    }
+
+    fn generate_statement_kind(&mut self, statement: &Statement) {
         match &statement.node {
             StatementKind::Assignment(lhs, rhs) => {
+                // `dst := src` between arrays copies every element
+                if let (ExpressionKind::Identifier(target), ExpressionKind::Identifier(source)) =
+                    (&lhs.node, &rhs.node)
+                    && let Some(&size) = self.array_sizes.get(target)
+                    && self.array_sizes.contains_key(source)
+                {
+                    self.program.add(Quadruple::new(
+                        Operation::MemCopy(size),
+                        Operand::Variable(source.clone()),
+                        Operand::Empty,
+                        Operand::Variable(target.clone()),
+                    ));
+                    return;
+                }
+
                 // Generate RHS expression first
                 let rhs_result = self.generate_expression(rhs);
 
@@ -33,22 +211,22 @@ impl CodeGenerator {
                 match &lhs.node {
                     ExpressionKind::Identifier(name) => {
                         // Simple variable assignment
-                        self.program.add(Quadruple {
-                            operation: Operation::Assign,
-                            operand1: rhs_result,
-                            operand2: Operand::Empty,
-                            result: Operand::Variable(name.clone()),
-                        });
+                        self.program.add(Quadruple::new(
+                            Operation::Assign,
+                            rhs_result,
+                            Operand::Empty,
+                            Operand::Variable(name.clone()),
+                        ));
                     }
                     ExpressionKind::ArrayAccess(name, index_expr) => {
                         // Array element assignment
                         let index = self.generate_expression(index_expr);
-                        self.program.add(Quadruple {
-                            operation: Operation::ArrayStore,
-                            operand1: rhs_result,
-                            operand2: index,
-                            result: Operand::Variable(name.clone()),
-                        });
+                        self.program.add(Quadruple::new(
+                            Operation::ArrayStore,
+                            rhs_result,
+                            index,
+                            Operand::Variable(name.clone()),
+                        ));
                     }
                     _ => {
                         // Invalid LHS, can't handle other expression types in assignment
@@ -61,12 +239,15 @@ impl CodeGenerator {
                 let cond_result = self.generate_expression(condition);
 
                 // Jump to else label if condition is false
-                self.program.add(Quadruple {
-                    operation: Operation::JumpIfFalse(else_label),
-                    operand1: cond_result,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::JumpIfFalse {
+                        id: else_label,
+                        name: Some("if_end".to_string()),
+                    },
+                    cond_result,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
 
                 // Generate code for then block
                 for stmt in then_block {
@@ -74,185 +255,280 @@ impl CodeGenerator {
                 }
 
                 // Add else label
-                self.program.add(Quadruple {
-                    operation: Operation::Label(else_label),
-                    operand1: Operand::Empty,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: else_label,
+                        name: Some("if_end".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
             }
             StatementKind::IfThenElse(condition, then_block, else_block) => {
                 let else_label = self.program.new_label();
+                let end_label = self.program.new_label();
                 let cond_result = self.generate_expression(condition);
 
                 // Jump to else label if condition is false
-                self.program.add(Quadruple {
-                    operation: Operation::JumpIfFalse(else_label),
-                    operand1: cond_result,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
-
-                // Generate code for then block
+                self.program.add(Quadruple::new(
+                    Operation::JumpIfFalse {
+                        id: else_label,
+                        name: Some("if_else".to_string()),
+                    },
+                    cond_result,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
+
+                // Generate code for then block, then skip the else block
                 for stmt in then_block {
                     self.generate_statement(stmt);
                 }
+                self.program.add(Quadruple::new(
+                    Operation::Jump {
+                        id: end_label,
+                        name: Some("if_end".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
 
                 // Add else label
-                self.program.add(Quadruple {
-                    operation: Operation::Label(else_label),
-                    operand1: Operand::Empty,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: else_label,
+                        name: Some("if_else".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
 
                 // Generate code for else block
-                for stmt in else_block {
+                for stmt in &else_block.node {
                     self.generate_statement(stmt);
                 }
+
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: end_label,
+                        name: Some("if_end".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
             }
             StatementKind::DoWhile(body, condition) => {
                 let start_label = self.program.new_label();
 
                 // Add start label
-                self.program.add(Quadruple {
-                    operation: Operation::Label(start_label),
-                    operand1: Operand::Empty,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: start_label,
+                        name: Some("do_while_start".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
 
                 // Generate code for body
+                self.break_labels.push((None, "do_while_end"));
+                self.continue_labels.push((None, "do_while_condition"));
                 for stmt in body {
                     self.generate_statement(stmt);
                 }
+                let continue_label = self.continue_labels.pop();
+                self.add_loop_label(continue_label);
 
                 // Generate condition
                 let cond_result = self.generate_expression(condition);
 
                 // Jump to start if condition is true
-                self.program.add(Quadruple {
-                    operation: Operation::JumpIfTrue(start_label),
-                    operand1: cond_result,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::JumpIfTrue {
+                        id: start_label,
+                        name: Some("do_while_start".to_string()),
+                    },
+                    cond_result,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
+
+                let break_label = self.break_labels.pop();
+                self.add_loop_label(break_label);
+            }
+            StatementKind::While(condition, body) => {
+                let condition_label = self.program.new_label();
+                let end_label = self.program.new_label();
+
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: condition_label,
+                        name: Some("while_condition".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
+
+                // Leave the loop as soon as the condition is false
+                let cond_result = self.generate_expression(condition);
+                self.program.add(Quadruple::new(
+                    Operation::JumpIfFalse {
+                        id: end_label,
+                        name: Some("while_end".to_string()),
+                    },
+                    cond_result,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
+
+                self.break_labels.push((Some(end_label), "while_end"));
+                self.continue_labels.push((Some(condition_label), "while_condition"));
+                for stmt in body {
+                    self.generate_statement(stmt);
+                }
+                self.break_labels.pop();
+                self.continue_labels.pop();
+
+                self.program.add(Quadruple::new(
+                    Operation::Jump {
+                        id: condition_label,
+                        name: Some("while_condition".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: end_label,
+                        name: Some("while_end".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
             }
             StatementKind::For(var_name, init, end, step, body) => {
-                // Extract variable name from expression
-                let var_str = match &var_name.node {
-                    ExpressionKind::Identifier(name) => name.clone(),
-                    _ => "unknown".to_string(), // Fallback, ideally handled by semantic analysis
+                // The counter is a variable or an array element, whose index
+                // is evaluated once before the loop
+                let counter = match &var_name.node {
+                    ExpressionKind::ArrayAccess(name, index_expr) => {
+                        let index = self.generate_expression(index_expr);
+                        Operand::ArrayElement(name.clone(), Box::new(index))
+                    }
+                    ExpressionKind::Identifier(name) => Operand::Variable(name.clone()),
+                    // The grammar only accepts variables and array elements
+                    _ => unreachable!("for loop counter is not an lvalue"),
                 };
 
                 // Generate initialization
                 let init_val = self.generate_expression(init);
-                self.program.add(Quadruple {
-                    operation: Operation::Assign,
-                    operand1: init_val,
-                    operand2: Operand::Empty,
-                    result: Operand::Variable(var_str.clone()),
-                });
+                self.store_counter(&counter, init_val);
 
                 let loop_start = self.program.new_label();
                 let loop_end = self.program.new_label();
 
                 // Add loop start label
-                self.program.add(Quadruple {
-                    operation: Operation::Label(loop_start),
-                    operand1: Operand::Empty,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: loop_start,
+                        name: Some("for_loop_start".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
 
                 // Generate end condition
                 let end_val = self.generate_expression(end);
-                let var_operand = Operand::Variable(var_str.clone());
-                let temp = self.program.new_temp();
-
-                // Compare var with end value
-                self.program.add(Quadruple {
-                    operation: Operation::LessThan,
-                    operand1: var_operand.clone(),
-                    operand2: end_val,
-                    result: temp.clone(),
-                });
-
-                // If var >= end, exit loop
-                self.program.add(Quadruple {
-                    operation: Operation::JumpIfFalse(loop_end),
-                    operand1: temp,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                let var_operand = self.load_counter(&counter);
+                let temp = self.generate_for_condition(&var_operand, end_val, step);
+
+                // If var has reached end, exit loop
+                self.program.add(Quadruple::new(
+                    Operation::JumpIfFalse {
+                        id: loop_end,
+                        name: Some("for_loop_end".to_string()),
+                    },
+                    temp,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
 
                 // Generate loop body
+                self.break_labels.push((Some(loop_end), "for_loop_end"));
+                self.continue_labels.push((None, "for_loop_step"));
                 for stmt in body {
                     self.generate_statement(stmt);
                 }
+                self.break_labels.pop();
+                let continue_label = self.continue_labels.pop();
+                self.add_loop_label(continue_label);
 
-                // Step increment
+                // Step increment, reading the counter again since the body
+                // may have changed it
                 let step_val = self.generate_expression(step);
+                let var_operand = self.load_counter(&counter);
                 let new_val = self.program.new_temp();
 
-                self.program.add(Quadruple {
-                    operation: Operation::Add,
-                    operand1: var_operand.clone(),
-                    operand2: step_val,
-                    result: new_val.clone(),
-                });
+                self.program.add(Quadruple::new(
+                    Operation::Add,
+                    var_operand,
+                    step_val,
+                    new_val.clone(),
+                ));
 
-                self.program.add(Quadruple {
-                    operation: Operation::Assign,
-                    operand1: new_val,
-                    operand2: Operand::Empty,
-                    result: var_operand,
-                });
+                self.store_counter(&counter, new_val);
 
                 // Jump back to condition
-                self.program.add(Quadruple {
-                    operation: Operation::Jump(loop_start),
-                    operand1: Operand::Empty,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::Jump {
+                        id: loop_start,
+                        name: Some("for_loop_start".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
 
                 // Loop end label
-                self.program.add(Quadruple {
-                    operation: Operation::Label(loop_end),
-                    operand1: Operand::Empty,
-                    operand2: Operand::Empty,
-                    result: Operand::Empty,
-                });
+                self.program.add(Quadruple::new(
+                    Operation::Label {
+                        id: loop_end,
+                        name: Some("for_loop_end".to_string()),
+                    },
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
             }
             StatementKind::Input(expr) => {
                 // Handle input for a variable
                 match &expr.node {
                     ExpressionKind::Identifier(name) => {
-                        self.program.add(Quadruple {
-                            operation: Operation::Input,
-                            operand1: Operand::Empty,
-                            operand2: Operand::Empty,
-                            result: Operand::Variable(name.clone()),
-                        });
+                        self.program.add(Quadruple::new(
+                            Operation::Input,
+                            Operand::Empty,
+                            Operand::Empty,
+                            Operand::Variable(name.clone()),
+                        ));
                     }
                     ExpressionKind::ArrayAccess(name, index_expr) => {
+                        // Read straight into the element, so that the value is
+                        // parsed as the element type of the array
                         let index = self.generate_expression(index_expr);
-                        let temp = self.program.new_temp();
-
-                        self.program.add(Quadruple {
-                            operation: Operation::Input,
-                            operand1: Operand::Empty,
-                            operand2: Operand::Empty,
-                            result: temp.clone(),
-                        });
-
-                        self.program.add(Quadruple {
-                            operation: Operation::ArrayStore,
-                            operand1: temp,
-                            operand2: index,
-                            result: Operand::Variable(name.clone()),
-                        });
+                        self.program.add(Quadruple::new(
+                            Operation::Input,
+                            Operand::Empty,
+                            Operand::Empty,
+                            Operand::ArrayElement(name.clone(), Box::new(index)),
+                        ));
                     }
                     _ => {
                         // Invalid input target
@@ -260,14 +536,61 @@ impl CodeGenerator {
                 }
             }
             StatementKind::Output(exprs) => {
-                for expr in exprs {
+                let mut values = exprs.iter();
+                if let Some(segments) = format_segments(exprs) {
+                    // The format string is split around its placeholders
+                    values.next();
+                    for segment in segments {
+                        let result = match segment {
+                            FormatSegment::Text(text) => Operand::StringLiteral(text),
+                            FormatSegment::Placeholder(_) => match values.next() {
+                                Some(value) => self.generate_expression(value),
+                                None => continue,
+                            },
+                        };
+                        self.program.add(Quadruple::new(
+                            Operation::Output,
+                            result,
+                            Operand::Empty,
+                            Operand::Empty,
+                        ));
+                    }
+                }
+                for expr in values {
                     let result = self.generate_expression(expr);
-                    self.program.add(Quadruple {
-                        operation: Operation::Output,
-                        operand1: result,
-                        operand2: Operand::Empty,
-                        result: Operand::Empty,
-                    });
+                    self.program.add(Quadruple::new(
+                        Operation::Output,
+                        result,
+                        Operand::Empty,
+                        Operand::Empty,
+                    ));
+                }
+
+                // Each output statement ends its line
+                self.program.add(Quadruple::new(
+                    Operation::Output,
+                    Operand::StringLiteral("\n".to_string()),
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
+            }
+            StatementKind::InlineAsm(lines) => {
+                self.program.add(Quadruple::new(
+                    Operation::InlineAsm(lines.clone()),
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
+            }
+            // Semantic analysis rejects both outside of a loop
+            StatementKind::Break => {
+                if let Some(label) = self.break_labels.last_mut() {
+                    jump_to_loop_label(&mut self.program, label);
+                }
+            }
+            StatementKind::Continue => {
+                if let Some(label) = self.continue_labels.last_mut() {
+                    jump_to_loop_label(&mut self.program, label);
                 }
             }
             StatementKind::Scope(statements) => {
@@ -277,11 +600,87 @@ impl CodeGenerator {
                 }
             }
             StatementKind::Empty => {
-                // Do nothing for empty statements
+                self.program.add(Quadruple::new(
+                    Operation::Nop,
+                    Operand::Empty,
+                    Operand::Empty,
+                    Operand::Empty,
+                ));
             }
         }
     }
 
+    /// Whether a for loop should run another iteration: `var < end` when
+    /// counting up and `var > end` when the step is negative. A step that is
+    /// not a literal is checked at run time.
+    fn generate_for_condition(&mut self, var: &Operand, end: Operand, step: &Expression) -> Operand {
+        let step_val = self.generate_expression(step);
+        if let Operand::IntLiteral(step) = step_val {
+            let operation = if step < 0 {
+                Operation::GreaterThan
+            } else {
+                Operation::LessThan
+            };
+            return self.emit_temp(operation, var.clone(), end);
+        }
+
+        // (step < 0 AND var > end) OR (NOT step < 0 AND var < end)
+        let descending = self.emit_temp(Operation::LessThan, step_val, Operand::IntLiteral(0));
+        let above_end = self.emit_temp(Operation::GreaterThan, var.clone(), end.clone());
+        let below_end = self.emit_temp(Operation::LessThan, var.clone(), end);
+        let down = self.emit_temp(Operation::And, descending.clone(), above_end);
+        let ascending = self.emit_temp(Operation::Not, descending, Operand::Empty);
+        let up = self.emit_temp(Operation::And, ascending, below_end);
+        self.emit_temp(Operation::Or, down, up)
+    }
+
+    /// Value of a `for` loop counter, loading it first if it is an array element
+    fn load_counter(&mut self, counter: &Operand) -> Operand {
+        match counter {
+            Operand::ArrayElement(name, index) => self.emit_temp(
+                Operation::ArrayLoad,
+                Operand::Variable(name.clone()),
+                (**index).clone(),
+            ),
+            _ => counter.clone(),
+        }
+    }
+
+    /// Assigns `value` to a `for` loop counter, see [`load_counter`](Self::load_counter)
+    fn store_counter(&mut self, counter: &Operand, value: Operand) {
+        let (operation, index, result) = match counter {
+            Operand::ArrayElement(name, index) => (
+                Operation::ArrayStore,
+                (**index).clone(),
+                Operand::Variable(name.clone()),
+            ),
+            _ => (Operation::Assign, Operand::Empty, counter.clone()),
+        };
+        self.program.add(Quadruple::new(operation, value, index, result));
+    }
+
+    /// Adds the label popped off a loop label stack, if a statement jumped to it
+    fn add_loop_label(&mut self, label: Option<LoopLabel>) {
+        if let Some((Some(id), name)) = label {
+            self.program.add(Quadruple::new(
+                Operation::Label {
+                    id,
+                    name: Some(name.to_string()),
+                },
+                Operand::Empty,
+                Operand::Empty,
+                Operand::Empty,
+            ));
+        }
+    }
+
+    /// Adds `operation` with its result in a new temporary, which is returned
+    fn emit_temp(&mut self, operation: Operation, operand1: Operand, operand2: Operand) -> Operand {
+        let result = self.program.new_temp();
+        self.program.add(Quadruple::new(operation, operand1, operand2, result.clone()));
+        result
+    }
+
     fn generate_expression(&mut self, expr: &Expression) -> Operand {
         match &expr.node {
             ExpressionKind::Identifier(name) => Operand::Variable(name.clone()),
@@ -289,24 +688,23 @@ impl CodeGenerator {
                 let index = self.generate_expression(index_expr);
                 let temp = self.program.new_temp();
 
-                self.program.add(Quadruple {
-                    operation: Operation::ArrayLoad,
-                    operand1: Operand::Variable(name.clone()),
-                    operand2: index,
-                    result: temp.clone(),
-                });
+                self.program.add(Quadruple::new(
+                    Operation::ArrayLoad,
+                    Operand::Variable(name.clone()),
+                    index,
+                    temp.clone(),
+                ));
 
                 temp
             }
             ExpressionKind::Literal(lit) => match &lit.node {
                 LiteralKind::Int(value) => Operand::IntLiteral(*value),
-                LiteralKind::Float(value) => Operand::FloatLiteral(*value),
+                LiteralKind::Float(value) => Operand::FloatLiteral(f64::from(*value)),
                 LiteralKind::String(value) => Operand::StringLiteral(value.clone()),
             },
             ExpressionKind::BinaryOp(left, op, right) => {
                 let left_result = self.generate_expression(left);
                 let right_result = self.generate_expression(right);
-                let result = self.program.new_temp();
 
                 // Map AST operator to quadruple operation
                 let operation = match op {
@@ -314,6 +712,7 @@ impl CodeGenerator {
                     Operator::Subtract => Operation::Subtract,
                     Operator::Multiply => Operation::Multiply,
                     Operator::Divide => Operation::Divide,
+                    Operator::Modulo => Operation::Modulo,
                     Operator::Equal => Operation::Equal,
                     Operator::NotEqual => Operation::NotEqual,
                     Operator::LessThan => Operation::LessThan,
@@ -324,12 +723,19 @@ impl CodeGenerator {
                     Operator::Or => Operation::Or,
                 };
 
-                self.program.add(Quadruple {
+                if self.optimization_level >= 1
+                    && let Some(folded) = fold_binary_op(&operation, &left_result, &right_result)
+                {
+                    return folded;
+                }
+
+                let result = self.program.new_temp();
+                self.program.add(Quadruple::new(
                     operation,
-                    operand1: left_result,
-                    operand2: right_result,
-                    result: result.clone(),
-                });
+                    left_result,
+                    right_result,
+                    result.clone(),
+                ));
 
                 result
             }
@@ -339,17 +745,33 @@ impl CodeGenerator {
 
                 let operation = match op {
                     UnaryOperator::Not => Operation::Not,
+                    UnaryOperator::Negate => Operation::Negate,
                 };
 
-                self.program.add(Quadruple {
+                self.program.add(Quadruple::new(
                     operation,
-                    operand1: expr_result,
-                    operand2: Operand::Empty,
-                    result: result.clone(),
-                });
+                    expr_result,
+                    Operand::Empty,
+                    result.clone(),
+                ));
 
                 result
             }
         }
     }
 }
+
+/// Jumps to a loop's `break` or `continue` label, allocating it on first use
+fn jump_to_loop_label(program: &mut QuadrupleProgram, label: &mut LoopLabel) {
+    let (id, name) = label;
+    let id = *id.get_or_insert_with(|| program.new_label());
+    program.add(Quadruple::new(
+        Operation::Jump {
+            id,
+            name: Some(name.to_string()),
+        },
+        Operand::Empty,
+        Operand::Empty,
+        Operand::Empty,
+    ));
+}
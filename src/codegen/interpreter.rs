@@ -0,0 +1,435 @@
+//! Direct execution of quadruples, used by the REPL.
+//!
+//! Values follow the assembly back end: integers are 64-bit, floats are
+//! doubles, mixed arithmetic is done in floating point, and comparisons and
+//! logical operations produce `0` or `1`.
+
+use super::quadruple::{Operand, Operation, Quadruple};
+use crate::parser::ast::Type;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+/// A runtime value
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Int(i64),
+    Float(f64),
+    String(String),
+}
+
+impl Value {
+    fn zero(typ: &Type) -> Self {
+        match typ {
+            Type::Int => Value::Int(0),
+            Type::Float => Value::Float(0.0),
+            Type::String => Value::String(String::new()),
+        }
+    }
+
+    fn value_type(&self) -> Type {
+        match self {
+            Value::Int(_) => Type::Int,
+            Value::Float(_) => Type::Float,
+            Value::String(_) => Type::String,
+        }
+    }
+
+    fn as_int(&self) -> i64 {
+        match self {
+            Value::Int(value) => *value,
+            Value::Float(value) => *value as i64,
+            Value::String(_) => 0,
+        }
+    }
+
+    fn as_float(&self) -> f64 {
+        match self {
+            Value::Int(value) => *value as f64,
+            Value::Float(value) => *value,
+            Value::String(_) => 0.0,
+        }
+    }
+
+    fn convert(self, typ: &Type) -> Self {
+        match (typ, self) {
+            (Type::Int, value) => Value::Int(value.as_int()),
+            (Type::Float, value) => Value::Float(value.as_float()),
+            (Type::String, Value::String(text)) => Value::String(text),
+            (Type::String, _) => Value::String(String::new()),
+        }
+    }
+}
+
+/// Same format as the `print_float` runtime helper: up to six decimals,
+/// keeping at least one.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Int(value) => write!(f, "{}", value),
+            Value::Float(value) => {
+                let text = format!("{:.6}", value);
+                let text = text.trim_end_matches('0');
+                if text.ends_with('.') {
+                    write!(f, "{}0", text)
+                } else {
+                    write!(f, "{}", text)
+                }
+            }
+            Value::String(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum RuntimeError {
+    DivisionByZero,
+    IndexOutOfBounds {
+        name: String,
+        index: i64,
+        size: usize,
+    },
+    UnknownLabel(usize),
+    /// Operations that only make sense in generated assembly
+    Unsupported(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuntimeError::DivisionByZero => write!(f, "Division by zero"),
+            RuntimeError::IndexOutOfBounds { name, index, size } => write!(
+                f,
+                "Index {} is out of bounds for array '{}' of size {}",
+                index, name, size
+            ),
+            RuntimeError::UnknownLabel(id) => write!(f, "Jump to unknown label L{}", id),
+            RuntimeError::Unsupported(operation) => {
+                write!(f, "{} cannot be interpreted", operation)
+            }
+            RuntimeError::Io(error) => write!(f, "I/O error: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for RuntimeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            RuntimeError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for RuntimeError {
+    fn from(error: io::Error) -> Self {
+        RuntimeError::Io(error)
+    }
+}
+
+/// Executes quadruples one at a time. Variables persist between calls, so
+/// code can be run piece by piece.
+#[derive(Debug, Default)]
+pub struct QuadrupleInterpreter {
+    variables: HashMap<String, Value>,
+    variable_types: HashMap<String, Type>,
+    arrays: HashMap<String, Vec<Value>>,
+    temps: HashMap<String, Value>,
+}
+
+impl QuadrupleInterpreter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current value of the variable `name`
+    pub fn get_variable(&self, name: &str) -> Option<&Value> {
+        self.variables.get(name)
+    }
+
+    /// Run `quadruples` from the first one until the end. Jumps must target
+    /// labels inside `quadruples`.
+    pub fn run(
+        &mut self,
+        quadruples: &[Quadruple],
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+    ) -> Result<(), RuntimeError> {
+        let labels: HashMap<usize, usize> = quadruples
+            .iter()
+            .enumerate()
+            .filter_map(|(index, quad)| match quad.operation {
+                Operation::Label { id, .. } => Some((id, index)),
+                _ => None,
+            })
+            .collect();
+
+        let mut pc = 0;
+        while let Some(quad) = quadruples.get(pc) {
+            pc = match self.execute_one(quad, input, output)? {
+                Some(label) => *labels
+                    .get(&label)
+                    .ok_or(RuntimeError::UnknownLabel(label))?,
+                None => pc + 1,
+            };
+        }
+        output.flush()?;
+        Ok(())
+    }
+
+    /// Execute a single quadruple. Returns the target label when it jumps.
+    pub fn execute_one(
+        &mut self,
+        quad: &Quadruple,
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+    ) -> Result<Option<usize>, RuntimeError> {
+        match &quad.operation {
+            Operation::DeclareVariable(typ) => {
+                if let Operand::Variable(name) = &quad.result {
+                    let value = match &quad.operand1 {
+                        Operand::Empty => Value::zero(typ),
+                        operand => self.read(operand)?.convert(typ),
+                    };
+                    self.variable_types.insert(name.clone(), typ.clone());
+                    self.variables.insert(name.clone(), value);
+                }
+            }
+            Operation::DeclareArray(typ, size) => {
+                if let Operand::Variable(name) = &quad.result {
+                    self.variable_types.insert(name.clone(), typ.clone());
+                    self.arrays
+                        .insert(name.clone(), vec![Value::zero(typ); *size]);
+                }
+            }
+            Operation::Assign => {
+                let value = self.read(&quad.operand1)?;
+                self.write(&quad.result, value)?;
+            }
+            Operation::ArrayStore => {
+                let value = self.read(&quad.operand1)?;
+                let index = self.read(&quad.operand2)?.as_int();
+                if let Operand::Variable(name) = &quad.result {
+                    self.write_element(name, index, value)?;
+                }
+            }
+            Operation::MemCopy(_) => {
+                if let (Operand::Variable(source), Operand::Variable(target)) =
+                    (&quad.operand1, &quad.result)
+                    && let Some(elements) = self.arrays.get(source).cloned()
+                {
+                    self.arrays.insert(target.clone(), elements);
+                }
+            }
+            Operation::ArrayLoad => {
+                if let Operand::Variable(name) = &quad.operand1 {
+                    let index = self.read(&quad.operand2)?.as_int();
+                    let value = self.read_element(name, index)?;
+                    self.write(&quad.result, value)?;
+                }
+            }
+            Operation::Add | Operation::Subtract | Operation::Multiply | Operation::Divide => {
+                let left = self.read(&quad.operand1)?;
+                let right = self.read(&quad.operand2)?;
+                let value = arithmetic(&quad.operation, &left, &right)?;
+                self.write(&quad.result, value)?;
+            }
+            Operation::Modulo => {
+                let left = self.read(&quad.operand1)?.as_int();
+                let right = self.read(&quad.operand2)?.as_int();
+                if right == 0 {
+                    return Err(RuntimeError::DivisionByZero);
+                }
+                self.write(&quad.result, Value::Int(left.wrapping_rem(right)))?;
+            }
+            Operation::Negate => {
+                let value = match self.read(&quad.operand1)? {
+                    Value::Float(value) => Value::Float(-value),
+                    value => Value::Int(value.as_int().wrapping_neg()),
+                };
+                self.write(&quad.result, value)?;
+            }
+            Operation::Equal
+            | Operation::NotEqual
+            | Operation::LessThan
+            | Operation::GreaterThan
+            | Operation::LessEqual
+            | Operation::GreaterEqual => {
+                let left = self.read(&quad.operand1)?;
+                let right = self.read(&quad.operand2)?;
+                let value = comparison(&quad.operation, &left, &right);
+                self.write(&quad.result, Value::Int(value as i64))?;
+            }
+            Operation::And | Operation::Or => {
+                let left = self.read(&quad.operand1)?.as_int() != 0;
+                let right = self.read(&quad.operand2)?.as_int() != 0;
+                let value = if quad.operation == Operation::And {
+                    left && right
+                } else {
+                    left || right
+                };
+                self.write(&quad.result, Value::Int(value as i64))?;
+            }
+            Operation::Not => {
+                let value = self.read(&quad.operand1)?.as_int() == 0;
+                self.write(&quad.result, Value::Int(value as i64))?;
+            }
+            Operation::Label { .. } => {}
+            Operation::Jump { id, .. } => return Ok(Some(*id)),
+            Operation::JumpIfTrue { id, .. } => {
+                if self.read(&quad.operand1)?.as_int() != 0 {
+                    return Ok(Some(*id));
+                }
+            }
+            Operation::JumpIfFalse { id, .. } => {
+                if self.read(&quad.operand1)?.as_int() == 0 {
+                    return Ok(Some(*id));
+                }
+            }
+            Operation::Input => {
+                let mut line = String::new();
+                input.read_line(&mut line)?;
+                let line = line.trim();
+                let value = match self.operand_type(&quad.result) {
+                    Type::Int => Value::Int(line.parse().unwrap_or(0)),
+                    Type::Float => Value::Float(line.parse().unwrap_or(0.0)),
+                    Type::String => Value::String(line.to_string()),
+                };
+                self.write(&quad.result, value)?;
+            }
+            Operation::Output => {
+                let value = self.read(&quad.operand1)?;
+                write!(output, "{}", value)?;
+            }
+            Operation::Nop => {}
+            Operation::Call(_)
+            | Operation::Return
+            | Operation::Phi(_)
+            | Operation::InlineAsm(_) => {
+                return Err(RuntimeError::Unsupported(quad.operation.to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    fn operand_type(&self, operand: &Operand) -> Type {
+        match operand {
+            Operand::Variable(name) | Operand::ArrayElement(name, _) => {
+                self.variable_types.get(name).cloned().unwrap_or_default()
+            }
+            _ => Type::Int,
+        }
+    }
+
+    fn read(&self, operand: &Operand) -> Result<Value, RuntimeError> {
+        Ok(match operand {
+            Operand::IntLiteral(value) => Value::Int(*value as i64),
+            Operand::FloatLiteral(value) => Value::Float(*value),
+            Operand::StringLiteral(text) => Value::String(text.clone()),
+            Operand::Variable(name) => self
+                .variables
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| Value::zero(&self.operand_type(operand))),
+            Operand::TempVariable(name) => self.temps.get(name).cloned().unwrap_or(Value::Int(0)),
+            Operand::ArrayElement(name, index) => {
+                let index = self.read(index)?.as_int();
+                self.read_element(name, index)?
+            }
+            Operand::Empty => Value::Int(0),
+        })
+    }
+
+    /// Variables keep their declared type, temporaries take the value's type
+    fn write(&mut self, target: &Operand, value: Value) -> Result<(), RuntimeError> {
+        match target {
+            Operand::Variable(name) => {
+                let typ = self
+                    .variable_types
+                    .get(name)
+                    .cloned()
+                    .unwrap_or_else(|| value.value_type());
+                self.variables.insert(name.clone(), value.convert(&typ));
+            }
+            Operand::TempVariable(name) => {
+                self.temps.insert(name.clone(), value);
+            }
+            Operand::ArrayElement(name, index) => {
+                let index = self.read(index)?.as_int();
+                self.write_element(name, index, value)?;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn element_index(&self, name: &str, index: i64) -> Result<usize, RuntimeError> {
+        let size = self.arrays.get(name).map_or(0, Vec::len);
+        match usize::try_from(index) {
+            Ok(position) if position < size => Ok(position),
+            _ => Err(RuntimeError::IndexOutOfBounds {
+                name: name.to_string(),
+                index,
+                size,
+            }),
+        }
+    }
+
+    fn read_element(&self, name: &str, index: i64) -> Result<Value, RuntimeError> {
+        let position = self.element_index(name, index)?;
+        Ok(self.arrays[name][position].clone())
+    }
+
+    fn write_element(&mut self, name: &str, index: i64, value: Value) -> Result<(), RuntimeError> {
+        let position = self.element_index(name, index)?;
+        let typ = self.variable_types[name].clone();
+        self.arrays.get_mut(name).unwrap()[position] = value.convert(&typ);
+        Ok(())
+    }
+}
+
+fn arithmetic(operation: &Operation, left: &Value, right: &Value) -> Result<Value, RuntimeError> {
+    if matches!(left, Value::Float(_)) || matches!(right, Value::Float(_)) {
+        let (left, right) = (left.as_float(), right.as_float());
+        return Ok(Value::Float(match operation {
+            Operation::Add => left + right,
+            Operation::Subtract => left - right,
+            Operation::Multiply => left * right,
+            _ => left / right,
+        }));
+    }
+
+    let (left, right) = (left.as_int(), right.as_int());
+    Ok(Value::Int(match operation {
+        Operation::Add => left.wrapping_add(right),
+        Operation::Subtract => left.wrapping_sub(right),
+        Operation::Multiply => left.wrapping_mul(right),
+        _ if right == 0 => return Err(RuntimeError::DivisionByZero),
+        _ => left.wrapping_div(right),
+    }))
+}
+
+fn comparison(operation: &Operation, left: &Value, right: &Value) -> bool {
+    if matches!(left, Value::Float(_)) || matches!(right, Value::Float(_)) {
+        let (left, right) = (left.as_float(), right.as_float());
+        return match operation {
+            Operation::Equal => left == right,
+            Operation::NotEqual => left != right,
+            Operation::LessThan => left < right,
+            Operation::GreaterThan => left > right,
+            Operation::LessEqual => left <= right,
+            _ => left >= right,
+        };
+    }
+
+    let (left, right) = (left.as_int(), right.as_int());
+    match operation {
+        Operation::Equal => left == right,
+        Operation::NotEqual => left != right,
+        Operation::LessThan => left < right,
+        Operation::GreaterThan => left > right,
+        Operation::LessEqual => left <= right,
+        _ => left >= right,
+    }
+}
@@ -1,4 +1,8 @@
+pub mod assembly_gen;
 pub mod generator;
+pub mod interpreter;
+pub mod optimizer;
 pub mod quadruple;
+pub mod ssa;
 
 pub use quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
@@ -0,0 +1,314 @@
+//! Optimization passes over quadruple programs.
+//!
+//! Level 0 leaves the program as generated. From level 1 the generator folds
+//! arithmetic whose operands are both literals, see [`fold_binary_op`], and
+//! the optimizer folds again over the whole program, propagating folded
+//! temporaries into later quadruples, removes quadruples that no path
+//! reaches, and then removes stores whose value is never read. Level 2 also
+//! goes through SSA form (see [`super::ssa`]) to propagate variables assigned
+//! a literal into the quadruples that read them, then folds and removes dead
+//! stores again.
+
+use super::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use std::collections::{HashMap, HashSet};
+use std::mem;
+
+impl QuadrupleProgram {
+    /// Apply the passes enabled at `level`, see the module documentation.
+    pub fn optimize(&mut self, level: u8) {
+        if level >= 1 {
+            self.fold_constants();
+            self.eliminate_dead_code();
+            self.eliminate_dead_stores();
+        }
+        if level >= 2 {
+            self.propagate_constants_in_ssa_form();
+            self.fold_constants();
+            self.eliminate_dead_stores();
+        }
+    }
+
+    /// Replace reads of variables assigned a literal with the literal. In SSA
+    /// form every version has a single definition that comes before its reads
+    /// on every path, so this holds across jumps.
+    ///
+    /// Inline assembly and calls may write any variable, so programs using
+    /// them are left alone.
+    pub fn propagate_constants_in_ssa_form(&mut self) {
+        if self
+            .quadruples
+            .iter()
+            .any(|quad| matches!(quad.operation, Operation::InlineAsm(_) | Operation::Call(_)))
+        {
+            return;
+        }
+
+        let mut ssa = self.to_ssa_form();
+        ssa.fold_constants_with(true);
+        *self = ssa.from_ssa_form();
+    }
+
+    /// Remove the quadruples that no path from the first one reaches, such as
+    /// the code after an unconditional jump up to the next label jumped to.
+    ///
+    /// Inline assembly may jump anywhere, so programs using it are left alone.
+    pub fn eliminate_dead_code(&mut self) {
+        if self
+            .quadruples
+            .iter()
+            .any(|quad| matches!(quad.operation, Operation::InlineAsm(_)))
+        {
+            return;
+        }
+
+        let labels: HashMap<usize, usize> = self
+            .quadruples
+            .iter()
+            .enumerate()
+            .filter_map(|(index, quad)| match quad.operation {
+                Operation::Label { id, .. } => Some((id, index)),
+                _ => None,
+            })
+            .collect();
+
+        let mut reachable = HashSet::new();
+        let mut pending = vec![0];
+        while let Some(index) = pending.pop() {
+            let Some(quad) = self.quadruples.get(index) else {
+                continue;
+            };
+            if !reachable.insert(index) {
+                continue;
+            }
+            match &quad.operation {
+                Operation::Jump { id, .. } => pending.extend(labels.get(id)),
+                Operation::JumpIfTrue { id, .. } | Operation::JumpIfFalse { id, .. } => {
+                    pending.extend(labels.get(id));
+                    pending.push(index + 1);
+                }
+                _ => pending.push(index + 1),
+            }
+        }
+
+        self.quadruples = mem::take(&mut self.quadruples)
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| reachable.contains(index))
+            .map(|(_, quad)| quad)
+            .collect();
+    }
+
+    /// Remove the assignments and array stores whose value no path reads
+    /// before the variable is assigned again or the program ends, such as the
+    /// first of `x := 5; x := 10;`. Removing one store can make the store of
+    /// the value it copied dead too, so the pass repeats until nothing changes.
+    ///
+    /// Inline assembly and calls may read any variable, so programs using
+    /// them are left alone.
+    pub fn eliminate_dead_stores(&mut self) {
+        if self
+            .quadruples
+            .iter()
+            .any(|quad| matches!(quad.operation, Operation::InlineAsm(_) | Operation::Call(_)))
+        {
+            return;
+        }
+
+        loop {
+            let live_out = self.live_variables();
+            let before = self.quadruples.len();
+            let mut index = 0;
+            self.quadruples.retain(|quad| {
+                let store = stored_variable(quad);
+                index += 1;
+                store.is_none_or(|name| live_out[index - 1].contains(name))
+            });
+            if self.quadruples.len() == before {
+                break;
+            }
+        }
+    }
+
+    /// The variables read on some path after each quadruple before being
+    /// assigned again, computed backwards until no set changes.
+    fn live_variables(&self) -> Vec<HashSet<String>> {
+        let labels: HashMap<usize, usize> = self
+            .quadruples
+            .iter()
+            .enumerate()
+            .filter_map(|(index, quad)| match quad.operation {
+                Operation::Label { id, .. } => Some((id, index)),
+                _ => None,
+            })
+            .collect();
+        let successors = |index: usize| -> Vec<usize> {
+            let next = (index + 1 < self.quadruples.len()).then_some(index + 1);
+            match &self.quadruples[index].operation {
+                Operation::Jump { id, .. } => labels.get(id).copied().into_iter().collect(),
+                Operation::JumpIfTrue { id, .. } | Operation::JumpIfFalse { id, .. } => {
+                    labels.get(id).copied().into_iter().chain(next).collect()
+                }
+                Operation::Return => Vec::new(),
+                _ => next.into_iter().collect(),
+            }
+        };
+
+        let count = self.quadruples.len();
+        let mut live_in: Vec<HashSet<String>> = vec![HashSet::new(); count];
+        let mut live_out: Vec<HashSet<String>> = vec![HashSet::new(); count];
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for index in (0..count).rev() {
+                let out: HashSet<String> = successors(index)
+                    .into_iter()
+                    .flat_map(|successor| live_in[successor].iter().cloned())
+                    .collect();
+
+                let quad = &self.quadruples[index];
+                let mut live = out.clone();
+                // An array store changes one element, so the rest stays live
+                if quad.operation != Operation::ArrayStore
+                    && let Operand::Variable(name) | Operand::TempVariable(name) = &quad.result
+                {
+                    live.remove(name);
+                }
+                read_variables(&quad.operand1, false, &mut live);
+                read_variables(&quad.operand2, false, &mut live);
+                read_variables(&quad.result, true, &mut live);
+
+                if live != live_in[index] {
+                    live_in[index] = live;
+                    changed = true;
+                }
+                live_out[index] = out;
+            }
+        }
+        live_out
+    }
+
+    /// Replace arithmetic on literals with an assignment of the result.
+    ///
+    /// Temporaries are assigned exactly once, so a temporary that folds to a
+    /// literal can be replaced by that literal wherever it is read.
+    fn fold_constants(&mut self) {
+        self.fold_constants_with(false);
+    }
+
+    /// [`fold_constants`](Self::fold_constants), also propagating `variables`
+    /// assigned a literal, which is only right when each is assigned once.
+    fn fold_constants_with(&mut self, variables: bool) {
+        let mut constants: HashMap<String, Operand> = HashMap::new();
+
+        for quad in &mut self.quadruples {
+            substitute(&mut quad.operand1, &constants);
+            substitute(&mut quad.operand2, &constants);
+            if let Operand::ArrayElement(_, index) = &mut quad.result {
+                substitute(index, &constants);
+            }
+
+            if let Operand::Variable(name) = &quad.result
+                && variables
+                && quad.operation == Operation::Assign
+                && is_literal(&quad.operand1)
+            {
+                constants.insert(name.clone(), quad.operand1.clone());
+            }
+
+            let Some(value) = fold_binary_op(&quad.operation, &quad.operand1, &quad.operand2)
+            else {
+                continue;
+            };
+
+            quad.operation = Operation::Assign;
+            quad.operand1 = value.clone();
+            quad.operand2 = Operand::Empty;
+            if let Operand::TempVariable(name) = &quad.result {
+                constants.insert(name.clone(), value);
+            }
+        }
+    }
+}
+
+/// Literal result of `left <operation> right` when both operands are literals
+/// of the same type, or `None` when it cannot be folded. Overflow and division
+/// by zero are left to run time.
+pub(crate) fn fold_binary_op(
+    operation: &Operation,
+    left: &Operand,
+    right: &Operand,
+) -> Option<Operand> {
+    match (left, right) {
+        (Operand::IntLiteral(left), Operand::IntLiteral(right)) => {
+            let value = match operation {
+                Operation::Add => left.checked_add(*right),
+                Operation::Subtract => left.checked_sub(*right),
+                Operation::Multiply => left.checked_mul(*right),
+                Operation::Divide => left.checked_div(*right),
+                Operation::Modulo => left.checked_rem(*right),
+                _ => None,
+            };
+            value.map(Operand::IntLiteral)
+        }
+        (Operand::FloatLiteral(left), Operand::FloatLiteral(right)) => {
+            let (left, right) = (*left, *right);
+            let value = match operation {
+                Operation::Add => left + right,
+                Operation::Subtract => left - right,
+                Operation::Multiply => left * right,
+                Operation::Divide if right != 0.0 => left / right,
+                _ => return None,
+            };
+            value.is_finite().then_some(Operand::FloatLiteral(value))
+        }
+        _ => None,
+    }
+}
+
+fn is_literal(operand: &Operand) -> bool {
+    matches!(
+        operand,
+        Operand::IntLiteral(_) | Operand::FloatLiteral(_) | Operand::StringLiteral(_)
+    )
+}
+
+/// The variable written by an assignment or array store, which may be removed
+/// when nothing reads that variable afterwards.
+fn stored_variable(quad: &Quadruple) -> Option<&str> {
+    match (&quad.operation, &quad.result) {
+        (
+            Operation::Assign | Operation::ArrayStore,
+            Operand::Variable(name) | Operand::TempVariable(name) | Operand::ArrayElement(name, _),
+        ) => Some(name),
+        _ => None,
+    }
+}
+
+/// Add the variables `operand` reads to `live`. A result only reads the
+/// index of an array element it stores into.
+fn read_variables(operand: &Operand, is_result: bool, live: &mut HashSet<String>) {
+    match operand {
+        Operand::Variable(name) | Operand::TempVariable(name) if !is_result => {
+            live.insert(name.clone());
+        }
+        Operand::ArrayElement(name, index) => {
+            if !is_result {
+                live.insert(name.clone());
+            }
+            read_variables(index, false, live);
+        }
+        _ => {}
+    }
+}
+
+fn substitute(operand: &mut Operand, constants: &HashMap<String, Operand>) {
+    match operand {
+        Operand::Variable(name) | Operand::TempVariable(name) => {
+            if let Some(value) = constants.get(name) {
+                *operand = value.clone();
+            }
+        }
+        Operand::ArrayElement(_, index) => substitute(index, constants),
+        _ => {}
+    }
+}
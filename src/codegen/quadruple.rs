@@ -1,24 +1,35 @@
+use crate::parser::ast::Type;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::mem;
 
 /// Represents the type of operation in a quadruple
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Operation {
     // Arithmetic operations
     Add,
     Subtract,
     Multiply,
     Divide,
+    Modulo,
+    Negate,
     
+    // Declarations
+    DeclareVariable(Type),
+    DeclareArray(Type, usize),
+
     // Assignment and memory operations
     Assign,
     ArrayStore,
     ArrayLoad,
+    MemCopy(usize), // Copy a whole array of `size` elements from operand1 into result
     
-    // Control flow operations
-    Label(usize),
-    Jump(usize),
-    JumpIfTrue(usize),
-    JumpIfFalse(usize),
+    // Control flow operations. `name` is a hint such as "for_loop_start"
+    // that makes dumps and assembly listings easier to read.
+    Label { id: usize, name: Option<String> },
+    Jump { id: usize, name: Option<String> },
+    JumpIfTrue { id: usize, name: Option<String> },
+    JumpIfFalse { id: usize, name: Option<String> },
     
     // Comparison operations
     Equal,
@@ -40,13 +51,22 @@ pub enum Operation {
     // Function operations
     Call(String),
     Return,
+
+    // SSA join point: (predecessor label, value coming from it)
+    Phi(Vec<(usize, Operand)>),
+
+    // Assembly lines emitted verbatim
+    InlineAsm(Vec<String>),
+
+    // Empty statement, kept so it has a place in dumps and listings
+    Nop,
 }
 
 /// Represents an operand in a quadruple
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub enum Operand {
     IntLiteral(i32),
-    FloatLiteral(f32),
+    FloatLiteral(f64),           // Floats are doubles at run time
     StringLiteral(String),
     Variable(String),            // Simple variable
     TempVariable(String),        // Compiler-generated temporary
@@ -54,13 +74,137 @@ pub enum Operand {
     Empty,
 }
 
+// Float literals compare and hash by bit pattern, like `LiteralKind`
+impl PartialEq for Operand {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Operand::IntLiteral(l), Operand::IntLiteral(r)) => l == r,
+            (Operand::FloatLiteral(l), Operand::FloatLiteral(r)) => l.to_bits() == r.to_bits(),
+            (Operand::StringLiteral(l), Operand::StringLiteral(r)) => l == r,
+            (Operand::Variable(l), Operand::Variable(r)) => l == r,
+            (Operand::TempVariable(l), Operand::TempVariable(r)) => l == r,
+            (Operand::ArrayElement(l, li), Operand::ArrayElement(r, ri)) => l == r && li == ri,
+            (Operand::Empty, Operand::Empty) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Operand {}
+
+impl Hash for Operand {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            Operand::IntLiteral(value) => value.hash(state),
+            Operand::FloatLiteral(value) => value.to_bits().hash(state),
+            Operand::StringLiteral(name) | Operand::Variable(name) | Operand::TempVariable(name) => {
+                name.hash(state)
+            }
+            Operand::ArrayElement(name, index) => {
+                name.hash(state);
+                index.hash(state);
+            }
+            Operand::Empty => {}
+        }
+    }
+}
+
 /// A single quadruple with operation and operands
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Quadruple {
     pub operation: Operation,
     pub operand1: Operand,
     pub operand2: Operand,
     pub result: Operand,
+    /// Line and column of the source statement or declaration this was
+    /// generated from
+    pub source_location: Option<(usize, usize)>,
+}
+
+impl Quadruple {
+    /// Quadruple not tagged with a source location yet
+    pub fn new(
+        operation: Operation,
+        operand1: Operand,
+        operand2: Operand,
+        result: Operand,
+    ) -> Self {
+        Quadruple {
+            operation,
+            operand1,
+            operand2,
+            result,
+            source_location: None,
+        }
+    }
+
+    /// Same quadruple, tagged with the source location it was generated from
+    pub fn with_source_location(mut self, line: usize, column: usize) -> Self {
+        self.source_location = Some((line, column));
+        self
+    }
+
+    /// Line of the source statement or declaration this was generated from
+    pub fn source_line(&self) -> Option<usize> {
+        self.source_location.map(|(line, _)| line)
+    }
+
+    /// The quadruple as a three-address instruction, see [`QuadrupleProgram::pretty_print`]
+    fn three_address(&self) -> String {
+        let (op1, op2, result) = (&self.operand1, &self.operand2, &self.result);
+        match &self.operation {
+            Operation::DeclareVariable(typ) if *op1 == Operand::Empty => {
+                format!("decl {}: {}", result, typ)
+            }
+            Operation::DeclareVariable(typ) => format!("decl {}: {} := {}", result, typ, op1),
+            Operation::DeclareArray(typ, size) => format!("decl {}: [{}; {}]", result, typ, size),
+            Operation::Assign => format!("{} := {}", result, op1),
+            Operation::ArrayStore => format!("{}[{}] := {}", result, op2, op1),
+            Operation::ArrayLoad => format!("{} := {}[{}]", result, op1, op2),
+            Operation::MemCopy(size) => format!("{}[0..{}] := {}[0..{}]", result, size, op1, size),
+            Operation::Negate => format!("{} := -{}", result, op1),
+            Operation::Not => format!("{} := !{}", result, op1),
+            Operation::Label { id, .. } => format!("L{}:", id),
+            Operation::Jump { id, .. } => format!("goto L{}", id),
+            Operation::JumpIfTrue { id, .. } => format!("if {} goto L{}", op1, id),
+            Operation::JumpIfFalse { id, .. } => format!("iffalse {} goto L{}", op1, id),
+            Operation::Input => format!("input {}", result),
+            Operation::Output => format!("output {}", op1),
+            Operation::Call(name) if *result == Operand::Empty => format!("call {}", name),
+            Operation::Call(name) => format!("{} := call {}", result, name),
+            Operation::Return if *op1 == Operand::Empty => "return".to_string(),
+            Operation::Return => format!("return {}", op1),
+            Operation::Phi(sources) => {
+                let sources: Vec<String> = sources
+                    .iter()
+                    .map(|(label, value)| format!("L{}: {}", label, value))
+                    .collect();
+                format!("{} := phi({})", result, sources.join(", "))
+            }
+            Operation::InlineAsm(lines) => format!("asm {:?}", lines.join("; ")),
+            Operation::Nop => "nop".to_string(),
+            operation => {
+                let symbol = match operation {
+                    Operation::Add => "+",
+                    Operation::Subtract => "-",
+                    Operation::Multiply => "*",
+                    Operation::Divide => "/",
+                    Operation::Modulo => "%",
+                    Operation::Equal => "==",
+                    Operation::NotEqual => "!=",
+                    Operation::LessThan => "<",
+                    Operation::GreaterThan => ">",
+                    Operation::LessEqual => "<=",
+                    Operation::GreaterEqual => ">=",
+                    Operation::And => "AND",
+                    Operation::Or => "OR",
+                    _ => unreachable!("{} is handled above", operation),
+                };
+                format!("{} := {} {} {}", result, op1, symbol, op2)
+            }
+        }
+    }
 }
 
 /// Collection of quadruples representing a program
@@ -81,6 +225,11 @@ impl QuadrupleProgram {
         }
     }
     
+    /// Remove every quadruple and number temporaries and labels from 1 again
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+
     /// Add a quadruple to the program
     pub fn add(&mut self, quad: Quadruple) {
         self.quadruples.push(quad);
@@ -92,7 +241,58 @@ impl QuadrupleProgram {
         self.next_temp += 1;
         Operand::TempVariable(temp)
     }
+
+    /// Number of temporaries allocated so far, `t1` to `t{temp_count}`
+    pub fn temp_count(&self) -> usize {
+        self.next_temp.saturating_sub(1)
+    }
     
+    /// One quadruple per line, followed by the source line it came from
+    pub fn dump_annotated(&self) -> String {
+        let mut dump = String::new();
+        for (index, quad) in self.quadruples.iter().enumerate() {
+            match quad.source_line() {
+                Some(line) => dump.push_str(&format!("{:4}: {}  ; line {}\n", index, quad, line)),
+                None => dump.push_str(&format!("{:4}: {}\n", index, quad)),
+            }
+        }
+        dump
+    }
+
+    /// Three-address text, one instruction per line: `t1 := a + b`,
+    /// `if t2 goto L3`, with each label on its own line as `L3:`.
+    pub fn pretty_print(&self) -> String {
+        self.three_address_text(false)
+    }
+
+    /// [`pretty_print`](Self::pretty_print) with the source location of each
+    /// instruction after it, as in `t1 := a + b  ; 7:5`
+    pub fn pretty_print_with_locations(&self) -> String {
+        self.three_address_text(true)
+    }
+
+    fn three_address_text(&self, locations: bool) -> String {
+        let mut text = String::new();
+        for quad in &self.quadruples {
+            match &quad.operation {
+                Operation::Label { id, name } => match name {
+                    Some(name) => text.push_str(&format!("L{}:  ; {}\n", id, name)),
+                    None => text.push_str(&format!("L{}:\n", id)),
+                },
+                _ => match quad.source_location {
+                    Some((line, column)) if locations => text.push_str(&format!(
+                        "    {}  ; {}:{}\n",
+                        quad.three_address(),
+                        line,
+                        column
+                    )),
+                    _ => text.push_str(&format!("    {}\n", quad.three_address())),
+                },
+            }
+        }
+        text
+    }
+
     /// Generate a new label identifier
     pub fn new_label(&mut self) -> usize {
         let label = self.next_label;
@@ -108,13 +308,17 @@ impl fmt::Display for Operation {
             Operation::Subtract => write!(f, "SUB"),
             Operation::Multiply => write!(f, "MUL"),
             Operation::Divide => write!(f, "DIV"),
+            Operation::Modulo => write!(f, "MOD"),
+            Operation::DeclareVariable(typ) => write!(f, "DECL_{}", typ),
+            Operation::DeclareArray(typ, size) => write!(f, "DECL_{}[{}]", typ, size),
             Operation::Assign => write!(f, "ASSIGN"),
             Operation::ArrayStore => write!(f, "ASTORE"),
             Operation::ArrayLoad => write!(f, "ALOAD"),
-            Operation::Label(id) => write!(f, "LABEL_{}", id),
-            Operation::Jump(id) => write!(f, "JUMP_{}", id),
-            Operation::JumpIfTrue(id) => write!(f, "JMPT_{}", id),
-            Operation::JumpIfFalse(id) => write!(f, "JMPF_{}", id),
+            Operation::MemCopy(size) => write!(f, "MEMCOPY[{}]", size),
+            Operation::Label { id, name } => write!(f, "LABEL_{}{}", id, label_hint(name)),
+            Operation::Jump { id, name } => write!(f, "JUMP_{}{}", id, label_hint(name)),
+            Operation::JumpIfTrue { id, name } => write!(f, "JMPT_{}{}", id, label_hint(name)),
+            Operation::JumpIfFalse { id, name } => write!(f, "JMPF_{}{}", id, label_hint(name)),
             Operation::Equal => write!(f, "EQ"),
             Operation::NotEqual => write!(f, "NEQ"),
             Operation::LessThan => write!(f, "LT"),
@@ -123,21 +327,42 @@ impl fmt::Display for Operation {
             Operation::GreaterEqual => write!(f, "GE"),
             Operation::And => write!(f, "AND"),
             Operation::Or => write!(f, "OR"),
+            Operation::Negate => write!(f, "NEG"),
             Operation::Not => write!(f, "NOT"),
             Operation::Input => write!(f, "INPUT"),
             Operation::Output => write!(f, "OUTPUT"),
             Operation::Call(name) => write!(f, "CALL_{}", name),
             Operation::Return => write!(f, "RETURN"),
+            Operation::Phi(sources) => {
+                let sources: Vec<String> = sources
+                    .iter()
+                    .map(|(label, value)| format!("L{}: {}", label, value))
+                    .collect();
+                write!(f, "PHI[{}]", sources.join(", "))
+            }
+            Operation::InlineAsm(lines) => write!(f, "ASM[{}]", lines.join("; ")),
+            Operation::Nop => write!(f, "NOP"),
         }
     }
 }
 
+/// `(name)` after a label id, or nothing for unnamed labels
+fn label_hint(name: &Option<String>) -> String {
+    name.as_ref()
+        .map(|name| format!("({})", name))
+        .unwrap_or_default()
+}
+
 impl fmt::Display for Operand {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Operand::IntLiteral(val) => write!(f, "{}", val),
+            // Source literals are parsed as f32, print those as written
+            Operand::FloatLiteral(val) if f64::from(*val as f32) == *val => {
+                write!(f, "{}", *val as f32)
+            }
             Operand::FloatLiteral(val) => write!(f, "{}", val),
-            Operand::StringLiteral(val) => write!(f, "\"{}\"", val),
+            Operand::StringLiteral(val) => write!(f, "{:?}", val),
             Operand::Variable(name) => write!(f, "{}", name),
             Operand::TempVariable(name) => write!(f, "{}", name),
             Operand::ArrayElement(name, idx) => write!(f, "{}[{}]", name, idx),
@@ -0,0 +1,604 @@
+//! Static single assignment form for quadruple programs.
+//!
+//! Only scalar user variables are renamed: temporaries are already assigned
+//! once by `CodeGenerator`, and array elements are not tracked. A variable
+//! keeps its declared name for its first version, later versions are named
+//! `x.1`, `x.2`, ... (`.` cannot appear in MiniSoft identifiers) and get
+//! their own declaration next to the original one.
+
+use super::quadruple::{Operand, Operation, Quadruple, QuadrupleProgram};
+use crate::parser::ast::Type;
+use std::collections::{HashMap, HashSet};
+
+/// A straight-line run of quadruples `start..end`, always starting with a label.
+#[derive(Debug)]
+struct BasicBlock {
+    label: usize,
+    start: usize,
+    end: usize,
+    successors: Vec<usize>,
+    predecessors: Vec<usize>,
+}
+
+/// Blocks of a program where every block starts with a `Label` quadruple.
+#[derive(Debug)]
+struct ControlFlowGraph {
+    blocks: Vec<BasicBlock>,
+}
+
+impl ControlFlowGraph {
+    fn new(quadruples: &[Quadruple]) -> Self {
+        let mut blocks: Vec<BasicBlock> = Vec::new();
+        let mut block_of_label = HashMap::new();
+
+        for (index, quad) in quadruples.iter().enumerate() {
+            if let Operation::Label { id, .. } = quad.operation {
+                if let Some(last) = blocks.last_mut() {
+                    last.end = index;
+                }
+                block_of_label.insert(id, blocks.len());
+                blocks.push(BasicBlock {
+                    label: id,
+                    start: index,
+                    end: quadruples.len(),
+                    successors: Vec::new(),
+                    predecessors: Vec::new(),
+                });
+            }
+        }
+
+        for block in 0..blocks.len() {
+            let falls_through = block + 1 < blocks.len();
+            let last = &quadruples[blocks[block].end - 1].operation;
+            let successors = match last {
+                Operation::Jump { id, .. } => vec![block_of_label[id]],
+                Operation::JumpIfTrue { id, .. } | Operation::JumpIfFalse { id, .. }
+                    if falls_through =>
+                {
+                    vec![block_of_label[id], block + 1]
+                }
+                Operation::JumpIfTrue { id, .. } | Operation::JumpIfFalse { id, .. } => {
+                    vec![block_of_label[id]]
+                }
+                _ if falls_through => vec![block + 1],
+                _ => Vec::new(),
+            };
+            for &successor in &successors {
+                if !blocks[successor].predecessors.contains(&block) {
+                    blocks[successor].predecessors.push(block);
+                }
+            }
+            blocks[block].successors = successors;
+        }
+
+        ControlFlowGraph { blocks }
+    }
+
+    /// Blocks reachable from the entry block, in reverse postorder.
+    fn reverse_postorder(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.blocks.len()];
+        let mut postorder = Vec::new();
+        // (block, index of the next successor to visit)
+        let mut stack = vec![(0, 0)];
+        visited[0] = true;
+
+        while let Some((block, next)) = stack.pop() {
+            if let Some(&successor) = self.blocks[block].successors.get(next) {
+                stack.push((block, next + 1));
+                if !visited[successor] {
+                    visited[successor] = true;
+                    stack.push((successor, 0));
+                }
+            } else {
+                postorder.push(block);
+            }
+        }
+
+        postorder.reverse();
+        postorder
+    }
+
+    /// Immediate dominator of every reachable block (the entry block is its
+    /// own), using the Cooper-Harvey-Kennedy iterative algorithm.
+    fn immediate_dominators(&self, order: &[usize]) -> Vec<Option<usize>> {
+        let mut rpo_index = vec![usize::MAX; self.blocks.len()];
+        for (index, &block) in order.iter().enumerate() {
+            rpo_index[block] = index;
+        }
+
+        let mut idom = vec![None; self.blocks.len()];
+        idom[0] = Some(0);
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &block in order.iter().skip(1) {
+                let mut new_idom = None;
+                for &pred in &self.blocks[block].predecessors {
+                    if idom[pred].is_none() {
+                        continue;
+                    }
+                    new_idom = Some(match new_idom {
+                        None => pred,
+                        Some(current) => intersect(&idom, &rpo_index, pred, current),
+                    });
+                }
+                if new_idom.is_some() && idom[block] != new_idom {
+                    idom[block] = new_idom;
+                    changed = true;
+                }
+            }
+        }
+
+        idom
+    }
+
+    fn dominance_frontiers(&self, idom: &[Option<usize>]) -> Vec<HashSet<usize>> {
+        let mut frontiers = vec![HashSet::new(); self.blocks.len()];
+        for (block, data) in self.blocks.iter().enumerate() {
+            if data.predecessors.len() < 2 || idom[block].is_none() {
+                continue;
+            }
+            for &pred in &data.predecessors {
+                let mut runner = pred;
+                while idom[runner].is_some() && Some(runner) != idom[block] {
+                    frontiers[runner].insert(block);
+                    runner = idom[runner].unwrap();
+                }
+            }
+        }
+        frontiers
+    }
+}
+
+fn intersect(idom: &[Option<usize>], rpo_index: &[usize], a: usize, b: usize) -> usize {
+    let (mut a, mut b) = (a, b);
+    while a != b {
+        while rpo_index[a] > rpo_index[b] {
+            a = idom[a].unwrap();
+        }
+        while rpo_index[b] > rpo_index[a] {
+            b = idom[b].unwrap();
+        }
+    }
+    a
+}
+
+/// Scalar variable defined by `quad`, if any.
+fn defined_variable<'a>(quad: &'a Quadruple, scalars: &HashMap<String, Type>) -> Option<&'a str> {
+    match (&quad.operation, &quad.result) {
+        (Operation::ArrayStore | Operation::MemCopy(_), _) => None,
+        (_, Operand::Variable(name)) if scalars.contains_key(name) => Some(name),
+        _ => None,
+    }
+}
+
+fn used_variables<'a>(
+    operand: &'a Operand,
+    scalars: &HashMap<String, Type>,
+    uses: &mut Vec<&'a str>,
+) {
+    match operand {
+        Operand::Variable(name) if scalars.contains_key(name) => uses.push(name),
+        Operand::ArrayElement(_, index) => used_variables(index, scalars, uses),
+        _ => {}
+    }
+}
+
+struct Renamer<'a> {
+    cfg: &'a ControlFlowGraph,
+    scalars: &'a HashMap<String, Type>,
+    dominator_children: Vec<Vec<usize>>,
+    /// Variable of each phi-node, per block
+    phi_variables: Vec<Vec<String>>,
+    /// Quadruples of every block, phi-nodes first after the label
+    blocks: Vec<Vec<Quadruple>>,
+    stacks: HashMap<String, Vec<String>>,
+    versions: HashMap<String, usize>,
+    /// New versions of each variable, to be declared
+    new_names: Vec<(String, String)>,
+}
+
+impl Renamer<'_> {
+    fn current(&self, name: &str) -> String {
+        self.stacks
+            .get(name)
+            .and_then(|stack| stack.last())
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    fn rename_use(&self, operand: &mut Operand) {
+        match operand {
+            Operand::Variable(name) if self.scalars.contains_key(name.as_str()) => {
+                *name = self.current(name);
+            }
+            Operand::ArrayElement(_, index) => self.rename_use(index),
+            _ => {}
+        }
+    }
+
+    fn new_version(&mut self, name: &str) -> String {
+        let version = self.versions.entry(name.to_string()).or_insert(0);
+        *version += 1;
+        let renamed = format!("{}.{}", name, version);
+        self.new_names.push((name.to_string(), renamed.clone()));
+        renamed
+    }
+
+    fn rename_block(&mut self, block: usize) {
+        let mut pushed: Vec<String> = Vec::new();
+        let mut quads = std::mem::take(&mut self.blocks[block]);
+
+        for quad in &mut quads {
+            if !matches!(quad.operation, Operation::Phi(_)) {
+                self.rename_use(&mut quad.operand1);
+                self.rename_use(&mut quad.operand2);
+            }
+            // Storing into an array element reads its index
+            if let Operand::ArrayElement(..) = quad.result {
+                self.rename_use(&mut quad.result);
+            }
+
+            let Some(name) = defined_variable(quad, self.scalars).map(str::to_string) else {
+                continue;
+            };
+            // The declaration is the first version and keeps the plain name
+            let renamed = if matches!(quad.operation, Operation::DeclareVariable(_)) {
+                name.clone()
+            } else {
+                self.new_version(&name)
+            };
+            quad.result = Operand::Variable(renamed.clone());
+            self.stacks.entry(name.clone()).or_default().push(renamed);
+            pushed.push(name);
+        }
+        self.blocks[block] = quads;
+
+        let label = self.cfg.blocks[block].label;
+        for &successor in &self.cfg.blocks[block].successors {
+            for (index, name) in self.phi_variables[successor].iter().enumerate() {
+                let value = Operand::Variable(self.current(name));
+                // Phi-nodes directly follow the block's label
+                if let Operation::Phi(sources) = &mut self.blocks[successor][index + 1].operation {
+                    sources.push((label, value));
+                }
+            }
+        }
+
+        for child in self.dominator_children[block].clone() {
+            self.rename_block(child);
+        }
+
+        for name in pushed {
+            if let Some(stack) = self.stacks.get_mut(&name) {
+                stack.pop();
+            }
+        }
+    }
+}
+
+impl QuadrupleProgram {
+    /// Returns the program in SSA form: every scalar variable is assigned
+    /// once, and `Phi` nodes after the labels of join points select the
+    /// version coming from each predecessor block.
+    ///
+    /// Blocks that do not start with a label get a new one, so every phi
+    /// source can name its predecessor.
+    pub fn to_ssa_form(&self) -> QuadrupleProgram {
+        let mut program = self.clone();
+        program.label_basic_blocks();
+
+        let scalars: HashMap<String, Type> = program
+            .quadruples
+            .iter()
+            .filter_map(|quad| match (&quad.operation, &quad.result) {
+                (Operation::DeclareVariable(typ), Operand::Variable(name)) => {
+                    Some((name.clone(), typ.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let cfg = ControlFlowGraph::new(&program.quadruples);
+        let order = cfg.reverse_postorder();
+        let idom = cfg.immediate_dominators(&order);
+        let frontiers = cfg.dominance_frontiers(&idom);
+
+        // Semi-pruned SSA: only variables read before being written in some
+        // block can need a phi-node
+        let mut def_blocks: HashMap<&str, HashSet<usize>> = HashMap::new();
+        let mut live_across_blocks: HashSet<&str> = HashSet::new();
+        for (index, block) in cfg.blocks.iter().enumerate() {
+            let mut defined = HashSet::new();
+            for quad in &program.quadruples[block.start..block.end] {
+                let mut uses = Vec::new();
+                used_variables(&quad.operand1, &scalars, &mut uses);
+                used_variables(&quad.operand2, &scalars, &mut uses);
+                if let Operand::ArrayElement(..) = quad.result {
+                    used_variables(&quad.result, &scalars, &mut uses);
+                }
+                for name in uses {
+                    if !defined.contains(name) {
+                        live_across_blocks.insert(name);
+                    }
+                }
+                if let Some(name) = defined_variable(quad, &scalars) {
+                    defined.insert(name);
+                    def_blocks.entry(name).or_default().insert(index);
+                }
+            }
+        }
+
+        let mut phis: Vec<Vec<String>> = vec![Vec::new(); cfg.blocks.len()];
+        let mut names: Vec<&str> = live_across_blocks.into_iter().collect();
+        names.sort_unstable();
+        for name in names {
+            let Some(defs) = def_blocks.get(name) else {
+                continue;
+            };
+            let mut has_phi = HashSet::new();
+            let mut worklist: Vec<usize> = defs.iter().copied().collect();
+            while let Some(block) = worklist.pop() {
+                for &frontier in &frontiers[block] {
+                    if has_phi.insert(frontier) {
+                        phis[frontier].push(name.to_string());
+                        if !defs.contains(&frontier) {
+                            worklist.push(frontier);
+                        }
+                    }
+                }
+            }
+        }
+
+        let blocks = cfg
+            .blocks
+            .iter()
+            .zip(&phis)
+            .map(|(block, names)| {
+                let mut quads = vec![program.quadruples[block.start].clone()];
+                quads.extend(names.iter().map(|name| {
+                    Quadruple::new(
+                        Operation::Phi(Vec::new()),
+                        Operand::Empty,
+                        Operand::Empty,
+                        Operand::Variable(name.clone()),
+                    )
+                }));
+                quads.extend_from_slice(&program.quadruples[block.start + 1..block.end]);
+                quads
+            })
+            .collect();
+
+        let mut dominator_children = vec![Vec::new(); cfg.blocks.len()];
+        for &block in order.iter().skip(1) {
+            if let Some(parent) = idom[block] {
+                dominator_children[parent].push(block);
+            }
+        }
+
+        let mut renamer = Renamer {
+            cfg: &cfg,
+            scalars: &scalars,
+            dominator_children,
+            phi_variables: phis,
+            blocks,
+            stacks: HashMap::new(),
+            versions: HashMap::new(),
+            new_names: Vec::new(),
+        };
+        renamer.rename_block(0);
+
+        // Declare each new version right after the variable it comes from
+        let mut versions_of: HashMap<String, Vec<String>> = HashMap::new();
+        for (name, renamed) in renamer.new_names {
+            versions_of.entry(name).or_default().push(renamed);
+        }
+
+        let mut quadruples = Vec::new();
+        for quad in renamer.blocks.into_iter().flatten() {
+            let declared = match (&quad.operation, &quad.result) {
+                (Operation::DeclareVariable(typ), Operand::Variable(name)) => {
+                    Some((typ.clone(), versions_of.remove(name).unwrap_or_default()))
+                }
+                _ => None,
+            };
+            quadruples.push(quad);
+            if let Some((typ, names)) = declared {
+                for renamed in names {
+                    quadruples.push(Quadruple::new(
+                        Operation::DeclareVariable(typ.clone()),
+                        Operand::Empty,
+                        Operand::Empty,
+                        Operand::Variable(renamed),
+                    ));
+                }
+            }
+        }
+
+        program.quadruples = quadruples;
+        program
+    }
+
+    /// Inserts a label at the start of every basic block that lacks one.
+    fn label_basic_blocks(&mut self) {
+        let mut labelled = Vec::with_capacity(self.quadruples.len());
+        let mut block_start = true;
+
+        for quad in std::mem::take(&mut self.quadruples) {
+            let is_label = matches!(quad.operation, Operation::Label { .. });
+            if block_start && !is_label {
+                labelled.push(label_quadruple(self.new_label()));
+            }
+            block_start = matches!(
+                quad.operation,
+                Operation::Jump { .. }
+                    | Operation::JumpIfTrue { .. }
+                    | Operation::JumpIfFalse { .. }
+            );
+            labelled.push(quad);
+        }
+
+        // An empty program is still one (empty) block
+        if labelled.is_empty() {
+            labelled.push(label_quadruple(self.new_label()));
+        }
+
+        self.quadruples = labelled;
+    }
+}
+
+impl QuadrupleProgram {
+    /// Removes the phi-nodes added by [`to_ssa_form`](Self::to_ssa_form).
+    ///
+    /// Each phi becomes a copy at the end of its predecessor block. The copies
+    /// of one edge happen in parallel, so they are ordered to never overwrite
+    /// a value that is still needed, using a temporary to break cycles. Edges
+    /// leaving a conditional jump for a join point are split: jump-target
+    /// edges go through a new block placed after the program, fall-through
+    /// edges through a new block placed in between.
+    pub fn from_ssa_form(&self) -> QuadrupleProgram {
+        let mut program = self.clone();
+        let cfg = ControlFlowGraph::new(&program.quadruples);
+
+        let block_of_label: HashMap<usize, usize> = cfg
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(index, block)| (block.label, index))
+            .collect();
+
+        // Parallel copies (destination, source) for each (predecessor, successor) edge
+        let mut copies: HashMap<(usize, usize), Vec<(Operand, Operand)>> = HashMap::new();
+        for (index, block) in cfg.blocks.iter().enumerate() {
+            for quad in &program.quadruples[block.start..block.end] {
+                if let Operation::Phi(sources) = &quad.operation {
+                    for (label, value) in sources {
+                        copies
+                            .entry((block_of_label[label], index))
+                            .or_default()
+                            .push((quad.result.clone(), value.clone()));
+                    }
+                }
+            }
+        }
+
+        let mut quadruples = Vec::new();
+        let mut split_blocks = Vec::new();
+
+        for (index, block) in cfg.blocks.iter().enumerate() {
+            let mut body: Vec<Quadruple> = program.quadruples[block.start..block.end]
+                .iter()
+                .filter(|quad| !matches!(quad.operation, Operation::Phi(_)))
+                .cloned()
+                .collect();
+
+            let last = body.len() - 1;
+            let mut fall_through = Vec::new();
+
+            for &successor in &block.successors {
+                let Some(edge) = copies.remove(&(index, successor)) else {
+                    continue;
+                };
+                let target = cfg.blocks[successor].label;
+                let sequence = program.sequentialize_copies(edge);
+
+                match &mut body[last].operation {
+                    Operation::Jump { .. } => {
+                        body.splice(last..last, sequence);
+                    }
+                    Operation::JumpIfTrue { id, name } | Operation::JumpIfFalse { id, name }
+                        if *id == target =>
+                    {
+                        let split = program.new_label();
+                        *id = split;
+                        *name = None;
+                        split_blocks.push(label_quadruple(split));
+                        split_blocks.extend(sequence);
+                        split_blocks.push(jump_quadruple(target));
+                    }
+                    Operation::JumpIfTrue { .. } | Operation::JumpIfFalse { .. } => {
+                        let split = program.new_label();
+                        fall_through.push(label_quadruple(split));
+                        fall_through.extend(sequence);
+                    }
+                    _ => body.extend(sequence),
+                }
+            }
+
+            quadruples.extend(body);
+            quadruples.extend(fall_through);
+        }
+
+        // Split blocks are only reached by jumps, so the program skips over them
+        if !split_blocks.is_empty() {
+            let end = program.new_label();
+            quadruples.push(jump_quadruple(end));
+            quadruples.extend(split_blocks);
+            quadruples.push(label_quadruple(end));
+        }
+
+        program.quadruples = quadruples;
+        program
+    }
+
+    fn sequentialize_copies(&mut self, copies: Vec<(Operand, Operand)>) -> Vec<Quadruple> {
+        let mut pending: Vec<(Operand, Operand)> = copies
+            .into_iter()
+            .filter(|(destination, source)| destination != source)
+            .collect();
+        let mut sequence = Vec::new();
+
+        while !pending.is_empty() {
+            // A destination that no other pending copy reads can be written now
+            let ready = (0..pending.len()).find(|&i| {
+                pending
+                    .iter()
+                    .enumerate()
+                    .all(|(j, (_, source))| i == j || *source != pending[i].0)
+            });
+
+            match ready {
+                Some(i) => {
+                    let (destination, source) = pending.remove(i);
+                    sequence.push(assign_quadruple(source, destination));
+                }
+                None => {
+                    // Only cycles are left: save one destination and read it from there
+                    let saved = pending[0].0.clone();
+                    let temp = self.new_temp();
+                    sequence.push(assign_quadruple(saved.clone(), temp.clone()));
+                    for (_, source) in &mut pending {
+                        if *source == saved {
+                            *source = temp.clone();
+                        }
+                    }
+                }
+            }
+        }
+
+        sequence
+    }
+}
+
+fn label_quadruple(id: usize) -> Quadruple {
+    Quadruple::new(
+        Operation::Label { id, name: None },
+        Operand::Empty,
+        Operand::Empty,
+        Operand::Empty,
+    )
+}
+
+fn jump_quadruple(id: usize) -> Quadruple {
+    Quadruple::new(
+        Operation::Jump { id, name: None },
+        Operand::Empty,
+        Operand::Empty,
+        Operand::Empty,
+    )
+}
+
+fn assign_quadruple(source: Operand, destination: Operand) -> Quadruple {
+    Quadruple::new(Operation::Assign, source, Operand::Empty, destination)
+}
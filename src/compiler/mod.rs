@@ -0,0 +1,772 @@
+pub mod module_loader;
+pub mod repl;
+
+use crate::codegen::assembly_gen::{AssemblyGenerator, Target};
+use crate::codegen::generator::CodeGenerator;
+use crate::codegen::quadruple::QuadrupleProgram;
+use crate::error_reporter::{
+    Diagnostic, DiagnosticHandler, ErrorReportFormatter, ErrorReporter, Severity,
+};
+use crate::lexer::lexer_core::{tokenize_with, TokenWithMetaData};
+use crate::parser::ast::{DeclarationKind, LiteralKind, Program};
+use crate::parser::parser_core::parse;
+use crate::semantics::symbol_table::{SymbolTable, SymbolValue};
+use crate::semantics::warning::SemanticWarning;
+use crate::semantics::{symbol_table::SymbolKind, SemanticAnalyzer};
+use colored::*;
+use module_loader::ModuleLoader;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Everything produced by a silent compilation: diagnostics from every stage
+/// that ran, plus the generated code when there were no errors.
+#[derive(Debug, Default)]
+pub struct CompilationResult {
+    pub diagnostics: Vec<Diagnostic>,
+    pub quadruples: Option<QuadrupleProgram>,
+    pub assembly: Option<String>,
+}
+
+/// Last stage of the pipeline that `run()` and `compile()` go through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Phase {
+    /// Lexer and parser only, including imported modules
+    Syntax,
+    /// Up to semantic analysis, without generating code
+    Semantic,
+    /// Everything up to the generated assembly
+    #[default]
+    Assembly,
+}
+
+/// How `run()` reports its progress and diagnostics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputFormat {
+    /// Colored report of every stage, for people
+    #[default]
+    Text,
+    /// Only a JSON array of diagnostics on stdout, for editors and tools
+    Json,
+}
+
+/// Serialization used by [`Compiler::set_ast_output`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AstFormat {
+    /// serde JSON of the `Program`, needs the `serde` feature
+    Json,
+    /// Indented S-expression, see [`Program::to_sexp`]
+    Sexp,
+}
+
+impl CompilationResult {
+    pub fn is_success(&self) -> bool {
+        !self.diagnostics.iter().any(Diagnostic::is_error)
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|d| d.severity == Severity::Warning)
+    }
+}
+
+/// Compile the MiniSoft file at `path` without printing anything.
+pub fn compile_file(path: &Path) -> CompilationResult {
+    match Compiler::new(&path.to_string_lossy()) {
+        Ok(mut compiler) => compiler.compile(),
+        Err(message) => CompilationResult {
+            diagnostics: vec![Diagnostic {
+                severity: Severity::Error,
+                kind: "IO Error".to_string(),
+                code: None,
+                message,
+                line: 0,
+                column: 0,
+                suggestion: None,
+            }],
+            ..Default::default()
+        },
+    }
+}
+
+/// Compile MiniSoft source code without printing anything.
+pub fn compile_source(src: &str) -> CompilationResult {
+    Compiler::from_source(src).compile()
+}
+
+/// Pipeline stage that can stop compilation with errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Stage {
+    Lexical,
+    Syntax,
+    Module,
+    Semantic,
+}
+
+impl Stage {
+    fn heading(self) -> &'static str {
+        match self {
+            Stage::Lexical => "Lexical Errors Detected:",
+            Stage::Syntax => "Parser Error Detected:",
+            Stage::Module => "Module Error Detected:",
+            Stage::Semantic => "Semantic Errors Detected:",
+        }
+    }
+}
+
+/// Intermediate results of `compile()` that only the stage report shows
+#[derive(Default)]
+struct StageOutput {
+    tokens: Vec<TokenWithMetaData>,
+    /// Whether the parsed program had imports to resolve
+    had_imports: bool,
+    /// The parsed program, with imports inlined once they are resolved
+    program: Option<Program>,
+    warnings: Vec<SemanticWarning>,
+    symbol_table: Option<SymbolTable>,
+    /// Stage that stopped the pipeline, with its errors rendered against the source
+    failure: Option<(Stage, Vec<String>)>,
+}
+
+pub struct Compiler {
+    source_code: String,
+    file_path: String,
+    output_path: PathBuf,
+    /// What the last `compile()` went through, for the `run()` report
+    stages: StageOutput,
+    show_warnings: bool,
+    warning_codes: Option<Vec<String>>,
+    diagnostic_handlers: Vec<DiagnosticHandler>,
+    allow_unsafe: bool,
+    warn_unused: bool,
+    profile: bool,
+    relaxed_identifiers: bool,
+    /// How many errors a stage prints before summarizing the rest, `None` for all
+    max_errors: Option<usize>,
+    target: Target,
+    optimization_level: u8,
+    last_phase: Phase,
+    output_format: OutputFormat,
+    /// Where `run()` writes the quadruples as three-address text, `-` for stdout
+    ir_output: Option<PathBuf>,
+    verbose_ir: bool,
+    /// Where `run()` writes the parsed program, and in which form
+    ast_output: Option<(AstFormat, PathBuf)>,
+}
+
+impl Compiler {
+    /// Compiler for the file at `file_path`. The assembly is written next to
+    /// it with an `.asm` extension unless [`with_output`](Self::with_output)
+    /// says otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_compiler::compiler::Compiler;
+    ///
+    /// let mut compiler = Compiler::new("examples/valid/sample_program.ms").unwrap();
+    /// let result = compiler.compile();
+    /// assert!(result.is_success(), "{:?}", result.diagnostics);
+    /// assert!(result.assembly.unwrap().contains("_start:"));
+    ///
+    /// assert!(Compiler::new("examples/missing.ms").is_err());
+    /// ```
+    pub fn new(file_path: &str) -> Result<Self, String> {
+        match fs::read_to_string(file_path) {
+            Ok(content) => Ok(Self::with_defaults(
+                content,
+                file_path,
+                Path::new(file_path).with_extension("asm"),
+            )),
+            Err(e) => Err(format!("Error reading file '{}': {}", file_path, e)),
+        }
+    }
+
+    /// Compiler for in-memory source code. The output defaults to `out.asm`.
+    pub fn from_source(source: &str) -> Self {
+        Self::with_defaults(source.to_string(), "<source>", PathBuf::from("out.asm"))
+    }
+
+    fn with_defaults(source_code: String, file_path: &str, output_path: PathBuf) -> Self {
+        Self {
+            source_code,
+            file_path: file_path.to_string(),
+            output_path,
+            stages: StageOutput::default(),
+            show_warnings: true,
+            warning_codes: None,
+            diagnostic_handlers: Vec::new(),
+            allow_unsafe: false,
+            warn_unused: false,
+            profile: false,
+            relaxed_identifiers: false,
+            max_errors: None,
+            target: Target::default(),
+            optimization_level: 0,
+            last_phase: Phase::default(),
+            output_format: OutputFormat::default(),
+            ir_output: None,
+            verbose_ir: false,
+            ast_output: None,
+        }
+    }
+
+    /// Write the generated assembly to `path` instead of next to the source file.
+    pub fn with_output(&mut self, path: &Path) -> &mut Self {
+        self.output_path = path.to_path_buf();
+        self
+    }
+
+    /// Name shown for the source in messages, e.g. when it was read from stdin.
+    pub fn with_source_name(&mut self, name: &str) -> &mut Self {
+        self.file_path = name.to_string();
+        self
+    }
+
+    /// Show or hide warnings. Hidden warnings are still counted.
+    pub fn set_show_warnings(&mut self, show: bool) {
+        self.show_warnings = show;
+    }
+
+    /// Only show warnings whose code (e.g. `W0001`) is in `codes`.
+    pub fn set_warning_codes(&mut self, codes: Vec<String>) {
+        self.warning_codes = Some(codes);
+    }
+
+    /// Accept `asm { ... }` statements. They are rejected by default.
+    pub fn set_allow_unsafe(&mut self, allow: bool) {
+        self.allow_unsafe = allow;
+    }
+
+    /// Warn about variables and constants the program never reads (W0005).
+    pub fn set_warn_unused(&mut self, warn: bool) {
+        self.warn_unused = warn;
+    }
+
+    /// Make the generated program print the cycles spent in each block.
+    pub fn set_profile(&mut self, profile: bool) {
+        self.profile = profile;
+    }
+
+    /// Accept identifiers longer than 14 characters or with uppercase letters
+    /// after the first one, as in code written for other MiniSoft compilers.
+    pub fn set_relaxed_identifiers(&mut self, relaxed: bool) {
+        self.relaxed_identifiers = relaxed;
+    }
+
+    /// Print only the first `limit` errors of a stage followed by how many
+    /// were left out. The error count and exit code still include all of them.
+    pub fn set_max_errors(&mut self, limit: usize) {
+        self.max_errors = Some(limit);
+    }
+
+    /// Generate `target` code instead of NASM assembly.
+    pub fn set_target(&mut self, target: Target) {
+        self.target = target;
+    }
+
+    /// Optimize the quadruples at `level`, 0 (the default) for none.
+    pub fn set_optimization_level(&mut self, level: u8) {
+        self.optimization_level = level;
+    }
+
+    /// Stop after `phase` instead of running the whole pipeline.
+    pub fn set_last_phase(&mut self, phase: Phase) {
+        self.last_phase = phase;
+    }
+
+    /// Print diagnostics as `format`, see [`OutputFormat`].
+    pub fn set_output_format(&mut self, format: OutputFormat) {
+        self.output_format = format;
+    }
+
+    /// Also write the quadruples as three-address text to `path`, or to
+    /// stdout when `path` is `-`.
+    pub fn set_ir_output(&mut self, path: &Path) {
+        self.ir_output = Some(path.to_path_buf());
+    }
+
+    /// Follow each quadruple in the stage report and in the `set_ir_output`
+    /// text with the `line:column` of the source it was generated from.
+    pub fn set_verbose_ir(&mut self, verbose: bool) {
+        self.verbose_ir = verbose;
+    }
+
+    /// Also write the parsed program, with imports inlined, to `path` as `format`.
+    pub fn set_ast_output(&mut self, format: AstFormat, path: &Path) {
+        self.ast_output = Some((format, path.to_path_buf()));
+    }
+
+    /// Call `handler` with each error and warning as soon as a stage
+    /// reports it, including warnings hidden from the terminal output.
+    pub fn add_diagnostic_handler(&mut self, handler: Box<dyn Fn(Diagnostic)>) {
+        self.diagnostic_handlers.push(Rc::from(handler));
+    }
+
+    fn notify(&self, diagnostic: &Diagnostic) {
+        for handler in &self.diagnostic_handlers {
+            handler(diagnostic.clone());
+        }
+    }
+
+    fn new_analyzer(&self) -> SemanticAnalyzer {
+        let mut analyzer = SemanticAnalyzer::new(&self.source_code);
+        analyzer.set_allow_unsafe(self.allow_unsafe);
+        analyzer.set_warn_unused(self.warn_unused);
+        for handler in &self.diagnostic_handlers {
+            analyzer.add_diagnostic_handler(handler.clone());
+        }
+        analyzer
+    }
+
+    /// Compile and print the report of each stage, then write the assembly.
+    /// Returns the exit code on errors.
+    pub fn run(&mut self) -> Result<(), i32> {
+        let result = self.compile();
+        match self.output_format {
+            OutputFormat::Json => self.print_json(&result),
+            OutputFormat::Text if self.last_phase == Phase::Semantic => {
+                eprintln!("{}", self.summary(&result))
+            }
+            OutputFormat::Text => self.print_stages(&result),
+        }
+
+        self.write_outputs(&result)?;
+        if result.is_success() {
+            Ok(())
+        } else {
+            Err(1)
+        }
+    }
+
+    /// Whether `run()` prints the report of each stage, rather than only
+    /// JSON diagnostics or the `--check` summary.
+    fn prints_stages(&self) -> bool {
+        self.output_format == OutputFormat::Text && self.last_phase != Phase::Semantic
+    }
+
+    /// Counts of errors and shown warnings in `result`, e.g. `3 errors, 1 warning`.
+    /// Warnings hidden by `set_show_warnings` or `set_warning_codes` are not counted.
+    pub fn summary(&self, result: &CompilationResult) -> String {
+        let plural = |count: usize, noun: &str| match count {
+            1 => format!("1 {}", noun),
+            _ => format!("{} {}s", count, noun),
+        };
+        let warnings = result
+            .warnings()
+            .filter(|d| self.is_warning_shown(d.code.as_deref().unwrap_or("")))
+            .count();
+        format!(
+            "{}, {}",
+            plural(result.errors().count(), "error"),
+            plural(warnings, "warning")
+        )
+    }
+
+    /// Print the errors and shown warnings as a JSON array.
+    fn print_json(&self, result: &CompilationResult) {
+        let diagnostics: Vec<serde_json::Value> = result
+            .diagnostics
+            .iter()
+            .filter(|d| d.is_error() || self.is_warning_shown(d.code.as_deref().unwrap_or("")))
+            .map(|d| d.to_json(Some(&self.source_code)))
+            .collect();
+        println!("{}", serde_json::Value::Array(diagnostics));
+    }
+
+    /// Run the whole pipeline silently and collect its diagnostics and output.
+    /// Stops after the first stage that reports errors.
+    pub fn compile(&mut self) -> CompilationResult {
+        let mut result = CompilationResult::default();
+        self.stages = StageOutput::default();
+
+        let (tokens, lexical_errors) = tokenize_with(&self.source_code, self.relaxed_identifiers);
+        if !lexical_errors.is_empty() {
+            self.fail(&mut result, Stage::Lexical, &lexical_errors);
+            return result;
+        }
+
+        let parsed = parse(tokens.clone(), &self.source_code);
+        self.stages.tokens = tokens;
+        let program = match parsed {
+            Ok(program) => program,
+            Err(parse_error) => {
+                self.fail(&mut result, Stage::Syntax, &[parse_error]);
+                return result;
+            }
+        };
+
+        self.stages.had_imports = program
+            .declarations
+            .iter()
+            .any(|declaration| matches!(declaration.node, DeclarationKind::Import(_)));
+        self.stages.program = Some(program.clone());
+        let mut module_loader = ModuleLoader::new();
+        module_loader.set_relaxed_identifiers(self.relaxed_identifiers);
+        let program = match module_loader.resolve(
+            program,
+            &self.source_code,
+            Path::new(&self.file_path),
+        ) {
+            Ok(program) => program,
+            Err(module_error) => {
+                self.fail(&mut result, Stage::Module, &[module_error]);
+                return result;
+            }
+        };
+        self.stages.program = Some(program.clone());
+        if self.last_phase == Phase::Syntax {
+            return result;
+        }
+
+        let mut analyzer = self.new_analyzer();
+        analyzer.analyze(&program);
+        let errors = analyzer.get_errors();
+        result.diagnostics.extend(errors.iter().map(Diagnostic::error));
+        result.diagnostics.extend(
+            analyzer
+                .get_warnings()
+                .iter()
+                .map(|w| Diagnostic::new(Severity::Warning, w).with_code(w.code())),
+        );
+        self.stages.warnings = analyzer.get_warnings().clone();
+        if !errors.is_empty() {
+            // The analyzer already told the diagnostic handlers
+            self.stages.failure = Some((Stage::Semantic, self.render(&errors)));
+            return result;
+        }
+        self.stages.symbol_table = Some(analyzer.get_symbol_table().clone());
+        if self.last_phase == Phase::Semantic {
+            return result;
+        }
+
+        result.quadruples = CodeGenerator::new_with_optimization(self.optimization_level)
+            .with_source(&self.source_code)
+            .generate_code(&program);
+        if let Some(quadruples) = &result.quadruples {
+            let mut assembly_generator = AssemblyGenerator::new(quadruples.clone());
+            assembly_generator.set_profile(self.profile);
+            assembly_generator.set_target(self.target);
+            result.assembly = Some(assembly_generator.generate_to_string());
+        }
+
+        result
+    }
+
+    /// Record the errors of the stage that stopped the pipeline.
+    fn fail<E: ErrorReporter>(
+        &mut self,
+        result: &mut CompilationResult,
+        stage: Stage,
+        errors: &[E],
+    ) {
+        for error in errors {
+            let diagnostic = Diagnostic::error(error);
+            self.notify(&diagnostic);
+            result.diagnostics.push(diagnostic);
+        }
+        self.stages.failure = Some((stage, self.render(errors)));
+    }
+
+    /// Reports of `errors` against the source, for `print_stages`.
+    fn render<E: ErrorReporter>(&self, errors: &[E]) -> Vec<String> {
+        errors
+            .iter()
+            .map(|error| error.report(Some(&self.source_code)))
+            .collect()
+    }
+
+    /// The colored report of each stage the last `compile()` went through.
+    fn print_stages(&self, result: &CompilationResult) {
+        let failed = self.stages.failure.as_ref().map(|(stage, _)| *stage);
+        println!("Compiling file: {}", self.file_path);
+
+        println!("{}: ", "Lexical Analysis".bold().underline());
+        if failed == Some(Stage::Lexical) {
+            return self.print_failure();
+        }
+        self.print_tokens(&self.stages.tokens);
+        println!(
+            "{}",
+            "Lexical analysis completed successfully.".green().bold()
+        );
+
+        println!("\n{} :", "Syntax Analysis".bold().underline());
+        println!("{} :", "Parsing".bold().underline());
+        if failed == Some(Stage::Syntax) {
+            return self.print_failure();
+        }
+        if let Some(program) = &self.stages.program {
+            self.print_ast(program);
+        }
+        println!("{}", "Parsing completed successfully.".green().bold());
+
+        // Nothing is printed for programs without imports
+        if self.stages.had_imports {
+            println!("\n{}", "Module Loading:".bold().underline());
+            if failed == Some(Stage::Module) {
+                return self.print_failure();
+            }
+            println!("{}", "Imports resolved successfully.".green());
+        }
+        if self.last_phase == Phase::Syntax {
+            return;
+        }
+
+        println!("\n{}", "Semantic Analysis:".bold().underline());
+        self.report_warnings(&self.stages.warnings);
+        if failed == Some(Stage::Semantic) {
+            return self.print_failure();
+        }
+        println!("{}", "analysis completed successfully.".green());
+        if let Some(symbol_table) = &self.stages.symbol_table {
+            self.print_symbol_table(symbol_table);
+        }
+
+        println!("\n{}", "Code Generation:".bold().underline());
+        if let Some(quadruples) = &result.quadruples {
+            self.print_quadruples(quadruples);
+        }
+        println!("{}", "Code generation completed successfully.".green());
+    }
+
+    fn print_failure(&self) {
+        if let Some((stage, reports)) = &self.stages.failure {
+            println!("{}", stage.heading().red().bold());
+            ErrorReportFormatter::print_error_reports(reports, self.max_errors);
+        }
+    }
+
+    fn report_warnings(&self, warnings: &[SemanticWarning]) {
+        if warnings.is_empty() {
+            return;
+        }
+
+        let shown: Vec<&SemanticWarning> = warnings
+            .iter()
+            .filter(|warning| self.is_warning_shown(warning.code()))
+            .collect();
+
+        if !shown.is_empty() {
+            ErrorReportFormatter::print_warnings(&shown, Some(&self.source_code));
+        }
+
+        let hidden = warnings.len() - shown.len();
+        if hidden > 0 {
+            println!(
+                "{} {} warning(s) generated ({} hidden)",
+                "Warning:".yellow().bold(),
+                warnings.len(),
+                hidden
+            );
+        }
+    }
+
+    /// Whether warnings with `code` are printed, see `set_show_warnings`
+    /// and `set_warning_codes`.
+    fn is_warning_shown(&self, code: &str) -> bool {
+        self.show_warnings
+            && match &self.warning_codes {
+                Some(codes) => codes.iter().any(|shown| shown == code),
+                None => true,
+            }
+    }
+
+    /// Write the AST, IR and assembly files that were asked for, for the
+    /// stages that completed.
+    fn write_outputs(&self, result: &CompilationResult) -> Result<(), i32> {
+        let parsed = matches!(self.stages.failure, None | Some((Stage::Semantic, _)));
+        if let Some(program) = self.stages.program.as_ref().filter(|_| parsed) {
+            self.emit_ast(program)?;
+        }
+        if let Some(quadruples) = &result.quadruples {
+            self.emit_ir(quadruples)?;
+        }
+        if let Some(assembly) = &result.assembly {
+            self.write_assembly(assembly)?;
+        }
+        Ok(())
+    }
+
+    fn emit_ast(&self, program: &Program) -> Result<(), i32> {
+        let Some((format, path)) = &self.ast_output else {
+            return Ok(());
+        };
+
+        let ast = match format {
+            AstFormat::Json => ast_json(program),
+            AstFormat::Sexp => Ok(program.to_sexp()),
+        };
+        let result = ast.and_then(|ast| fs::write(path, ast).map_err(|e| e.to_string()));
+        match result {
+            Ok(()) => {
+                if self.prints_stages() {
+                    println!("{} {}", "AST written to".green(), path.display());
+                }
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("{} '{}': {}", "Error writing AST to".red().bold(), path.display(), e);
+                Err(1)
+            }
+        }
+    }
+
+    fn emit_ir(&self, quadruples: &QuadrupleProgram) -> Result<(), i32> {
+        let Some(path) = &self.ir_output else {
+            return Ok(());
+        };
+
+        let ir = if self.verbose_ir {
+            quadruples.pretty_print_with_locations()
+        } else {
+            quadruples.pretty_print()
+        };
+        if path.as_os_str() == "-" {
+            print!("{}", ir);
+            return Ok(());
+        }
+        match fs::write(path, ir) {
+            Ok(()) => {
+                if self.prints_stages() {
+                    println!("{} {}", "IR written to".green(), path.display());
+                }
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("{} '{}': {}", "Error writing IR to".red().bold(), path.display(), e);
+                Err(1)
+            }
+        }
+    }
+
+    fn write_assembly(&self, assembly: &str) -> Result<(), i32> {
+        if self.prints_stages() {
+            println!("\n{}", "Assembly Generation:".bold().underline());
+        }
+        match fs::write(&self.output_path, assembly) {
+            Ok(()) => {
+                if self.prints_stages() {
+                    println!(
+                        "{} {}",
+                        "Assembly written to".green(),
+                        self.output_path.display()
+                    );
+                }
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!(
+                    "{} '{}': {}",
+                    "Error writing assembly to".red().bold(),
+                    self.output_path.display(),
+                    e
+                );
+                Err(1)
+            }
+        }
+    }
+
+    fn print_quadruples(&self, quadruples: &QuadrupleProgram) {
+        println!("{}", "Generated Quadruples:".bold().underline());
+        // Print each quadruple with alternating background
+        for (i, quad) in quadruples.quadruples.iter().enumerate() {
+            let index_str = format!(" {:<4}", i);
+            let index = if i % 2 == 0 {
+                index_str.blue()
+            } else {
+                index_str.green()
+            };
+            match quad.source_location {
+                Some((line, column)) if self.verbose_ir => println!(
+                    "{}│ {}  {}",
+                    index,
+                    quad,
+                    format!("; {}:{}", line, column).dimmed()
+                ),
+                _ => println!("{}│ {}", index, quad),
+            }
+        }
+    }
+
+    fn print_tokens(&self, tokens: &[TokenWithMetaData]) {
+        println!("{}", "Tokens:".bold().underline());
+        for token_with_pos in tokens {
+            let token_name = format!("{:?}", token_with_pos.kind).green();
+            let token_value = token_with_pos.value.yellow();
+            let position = format!(
+                "Line {}, Col {}",
+                token_with_pos.line, token_with_pos.column
+            )
+            .blue();
+            let span = format!("{:?}", token_with_pos.span).magenta();
+
+            println!(
+                "{}  →  {}  {}  [span: {}]",
+                token_name, token_value, position, span
+            );
+        }
+    }
+
+    fn print_ast(&self, ast: &Program) {
+        println!("{}", "AST:".green());
+        ast.pretty_print();
+    }
+
+    fn print_symbol_table(&self, symbol_table: &SymbolTable) {
+        println!("\n{}", "Symbol Table:".bold().underline());
+        for symbol in symbol_table.get_all() {
+            let kind = match &symbol.kind {
+                SymbolKind::Variable => "Variable".cyan(),
+                SymbolKind::Constant => "Constant".yellow(),
+                SymbolKind::Array(size) => format!("Array[{}]", size).magenta(),
+            };
+
+            let value = match &symbol.value {
+                SymbolValue::Single(lit) => format!("{}", LiteralKind::format_literal(lit))
+                    .green()
+                    .to_string(),
+                SymbolValue::Array(values) => {
+                    if values.is_empty() {
+                        "[]".dimmed().to_string()
+                    } else {
+                        let elements: Vec<String> = values
+                            .iter()
+                            .map(|v| LiteralKind::format_literal(v))
+                            .collect();
+                        format!("[{}]", elements.join(", ")).green().to_string()
+                    }
+                }
+                SymbolValue::Assigned => "<assigned>".dimmed().to_string(),
+                SymbolValue::Uninitialized => "<uninitialized>".dimmed().to_string(),
+            };
+
+            println!(
+                "{} {} {} = {} (line {}, col {})",
+                kind,
+                symbol.name.white(),
+                format!("({})", symbol.symbol_type).blue(),
+                value,
+                symbol.line,
+                symbol.column
+            );
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+fn ast_json(program: &Program) -> Result<String, String> {
+    serde_json::to_string_pretty(program).map_err(|e| e.to_string())
+}
+
+#[cfg(not(feature = "serde"))]
+fn ast_json(_program: &Program) -> Result<String, String> {
+    Err("JSON output needs msrc built with `--features serde`".to_string())
+}
@@ -0,0 +1,363 @@
+//! Resolves `import "file.ms";` declarations.
+//!
+//! Every imported file is a complete MiniSoft program. Its declarations are
+//! inlined in place of the import and its statements run before the
+//! importing program's own, so the rest of the pipeline sees one program.
+
+use crate::error_reporter::{DEFAULT_TAB_WIDTH, ErrorReporter, format_code_context};
+use crate::parser::ast::{
+    Declaration, DeclarationKind, Expression, ExpressionKind, Name, Program, Span, Statement,
+    StatementKind,
+};
+use crate::parser::error::SyntaxError;
+use crate::parser::parser_core::parse_source_with;
+use crate::semantics::source_map::SourceMap;
+use colored::Colorize;
+use std::collections::HashSet;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+pub enum ModuleError {
+    /// The imported file could not be read
+    Io {
+        path: String,
+        message: String,
+        /// File containing the import
+        file: String,
+        line: usize,
+        column: usize,
+        source_line: Option<String>,
+    },
+    /// The imported file has lexical or syntax errors
+    Syntax {
+        path: String,
+        error: Box<SyntaxError>,
+    },
+    /// A module imports itself, directly or through other modules
+    CircularImport {
+        path: String,
+        file: String,
+        line: usize,
+        column: usize,
+        source_line: Option<String>,
+    },
+}
+
+impl ModuleError {
+    fn get_detailed_message(&self) -> String {
+        match self {
+            ModuleError::Io { path, message, .. } => {
+                format!("Cannot import '{}': {}", path, message)
+            }
+            ModuleError::Syntax { path, error } => {
+                format!(
+                    "Error in imported module '{}': {}",
+                    path,
+                    error.get_message()
+                )
+            }
+            ModuleError::CircularImport { path, .. } => {
+                format!("Circular import of '{}'", path)
+            }
+        }
+    }
+}
+
+impl ErrorReporter for ModuleError {
+    // Errors may point into an imported file, so the offending line is
+    // captured when the error is created instead of taken from `source_code`
+    fn report(&self, _source_code: Option<&str>) -> String {
+        let mut result = String::new();
+
+        result.push_str(&format!(
+            "{}: {}\n",
+            "Module Error".red().bold(),
+            self.get_detailed_message()
+        ));
+
+        let (line, column) = self.get_location_info();
+        let file = match self {
+            ModuleError::Io { file, .. } | ModuleError::CircularImport { file, .. } => file,
+            ModuleError::Syntax { path, .. } => path,
+        };
+        result.push_str(&format!(
+            "{} {} line {}, column {}\n",
+            "-->".blue(),
+            file,
+            line,
+            column
+        ));
+
+        let source_line = match self {
+            ModuleError::Io { source_line, .. }
+            | ModuleError::CircularImport { source_line, .. } => source_line.as_ref(),
+            ModuleError::Syntax { error, .. } => match error.as_ref() {
+                SyntaxError::InvalidToken { source_line, .. }
+                | SyntaxError::UnexpectedToken { source_line, .. }
+                | SyntaxError::ExtraToken { source_line, .. } => source_line.as_ref(),
+                _ => None,
+            },
+        };
+        if let Some(source_line) = source_line {
+            result.push_str(&format_code_context(source_line, column, 1, DEFAULT_TAB_WIDTH));
+        }
+
+        if let Some(suggestion) = self.get_suggestion() {
+            result.push_str(&format!("{}: {}\n", "Suggestion".cyan().bold(), suggestion));
+        }
+
+        result
+    }
+
+    fn get_suggestion(&self) -> Option<String> {
+        match self {
+            ModuleError::Io { .. } => {
+                Some("Check the file name. Imports are relative to the importing file".to_string())
+            }
+            ModuleError::Syntax { error, .. } => error.get_suggestion(),
+            ModuleError::CircularImport { .. } => {
+                Some("Remove one of the imports that form the cycle".to_string())
+            }
+        }
+    }
+
+    fn get_error_name(&self) -> String {
+        "Module Error".to_string()
+    }
+
+    fn get_message(&self) -> String {
+        self.get_detailed_message()
+    }
+
+    fn get_location_info(&self) -> (usize, usize) {
+        match self {
+            ModuleError::Io { line, column, .. } => (*line, *column),
+            ModuleError::Syntax { error, .. } => error.get_location_info(),
+            ModuleError::CircularImport { line, column, .. } => (*line, *column),
+        }
+    }
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.report(None))
+    }
+}
+
+impl std::error::Error for ModuleError {}
+
+/// Loads imported modules, each at most once.
+#[derive(Debug, Default)]
+pub struct ModuleLoader {
+    /// Modules already inlined, a second import of the same file is skipped
+    loaded: HashSet<PathBuf>,
+    /// Modules being resolved, used to detect circular imports
+    loading: Vec<PathBuf>,
+    /// Lex imported modules with the relaxed identifier rules
+    relaxed_identifiers: bool,
+}
+
+impl ModuleLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lex imported modules as `tokenize_with(source, relaxed)` does, so they
+    /// follow the same identifier rules as the importing program.
+    pub fn set_relaxed_identifiers(&mut self, relaxed: bool) {
+        self.relaxed_identifiers = relaxed;
+    }
+
+    /// Replaces the imports of `program`, parsed from `source` in `file`, with
+    /// the contents of the imported modules. Import paths are relative to the
+    /// directory of `file`.
+    ///
+    /// Imported code has no position in `source`, so its nodes take the span
+    /// of the import declaration: errors in it are reported at the import.
+    pub fn resolve(
+        &mut self,
+        program: Program,
+        source: &str,
+        file: &Path,
+    ) -> Result<Program, ModuleError> {
+        // In-memory sources have no file to canonicalize
+        let key = fs::canonicalize(file).ok();
+        if let Some(key) = &key {
+            self.loaded.insert(key.clone());
+            self.loading.push(key.clone());
+        }
+
+        let result = self.resolve_imports(program, source, file);
+
+        if key.is_some() {
+            self.loading.pop();
+        }
+        result
+    }
+
+    fn resolve_imports(
+        &mut self,
+        program: Program,
+        source: &str,
+        file: &Path,
+    ) -> Result<Program, ModuleError> {
+        let source_map = SourceMap::new(source);
+        let base_dir = file.parent().unwrap_or(Path::new(""));
+
+        let mut declarations = Vec::new();
+        let mut statements = Vec::new();
+
+        for declaration in program.declarations {
+            let DeclarationKind::Import(name) = &declaration.node else {
+                declarations.push(declaration);
+                continue;
+            };
+
+            let path = base_dir.join(name);
+            let (line, column) = source_map.get_line_column(&declaration.span);
+            let source_line = source.lines().nth(line - 1).map(String::from);
+            let importer = file.display().to_string();
+
+            let io_error = |e: std::io::Error| ModuleError::Io {
+                path: name.clone(),
+                message: e.to_string(),
+                file: importer.clone(),
+                line,
+                column,
+                source_line: source_line.clone(),
+            };
+
+            let canonical = fs::canonicalize(&path).map_err(io_error)?;
+            if self.loading.contains(&canonical) {
+                return Err(ModuleError::CircularImport {
+                    path: name.clone(),
+                    file: importer,
+                    line,
+                    column,
+                    source_line,
+                });
+            }
+            if self.loaded.contains(&canonical) {
+                continue;
+            }
+
+            let module_source = fs::read_to_string(&path).map_err(io_error)?;
+            let module = parse_source_with(&module_source, self.relaxed_identifiers).map_err(
+                |error| ModuleError::Syntax {
+                    path: path.display().to_string(),
+                    error: Box::new(error),
+                },
+            )?;
+            let module = self.resolve(module, &module_source, &path)?;
+
+            for mut imported in module.declarations {
+                relocate_declaration(&mut imported, &declaration.span);
+                declarations.push(imported);
+            }
+            for mut imported in module.statements {
+                relocate_statement(&mut imported, &declaration.span);
+                statements.push(imported);
+            }
+        }
+
+        statements.extend(program.statements);
+
+        Ok(Program {
+            name: program.name,
+            name_span: program.name_span,
+            declarations,
+            statements,
+        })
+    }
+}
+
+fn relocate_declaration(declaration: &mut Declaration, span: &Span) {
+    declaration.span = span.clone();
+    match &mut declaration.node {
+        DeclarationKind::Variable(names, _) | DeclarationKind::Array(names, _, _) => {
+            relocate_names(names, span)
+        }
+        DeclarationKind::VariableWithInit(names, _, value) => {
+            relocate_names(names, span);
+            relocate_expression(value, span);
+        }
+        DeclarationKind::ArrayWithInit(names, _, _, values) => {
+            relocate_names(names, span);
+            for value in values {
+                relocate_expression(value, span);
+            }
+        }
+        DeclarationKind::Constant(name, _, literal) => {
+            name.span = span.clone();
+            literal.span = span.clone();
+        }
+        DeclarationKind::Import(_) => {}
+    }
+}
+
+fn relocate_names(names: &mut [Name], span: &Span) {
+    for name in names {
+        name.span = span.clone();
+    }
+}
+
+fn relocate_statement(statement: &mut Statement, span: &Span) {
+    statement.span = span.clone();
+    match &mut statement.node {
+        StatementKind::Assignment(target, value) => {
+            relocate_expression(target, span);
+            relocate_expression(value, span);
+        }
+        StatementKind::IfThen(condition, body)
+        | StatementKind::DoWhile(body, condition)
+        | StatementKind::While(condition, body) => {
+            relocate_expression(condition, span);
+            relocate_block(body, span);
+        }
+        StatementKind::IfThenElse(condition, then_block, else_block) => {
+            relocate_expression(condition, span);
+            relocate_block(then_block, span);
+            else_block.span = span.clone();
+            relocate_block(&mut else_block.node, span);
+        }
+        StatementKind::For(var, from, to, step, body) => {
+            for expression in [var, from, to, step] {
+                relocate_expression(expression, span);
+            }
+            relocate_block(body, span);
+        }
+        StatementKind::Input(target) => relocate_expression(target, span),
+        StatementKind::Output(values) => {
+            for value in values {
+                relocate_expression(value, span);
+            }
+        }
+        StatementKind::Scope(body) => relocate_block(body, span),
+        StatementKind::Break
+        | StatementKind::Continue
+        | StatementKind::InlineAsm(_)
+        | StatementKind::Empty => {}
+    }
+}
+
+fn relocate_block(statements: &mut [Statement], span: &Span) {
+    for statement in statements {
+        relocate_statement(statement, span);
+    }
+}
+
+fn relocate_expression(expression: &mut Expression, span: &Span) {
+    expression.span = span.clone();
+    match &mut expression.node {
+        ExpressionKind::ArrayAccess(_, index) => relocate_expression(index, span),
+        ExpressionKind::Literal(literal) => literal.span = span.clone(),
+        ExpressionKind::BinaryOp(left, _, right) => {
+            relocate_expression(left, span);
+            relocate_expression(right, span);
+        }
+        ExpressionKind::UnaryOp(_, operand) => relocate_expression(operand, span),
+        ExpressionKind::Identifier(_) => {}
+    }
+}
@@ -0,0 +1,101 @@
+//! Interactive session started with `msrc --repl`.
+//!
+//! Every line is one declaration or statement. Declarations extend a symbol
+//! table kept for the whole session, statements are checked against it,
+//! compiled to quadruples and run right away by `QuadrupleInterpreter`.
+
+use crate::codegen::generator::CodeGenerator;
+use crate::codegen::interpreter::QuadrupleInterpreter;
+use crate::error_reporter::ErrorReporter;
+use crate::parser::parser_core::{parse_declaration, parse_statement};
+use crate::semantics::analyzer_core::SemanticAnalyzer;
+use std::io::{self, BufRead, Write};
+
+pub struct Repl {
+    analyzer: SemanticAnalyzer,
+    generator: CodeGenerator,
+    interpreter: QuadrupleInterpreter,
+}
+
+impl Default for Repl {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Repl {
+    pub fn new() -> Self {
+        Repl {
+            analyzer: SemanticAnalyzer::new(""),
+            generator: CodeGenerator::new(),
+            interpreter: QuadrupleInterpreter::new(),
+        }
+    }
+
+    /// Read and evaluate lines until `:quit` or the end of `input`.
+    pub fn run(&mut self, input: &mut dyn BufRead, output: &mut dyn Write) -> io::Result<()> {
+        loop {
+            write!(output, "> ")?;
+            output.flush()?;
+
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            match line.trim() {
+                ":quit" => return Ok(()),
+                "" => {}
+                line => self.eval(line, input, output)?,
+            }
+        }
+    }
+
+    /// Check, compile and run one declaration or statement. `input(x);`
+    /// reads its value from the next line of `input`.
+    pub fn eval(
+        &mut self,
+        line: &str,
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+    ) -> io::Result<()> {
+        self.analyzer.reset_source(line);
+        let start = self.generator.program.quadruples.len();
+
+        if matches!(line.split_whitespace().next(), Some("let" | "@define")) {
+            let declaration = match parse_declaration(line) {
+                Ok(declaration) => declaration,
+                Err(error) => return write!(output, "{}", error.report(Some(line))),
+            };
+            self.analyzer.analyze_declaration(&declaration);
+            if self.report_diagnostics(line, output)? {
+                self.generator.generate_declaration(&declaration);
+            }
+        } else {
+            let statement = match parse_statement(line) {
+                Ok(statement) => statement,
+                Err(error) => return write!(output, "{}", error.report(Some(line))),
+            };
+            self.analyzer.analyze_statement(&statement);
+            if self.report_diagnostics(line, output)? {
+                self.generator.generate_statement(&statement);
+            }
+        }
+
+        let quadruples = self.generator.program.quadruples[start..].to_vec();
+        if let Err(error) = self.interpreter.run(&quadruples, input, output) {
+            writeln!(output, "Runtime error: {}", error)?;
+        }
+        Ok(())
+    }
+
+    /// Print the diagnostics for `line`. Returns true when there are no errors.
+    fn report_diagnostics(&self, line: &str, output: &mut dyn Write) -> io::Result<bool> {
+        for warning in self.analyzer.get_warnings() {
+            write!(output, "{}", warning.report(Some(line)))?;
+        }
+        for error in self.analyzer.get_errors() {
+            write!(output, "{}", error.report(Some(line)))?;
+        }
+        Ok(self.analyzer.get_errors().is_empty())
+    }
+}
@@ -0,0 +1,75 @@
+use super::ErrorReporter;
+use std::rc::Rc;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+/// A compiler message flattened into plain data, independent of the stage
+/// that produced it. Used by the library API and editor integrations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Stage that reported it, e.g. "Syntax Error" or "Warning"
+    pub kind: String,
+    /// Stable code for warnings (`W0001`, ...)
+    pub code: Option<String>,
+    pub message: String,
+    pub line: usize,
+    pub column: usize,
+    pub suggestion: Option<String>,
+}
+
+/// Callback receiving each diagnostic as soon as it is produced.
+pub type DiagnosticHandler = Rc<dyn Fn(Diagnostic)>;
+
+impl Diagnostic {
+    pub fn new<E: ErrorReporter>(severity: Severity, reporter: &E) -> Self {
+        let (line, column) = reporter.get_location_info();
+        Diagnostic {
+            severity,
+            kind: reporter.get_error_name(),
+            code: None,
+            message: reporter.get_message(),
+            line,
+            column,
+            suggestion: reporter.get_suggestion(),
+        }
+    }
+
+    pub fn error<E: ErrorReporter>(reporter: &E) -> Self {
+        Self::new(Severity::Error, reporter)
+    }
+
+    pub fn with_code(mut self, code: &str) -> Self {
+        self.code = Some(code.to_string());
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// JSON object with the `type`, `message`, `line`, `column`, `suggestion`
+    /// and `source_snippet` (the source line, when known) of the diagnostic.
+    /// Warnings also have their `code`.
+    pub fn to_json(&self, source_code: Option<&str>) -> serde_json::Value {
+        let source_snippet = source_code
+            .and_then(|source| source.lines().nth(self.line.checked_sub(1)?))
+            .map(str::trim_end);
+        let mut json = serde_json::json!({
+            "type": self.kind,
+            "message": self.message,
+            "line": self.line,
+            "column": self.column,
+            "suggestion": self.suggestion,
+            "source_snippet": source_snippet,
+        });
+        if let Some(code) = &self.code {
+            json["code"] = serde_json::Value::from(code.as_str());
+        }
+        json
+    }
+}
@@ -1,5 +1,8 @@
+mod diagnostic;
 mod reporter;
 
+pub use diagnostic::{Diagnostic, DiagnosticHandler, Severity};
+
 pub use reporter::ErrorReporter;
-pub use reporter::format_code_context;
+pub use reporter::{DEFAULT_TAB_WIDTH, format_code_context};
 pub use reporter::ErrorReportFormatter;
\ No newline at end of file
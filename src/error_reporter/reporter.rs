@@ -1,27 +1,73 @@
+use super::Diagnostic;
 use colored::Colorize;
 
-pub fn format_code_context(source_line: &str, column: usize, token_length: usize) -> String {
+/// Tab stop used when rendering source lines in reports
+pub const DEFAULT_TAB_WIDTH: usize = 4;
+
+/// Renders `source_line` with a caret under `column` (1-based), expanding
+/// tabs to `tab_width` columns so the caret lines up in the terminal.
+pub fn format_code_context(
+    source_line: &str,
+    column: usize,
+    token_length: usize,
+    tab_width: usize,
+) -> String {
     let mut result: String = String::new();
-    
+    let tab_width = tab_width.max(1);
+
+    // Expand tabs, and find where `column` lands in the expanded line.
+    // Columns past the end of the line point just after it.
+    let offset = column.saturating_sub(1);
+    let mut expanded = String::with_capacity(source_line.len());
+    let mut pointer = None;
+    for (index, c) in source_line.char_indices() {
+        if pointer.is_none() && index >= offset {
+            pointer = Some(expanded.len());
+        }
+        if c == '\t' {
+            let spaces = tab_width - expanded.chars().count() % tab_width;
+            expanded.push_str(&" ".repeat(spaces));
+        } else {
+            expanded.push(c);
+        }
+    }
+    let pointer = match pointer {
+        Some(byte_index) => expanded[..byte_index].chars().count(),
+        None => expanded.chars().count(),
+    };
+
     // Source code line
-    result.push_str(&format!("{}{}\n", " | ".blue(), source_line));
-    
-    // Error indicator pointing to the exact column
-    let mut pointer = String::new();
-    for _ in 0..column.saturating_sub(1) {
-        pointer.push(' ');
-    }
-    
+    result.push_str(&format!("{}{}\n", " | ".blue(), expanded));
+
     // Create underline of appropriate length for the token
     let length = token_length.max(1);
     let mut underline = "^".to_string();
     for _ in 1..length {
         underline.push('~');
     }
-    
-    result.push_str(&format!(" | {}{}\n", " ".repeat(column.saturating_sub(1)), 
+
+    result.push_str(&format!(" | {}{}\n", " ".repeat(pointer),
                             underline.bright_red().bold()));
-    
+
+    result
+}
+
+/// Remove ANSI color escape sequences from `text`.
+pub fn strip_ansi_codes(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip until the final letter of the escape sequence
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
     result
 }
 
@@ -30,6 +76,24 @@ pub trait ErrorReporter {
     fn get_suggestion(&self) -> Option<String>;
     fn get_error_name(&self) -> String;
     fn get_location_info(&self) -> (usize, usize);
+
+    /// Machine-readable form of the error for editors, see [`Diagnostic::to_json`].
+    fn to_json(&self, source_code: Option<&str>) -> serde_json::Value
+    where
+        Self: Sized,
+    {
+        Diagnostic::error(self).to_json(source_code)
+    }
+
+    /// One-line description without the error name, location or colors.
+    fn get_message(&self) -> String {
+        let report = strip_ansi_codes(&self.report(None));
+        let first_line = report.lines().next().unwrap_or_default();
+        match first_line.split_once(": ") {
+            Some((_, message)) => message.to_string(),
+            None => first_line.to_string(),
+        }
+    }
 }
 
 pub struct ErrorReportFormatter;
@@ -37,18 +101,46 @@ pub struct ErrorReportFormatter;
 impl ErrorReportFormatter {
 
     pub fn print_errors<E: ErrorReporter>(errors: &[E], source_code: Option<&str>) {
+        let reports: Vec<String> = errors.iter().map(|e| e.report(source_code)).collect();
+        Self::print_error_reports(&reports, None);
+    }
+
+    /// Prints errors already rendered with [`ErrorReporter::report`]. With a
+    /// `limit`, only the first `limit` are printed, followed by how many more
+    /// there were.
+    pub fn print_error_reports(reports: &[String], limit: Option<usize>) {
         println!("{} {} error(s) found\n", 
             "Error:".red().bold(), 
-            errors.len());
+            reports.len());
         
-        for (_i, error) in errors.iter().enumerate() {
-            
-            let report = error.report(source_code);
+        let shown = limit.unwrap_or(reports.len()).min(reports.len());
+        for report in &reports[..shown] {
             for line in report.lines() {
                 println!("      {}", line);
             }
             
             println!(); // Add spacing between errors
         }
+
+        let hidden = reports.len() - shown;
+        if hidden > 0 {
+            let noun = if hidden == 1 { "error" } else { "errors" };
+            println!("      ...and {} more {}\n", hidden, noun);
+        }
+    }
+
+    pub fn print_warnings<E: ErrorReporter>(warnings: &[&E], source_code: Option<&str>) {
+        println!("{} {} warning(s) found\n",
+            "Warning:".yellow().bold(),
+            warnings.len());
+
+        for warning in warnings {
+            let report = warning.report(source_code);
+            for line in report.lines() {
+                println!("      {}", line);
+            }
+
+            println!(); // Add spacing between warnings
+        }
     }
 }
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::error_reporter::ErrorReporter;
-use crate::error_reporter::format_code_context;
+use crate::error_reporter::{DEFAULT_TAB_WIDTH, format_code_context};
 use crate::lexer::lexer_core::TokenWithMetaData;
 use colored::Colorize;
 use std::error::Error;
@@ -102,6 +102,7 @@ impl ErrorReporter for LexicalError {
                     line,
                     self.column,
                     self.invalid_token.len(),
+                    DEFAULT_TAB_WIDTH,
                 ));
             }
         }
@@ -122,9 +123,10 @@ impl ErrorReporter for LexicalError {
             LexicalErrorType::NonAsciiCharacters => {
                 Some("Use only ASCII characters in identifiers and strings".to_string())
             }
-            LexicalErrorType::IdentifierTooLong => {
-                Some("Identifiers must be 14 characters or less".to_string())
-            }
+            LexicalErrorType::IdentifierTooLong => Some(
+                "Identifiers must be 14 characters or less, or compile with --relaxed-identifiers"
+                    .to_string(),
+            ),
             LexicalErrorType::ConsecutiveUnderscores => {
                 let fixed = self.invalid_token.replace("__", "_");
                 Some(format!("Use single underscores: '{}'", fixed))
@@ -145,7 +147,7 @@ impl ErrorReporter for LexicalError {
                 ))
             }
             LexicalErrorType::InvalidIdentifier => Some(
-                "Identifiers must not contain uppercase letters after the first character"
+                "Only the first letter of an identifier may be uppercase, or compile with --relaxed-identifiers"
                     .to_string(),
             ),
             LexicalErrorType::IntegerOutOfRange => {
@@ -163,6 +165,10 @@ impl ErrorReporter for LexicalError {
         "Lexical Error".to_string()
     }
 
+    fn get_message(&self) -> String {
+        self.get_error_description()
+    }
+
     fn get_location_info(&self) -> (usize, usize) {
         (self.line, self.column)
     }
@@ -179,7 +185,7 @@ impl LexicalError {
                 format!("Non-ASCII characters in '{}'", self.invalid_token)
             }
             LexicalErrorType::IdentifierTooLong => format!(
-                "Identifier '{}' exceeds maximum length of 14 characters",
+                "Identifier '{}' exceeds 14 characters",
                 self.invalid_token
             ),
             LexicalErrorType::ConsecutiveUnderscores => format!(
@@ -189,9 +195,10 @@ impl LexicalError {
             LexicalErrorType::TrailingUnderscore => {
                 format!("Identifier '{}' ends with underscore", self.invalid_token)
             }
-            LexicalErrorType::InvalidIdentifier => {
-                format!("Invalid identifier '{}'", self.invalid_token)
-            }
+            LexicalErrorType::InvalidIdentifier => format!(
+                "Uppercase letter not allowed after position 1 in identifier '{}'",
+                self.invalid_token
+            ),
             LexicalErrorType::IdentifierStartsWithNumber => {
                 format!("Identifier '{}' starts with a number", self.invalid_token)
             }
@@ -14,41 +14,106 @@ pub struct TokenWithMetaData {
 }
 
 pub fn tokenize(source: &str) -> (Vec<TokenWithMetaData>, Vec<LexicalError>) {
-    let mut lexer = Token::lexer(source);
+    tokenize_with(source, false)
+}
+
+/// Like [`tokenize`], but identifiers may be longer than 14 characters and
+/// contain uppercase letters anywhere when `relaxed_identifiers` is set.
+pub fn tokenize_with(
+    source: &str,
+    relaxed_identifiers: bool,
+) -> (Vec<TokenWithMetaData>, Vec<LexicalError>) {
     let mut valid_tokens = Vec::new();
     let mut errors = Vec::new();
 
-    while let Some(valid_result) = lexer.next() {
-        let span = lexer.span();
-        let value = lexer.slice().to_string();
-        let (line, column) = get_position(&lexer, span.start);
-
-        match valid_result {
-            Ok(kind) => {
-                valid_tokens.push(TokenWithMetaData {
-                    kind,
-                    value,
-                    line,
-                    column,
-                    span,
-                });
-            }
-            Err(_) => {
-                let invalid_token = TokenWithMetaData {
-                    kind: Token::Error,
-                    value: value.clone(),
-                    line,
-                    column,
-                    span,
-                };
-                errors.push(LexicalError::new(invalid_token));
-            }
-        };
+    for token in LexerIter::new(source).relaxed_identifiers(relaxed_identifiers) {
+        match token {
+            Ok(token) => valid_tokens.push(token),
+            Err(error) => errors.push(error),
+        }
     }
 
     (valid_tokens, errors)
 }
 
+/// Whether `source` has comments. The lexer skips them, so they are the
+/// only non-whitespace text left between tokens.
+pub fn has_comments(source: &str) -> bool {
+    let mut lexer = Token::lexer(source);
+    let mut previous_end = 0;
+    while lexer.next().is_some() {
+        let span = lexer.span();
+        if !source[previous_end..span.start].trim().is_empty() {
+            return true;
+        }
+        previous_end = span.end;
+    }
+    !source[previous_end..].trim().is_empty()
+}
+
+/// Lexes `source` one token at a time, for callers that do not need the
+/// whole token list at once.
+///
+/// # Examples
+///
+/// ```
+/// use rust_compiler::lexer::lexer_core::LexerIter;
+/// use rust_compiler::parser::parser_core::parse;
+///
+/// let source = "MainPrgm demo; Var let x: Int; BeginPg { x := 1; } EndPg;";
+/// let mut errors = Vec::new();
+/// let tokens = LexerIter::new(source).filter_map(|token| token.map_err(|e| errors.push(e)).ok());
+/// let program = parse(tokens, source).unwrap();
+/// assert!(errors.is_empty());
+/// assert_eq!(program.name, "demo");
+/// ```
+pub struct LexerIter<'src> {
+    lexer: Lexer<'src, Token>,
+}
+
+impl<'src> LexerIter<'src> {
+    pub fn new(source: &'src str) -> Self {
+        LexerIter {
+            lexer: Token::lexer(source),
+        }
+    }
+
+    /// Accept identifiers of any length with uppercase letters anywhere, see
+    /// [`tokenize_with`]
+    pub fn relaxed_identifiers(mut self, relaxed: bool) -> Self {
+        self.lexer.extras.relaxed_identifiers = relaxed;
+        self
+    }
+}
+
+impl Iterator for LexerIter<'_> {
+    type Item = Result<TokenWithMetaData, LexicalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let result = self.lexer.next()?;
+        let span = self.lexer.span();
+        let value = self.lexer.slice().to_string();
+        let (line, column) = get_position(&self.lexer, span.start);
+
+        Some(match result {
+            Ok(kind) => Ok(TokenWithMetaData {
+                kind,
+                value,
+                line,
+                column,
+                span,
+            }),
+            Err(_) => Err(LexicalError::new(TokenWithMetaData {
+                kind: Token::Error,
+                value,
+                line,
+                column,
+                span,
+            })),
+        })
+    }
+}
+
 fn get_position(lexer: &Lexer<Token>, byte_offset: usize) -> (usize, usize) {
     let line = lexer.extras.line_number;
     let col = byte_offset - lexer.extras.line_start + 1;
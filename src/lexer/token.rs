@@ -22,6 +22,8 @@ pub enum Token {
     Int,
     #[token("Float")]
     Float,
+    #[token("String")]
+    String,
 
     // Control flow
     #[token("if")]
@@ -42,6 +44,10 @@ pub enum Token {
     To,
     #[token("step")]
     Step,
+    #[token("break")]
+    Break,
+    #[token("continue")]
+    Continue,
 
     // I/O operations
     #[token("input")]
@@ -53,6 +59,14 @@ pub enum Token {
     #[token("Const")]
     Const,
 
+    // Modules
+    #[token("import")]
+    Import,
+
+    // Inline assembly
+    #[token("asm")]
+    Asm,
+
     // Punctuation and symbols
     #[token(";")]
     Semicolon,
@@ -88,6 +102,8 @@ pub enum Token {
     Multiply,
     #[token("/")]
     Divide,
+    #[token("%")]
+    Modulo,
 
     // Comparison operators
     #[token(">")]
@@ -112,6 +128,15 @@ pub enum Token {
     Not,
 
     // Identifiers
+    /// A MiniSoft identifier such as `x`, `total_2` or `Counter`:
+    /// - starts with an ASCII letter, which may be upper or lower case
+    /// - continues with lowercase letters, digits and underscores
+    /// - is at most 14 characters long
+    /// - has no two consecutive underscores and does not end with one
+    ///
+    /// Words that break one of the rules, like `invalidIdentifier`, are not
+    /// split into shorter identifiers: the whole word is a lexical error.
+    /// With [`Line::relaxed_identifiers`] the case and length rules are lifted.
     #[regex("[a-zA-Z][a-zA-Z0-9_]*", parse_identifier)]
     Identifier(String),
 
@@ -145,6 +170,131 @@ impl fmt::Display for Token {
     }
 }
 
+impl Token {
+    /// Human-readable name used in error messages, e.g. `'MainPrgm' keyword`
+    /// or `identifier`.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Token::MainPrgm => "'MainPrgm' keyword",
+            Token::Var => "'Var' keyword",
+            Token::BeginPg => "'BeginPg' keyword",
+            Token::EndPg => "'EndPg' keyword",
+            Token::Let => "'let' keyword",
+            Token::Int => "'Int' keyword",
+            Token::Float => "'Float' keyword",
+            Token::String => "'String' keyword",
+            Token::If => "'if' keyword",
+            Token::Then => "'then' keyword",
+            Token::Else => "'else' keyword",
+            Token::While => "'while' keyword",
+            Token::For => "'for' keyword",
+            Token::Do => "'do' keyword",
+            Token::From => "'from' keyword",
+            Token::To => "'to' keyword",
+            Token::Step => "'step' keyword",
+            Token::Break => "'break' keyword",
+            Token::Continue => "'continue' keyword",
+            Token::Input => "'input' keyword",
+            Token::Output => "'output' keyword",
+            Token::Define => "'@define' keyword",
+            Token::Const => "'Const' keyword",
+            Token::Import => "'import' keyword",
+            Token::Asm => "'asm' keyword",
+            Token::Semicolon => "';'",
+            Token::Comma => "','",
+            Token::Colon => "':'",
+            Token::OpenBracket => "'['",
+            Token::CloseBracket => "']'",
+            Token::OpenBrace => "'{'",
+            Token::CloseBrace => "'}'",
+            Token::OpenParen => "'('",
+            Token::CloseParen => "')'",
+            Token::Assign => "':='",
+            Token::Equals => "'='",
+            Token::Plus => "'+'",
+            Token::Minus => "'-'",
+            Token::Multiply => "'*'",
+            Token::Divide => "'/'",
+            Token::Modulo => "'%'",
+            Token::GreaterThan => "'>'",
+            Token::LessThan => "'<'",
+            Token::GreaterEqual => "'>='",
+            Token::LessEqual => "'<='",
+            Token::Equal => "'=='",
+            Token::NotEqual => "'!='",
+            Token::And => "'AND'",
+            Token::Or => "'OR'",
+            Token::Not => "'!'",
+            Token::Identifier(_) => "identifier",
+            Token::IntLiteral(_) => "integer literal",
+            Token::FloatLiteral(_) => "float literal",
+            Token::StringLiteral(_) => "string literal",
+            Token::Comment => "comment",
+            Token::Error => "invalid token",
+        }
+    }
+
+    /// Whether the token is a reserved word such as `MainPrgm` or `while`.
+    pub fn is_keyword(&self) -> bool {
+        matches!(
+            self,
+            Token::MainPrgm
+                | Token::Var
+                | Token::BeginPg
+                | Token::EndPg
+                | Token::Let
+                | Token::Int
+                | Token::Float
+                | Token::String
+                | Token::If
+                | Token::Then
+                | Token::Else
+                | Token::While
+                | Token::For
+                | Token::Do
+                | Token::From
+                | Token::To
+                | Token::Step
+                | Token::Break
+                | Token::Continue
+                | Token::Input
+                | Token::Output
+                | Token::Define
+                | Token::Const
+                | Token::Import
+                | Token::Asm
+        )
+    }
+
+    /// The keyword spelled `text`, e.g. `Token::Int` for `"Int"`.
+    pub fn keyword(text: &str) -> Option<Token> {
+        let mut lexer = Token::lexer(text);
+        match (lexer.next(), lexer.next()) {
+            (Some(Ok(token)), None) if token.is_keyword() => Some(token),
+            _ => None,
+        }
+    }
+
+    /// Maps a terminal name from `grammar.lalrpop` (`"\"MainPrgm\""`, `Idf`, ...)
+    /// back to a token of that kind.
+    pub fn from_terminal(terminal: &str) -> Option<Token> {
+        match terminal {
+            "Int" => Some(Token::IntLiteral(0)),
+            "Float" => Some(Token::FloatLiteral(0.0)),
+            "String" => Some(Token::StringLiteral(String::new())),
+            "Idf" => Some(Token::Identifier(String::new())),
+            quoted => {
+                let text = quoted.strip_prefix('"')?.strip_suffix('"')?;
+                let mut lexer = Token::lexer(text);
+                match (lexer.next(), lexer.next()) {
+                    (Some(Ok(token)), None) => Some(token),
+                    _ => None,
+                }
+            }
+        }
+    }
+}
+
 fn parse_int_literal(lex: &mut logos::Lexer<Token>) -> Option<i32> {
     let s = lex.slice();
     let parsed = if s.starts_with('(') {
@@ -171,12 +321,15 @@ fn parse_string_literal(lex: &mut logos::Lexer<Token>) -> Option<String> {
     Some(s[1..s.len() - 1].to_string())
 }
 
+/// Applies the identifier rules the regex cannot express, see [`Token::Identifier`]
 fn parse_identifier(lex: &mut logos::Lexer<Token>) -> Option<String> {
     let s = lex.slice();
     // Check if identifier contains uppercase letters (after the first character)
     let has_uppercase_after_first = s.chars().skip(1).any(|c| c.is_ascii_uppercase());
+    let follows_convention =
+        lex.extras.relaxed_identifiers || (s.len() <= 14 && !has_uppercase_after_first);
 
-    if s.len() <= 14 && !s.contains("__") && !s.ends_with("_") && !has_uppercase_after_first {
+    if follows_convention && !s.contains("__") && !s.ends_with("_") {
         Some(s.to_string())
     } else {
         None
@@ -186,6 +339,8 @@ fn parse_identifier(lex: &mut logos::Lexer<Token>) -> Option<String> {
 pub struct Line {
     pub line_number: usize,
     pub line_start: usize,
+    /// Accept identifiers of any length with uppercase letters anywhere
+    pub relaxed_identifiers: bool,
 }
 
 impl Default for Line {
@@ -193,6 +348,7 @@ impl Default for Line {
         Line {
             line_number: 1,
             line_start: 0,
+            relaxed_identifiers: false,
         }
     }
 }
@@ -1,6 +1,37 @@
+//! Compiler for the MiniSoft language.
+//!
+//! The simplest entry points are [`compile_source`] and [`compile_file`],
+//! which run the whole pipeline without printing and return a
+//! [`CompilationResult`] with every diagnostic and the generated code:
+//!
+//! ```
+//! let result = rust_compiler::compile_source(
+//!     "MainPrgm demo; Var let x: Int; BeginPg { x := 1; } EndPg;",
+//! );
+//! assert!(result.is_success());
+//! assert!(result.assembly.unwrap().contains("_start:"));
+//! ```
+//!
+//! Errors are reported as [`Diagnostic`](error_reporter::Diagnostic)s:
+//!
+//! ```
+//! let result = rust_compiler::compile_source(
+//!     "MainPrgm demo; Var let x: Int; BeginPg { y := 1; } EndPg;",
+//! );
+//! assert!(!result.is_success());
+//! let error = result.errors().next().unwrap();
+//! assert_eq!(error.message, "Undeclared identifier 'y'");
+//! ```
+//!
+//! Use [`compiler::Compiler`] for the verbose, step-by-step command line output.
+
 pub mod codegen;
 pub mod compiler;
 pub mod error_reporter;
 pub mod lexer;
+#[cfg(feature = "lsp")]
+pub mod lsp;
 pub mod parser;
 pub mod semantics;
+
+pub use compiler::{compile_file, compile_source, CompilationResult};
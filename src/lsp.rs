@@ -0,0 +1,78 @@
+//! Compilation → LSP diagnostics pipeline for editor integration.
+//!
+//! This is not a language server on its own: it turns a document into the
+//! `textDocument/publishDiagnostics` notification a full server would send.
+
+use crate::compile_source;
+use crate::error_reporter::{Diagnostic, Severity};
+use lsp_types::notification::{Notification, PublishDiagnostics};
+use lsp_types::{
+    DiagnosticSeverity, NumberOrString, Position, PublishDiagnosticsParams, Range, Uri,
+};
+
+#[derive(Debug, Default)]
+pub struct MiniSoftLanguageServer;
+
+impl MiniSoftLanguageServer {
+    pub fn new() -> Self {
+        MiniSoftLanguageServer
+    }
+
+    /// Compile `source` and convert every diagnostic to its LSP form.
+    pub fn diagnostics(&self, source: &str) -> Vec<lsp_types::Diagnostic> {
+        compile_source(source)
+            .diagnostics
+            .iter()
+            .map(to_lsp_diagnostic)
+            .collect()
+    }
+
+    pub fn publish_diagnostics(
+        &self,
+        uri: Uri,
+        source: &str,
+        version: Option<i32>,
+    ) -> PublishDiagnosticsParams {
+        PublishDiagnosticsParams::new(uri, self.diagnostics(source), version)
+    }
+
+    /// The full JSON-RPC `textDocument/publishDiagnostics` notification.
+    pub fn publish_diagnostics_json(&self, uri: Uri, source: &str, version: Option<i32>) -> String {
+        let notification = serde_json::json!({
+            "jsonrpc": "2.0",
+            "method": PublishDiagnostics::METHOD,
+            "params": self.publish_diagnostics(uri, source, version),
+        });
+        notification.to_string()
+    }
+}
+
+/// Compiler positions are 1-based, LSP positions are 0-based. Diagnostics
+/// without a location (line 0) are pinned to the start of the document.
+fn to_lsp_diagnostic(diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+    let start = Position::new(
+        diagnostic.line.saturating_sub(1) as u32,
+        diagnostic.column.saturating_sub(1) as u32,
+    );
+    let end = Position::new(start.line, start.character + 1);
+
+    let severity = match diagnostic.severity {
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+    };
+
+    let message = match &diagnostic.suggestion {
+        Some(suggestion) => format!("{}\nSuggestion: {}", diagnostic.message, suggestion),
+        None => diagnostic.message.clone(),
+    };
+
+    lsp_types::Diagnostic::new(
+        Range::new(start, end),
+        Some(severity),
+        diagnostic.code.clone().map(NumberOrString::String),
+        Some("msrc".to_string()),
+        message,
+        None,
+        None,
+    )
+}
@@ -1,17 +1,37 @@
 use colored::*;
-use rust_compiler::compiler::Compiler;
+use rust_compiler::codegen::assembly_gen::Target;
+use rust_compiler::compiler::{AstFormat, Compiler, OutputFormat, Phase};
+use rust_compiler::compiler::repl::Repl;
+use rust_compiler::error_reporter::ErrorReporter;
+use rust_compiler::lexer::lexer_core::has_comments;
+use rust_compiler::parser::parser_core::format_source;
 use clap::{Arg, Command};
+use std::fs;
+use std::io;
+use std::path::Path;
 use std::process;
 
+/// Text printed by `--version`, including toolchain, targets and enabled features
+const LONG_VERSION: &str = concat!(
+    "1.0 (",
+    env!("MSRC_RUSTC_VERSION"),
+    ", targets: ",
+    "x86_64-linux",
+    ", features: ",
+    env!("MSRC_FEATURES"),
+    ")"
+);
+
 fn main() {
     let matches = Command::new("rust-compiler")
         .version("1.0")
+        .long_version(LONG_VERSION)
         .author("Your Name")
         .about("Compiles MiniSoft programming language")
         .arg(
             Arg::new("file")
                 .help("Input file to compile")
-                .required(true)
+                .required_unless_present("repl")
                 .index(1),
         )
         .arg(
@@ -21,23 +41,255 @@ fn main() {
                 .help("Enable verbose output")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("output")
+                .short('o')
+                .long("output")
+                .value_name("FILE")
+                .help("Write the generated assembly to FILE"),
+        )
+        .arg(
+            Arg::new("target")
+                .long("target")
+                .value_name("TARGET")
+                .help("Output NASM assembly (default), GNU assembler syntax (.s) or LLVM IR (.ll)")
+                .value_parser(["nasm", "gas", "llvm-ir"])
+                .default_value("nasm"),
+        )
+        .arg(
+            Arg::new("no-warnings")
+                .long("no-warnings")
+                .help("Do not print warnings (they are still counted)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("warnings")
+                .long("warnings")
+                .value_name("CODES")
+                .help("Only print warnings with these codes, e.g. --warnings=W0001,W0003")
+                .require_equals(true)
+                .value_delimiter(','),
+        )
+        .arg(
+            Arg::new("warn-unused")
+                .long("warn-unused")
+                .help("Warn about variables and constants that are never read (W0005)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("check")
+                .long("check")
+                .help("Only check the file for errors, printing how many errors and warnings it has")
+                .conflicts_with("syntax-only")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("syntax-only")
+                .long("syntax-only")
+                .help("Only check that the file parses, skipping semantic analysis and code generation")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("format")
+                .long("format")
+                .help("Reformat the file in place instead of compiling it")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("dry-run")
+                .long("dry-run")
+                .help("With --format, print the formatted source instead of writing the file")
+                .requires("format")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("repl")
+                .long("repl")
+                .help("Start an interactive session instead of compiling a file")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("profile")
+                .long("profile")
+                .help("Make the program print the CPU cycles spent in each block to stderr (nasm and gas targets)")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("opt-level")
+                .short('O')
+                .long("opt-level")
+                .value_name("LEVEL")
+                .help("Optimize the generated code: 0 for none (default), 1 to fold constants and remove unreachable code and dead stores, 2 to also propagate constants through SSA form")
+                .value_parser(clap::value_parser!(u8).range(0..=2))
+                .default_value("0"),
+        )
+        .arg(
+            Arg::new("emit-ast")
+                .long("emit-ast")
+                .value_name("FORMAT")
+                .help("Also write the parsed program to <input>.ast.json (json, needs the serde feature) or <input>.ast.sexp (sexp)")
+                .value_parser(["json", "sexp"])
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value("json"),
+        )
+        .arg(
+            Arg::new("emit-ir")
+                .long("emit-ir")
+                .value_name("FILE")
+                .help("Also write the quadruples as three-address text to FILE (default: <input>.ir, - for stdout)")
+                .num_args(0..=1)
+                .require_equals(true)
+                .default_missing_value(""),
+        )
+        .arg(
+            Arg::new("verbose-ir")
+                .long("verbose-ir")
+                .help("Follow each printed quadruple with the line:column it was generated from")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("output-format")
+                .long("output-format")
+                .value_name("FORMAT")
+                .help("Report diagnostics as colored text (default) or as a JSON array on stdout")
+                .value_parser(["text", "json"])
+                .default_value("text"),
+        )
+        .arg(
+            Arg::new("optimize")
+                .long("optimize")
+                .help("Apply the standard optimizations, same as -O 1")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("allow-unsafe")
+                .long("allow-unsafe")
+                .help("Allow inline assembly with asm { \"...\"; }")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("relaxed-identifiers")
+                .long("relaxed-identifiers")
+                .help("Allow identifiers longer than 14 characters and with uppercase letters anywhere")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("max-errors")
+                .long("max-errors")
+                .value_name("N")
+                .help("Print only the first N errors of a stage (default: all)")
+                .value_parser(clap::value_parser!(usize)),
+        )
         .get_matches();
 
+    if matches.get_flag("repl") {
+        println!("MiniSoft REPL. Type :quit to exit.");
+        let stdin = io::stdin();
+        if let Err(error) = Repl::new().run(&mut stdin.lock(), &mut io::stdout()) {
+            eprintln!("{}: {}", "Error".red().bold(), error);
+            process::exit(1);
+        }
+        return;
+    }
+
     let file_path = matches.get_one::<String>("file").unwrap();
+    if matches.get_flag("format") {
+        format_file(file_path, matches.get_flag("dry-run"));
+        return;
+    }
     let verbose = matches.get_flag("verbose");
+    let output_path = matches.get_one::<String>("output");
+    let no_warnings = matches.get_flag("no-warnings");
+    let allow_unsafe = matches.get_flag("allow-unsafe");
+    let relaxed_identifiers = matches.get_flag("relaxed-identifiers");
+    let verbose_ir = matches.get_flag("verbose-ir");
+    let max_errors = matches.get_one::<usize>("max-errors").copied();
+    let warn_unused = matches.get_flag("warn-unused");
+    let profile = matches.get_flag("profile");
+    let syntax_only = matches.get_flag("syntax-only");
+    let check = matches.get_flag("check");
+    let optimization_level = if matches.get_flag("optimize") {
+        1
+    } else {
+        *matches.get_one::<u8>("opt-level").unwrap()
+    };
+    let json = matches.get_one::<String>("output-format").unwrap() == "json";
+    let target = match matches.get_one::<String>("target").unwrap().as_str() {
+        "gas" => Target::Gas,
+        "llvm-ir" => Target::LlvmIr,
+        _ => Target::Nasm,
+    };
+    if profile && target == Target::LlvmIr {
+        eprintln!(
+            "{}: --profile is only supported with the nasm and gas targets",
+            "Error".red().bold()
+        );
+        process::exit(1);
+    }
+    let ast_output = matches.get_one::<String>("emit-ast").map(|format| match format.as_str() {
+        "sexp" => (AstFormat::Sexp, Path::new(file_path).with_extension("ast.sexp")),
+        _ => (AstFormat::Json, Path::new(file_path).with_extension("ast.json")),
+    });
+    let ir_output = matches.get_one::<String>("emit-ir").map(|path| match path.as_str() {
+        "" => Path::new(file_path).with_extension("ir"),
+        path => Path::new(path).to_path_buf(),
+    });
+    let warning_codes: Option<Vec<String>> = matches
+        .get_many::<String>("warnings")
+        .map(|codes| codes.cloned().collect());
 
     match Compiler::new(file_path) {
         Ok(mut compiler) => {
             if verbose {
                 println!("{}", "Verbose mode enabled".yellow().bold());
             }
+            match output_path {
+                Some(output_path) => compiler.with_output(Path::new(output_path)),
+                None => {
+                    compiler.with_output(&Path::new(file_path).with_extension(target.extension()))
+                }
+            };
+            compiler.set_target(target);
+            compiler.set_show_warnings(!no_warnings);
+            compiler.set_allow_unsafe(allow_unsafe);
+            compiler.set_relaxed_identifiers(relaxed_identifiers);
+            compiler.set_verbose_ir(verbose_ir);
+            if let Some(max_errors) = max_errors {
+                compiler.set_max_errors(max_errors);
+            }
+            compiler.set_warn_unused(warn_unused);
+            compiler.set_profile(profile);
+            compiler.set_optimization_level(optimization_level);
+            if json {
+                compiler.set_output_format(OutputFormat::Json);
+            }
+            if let Some((format, path)) = ast_output {
+                compiler.set_ast_output(format, &path);
+            }
+            if let Some(ir_output) = ir_output {
+                compiler.set_ir_output(&ir_output);
+            }
+            if syntax_only {
+                compiler.set_last_phase(Phase::Syntax);
+            }
+            if check {
+                compiler.set_last_phase(Phase::Semantic);
+            }
+            if let Some(codes) = warning_codes {
+                compiler.set_warning_codes(codes);
+            }
             match compiler.run() {
                 Ok(_) => {
-                    println!("{}", "✓ Compilation successful!".green().bold());
+                    if !json && !check {
+                        println!("{}", "✓ Compilation successful!".green().bold());
+                    }
                     process::exit(0);
                 }
                 Err(exit_code) => {
-                    eprintln!("{}", "✗ Compilation failed".red().bold());
+                    if !json && !check {
+                        eprintln!("{}", "✗ Compilation failed".red().bold());
+                    }
                     process::exit(exit_code);
                 }
             }
@@ -47,4 +299,41 @@ fn main() {
             process::exit(1);
         }
     }
-}
\ No newline at end of file
+}
+
+/// Rewrites `file_path` in the canonical layout, or prints it with `dry_run`
+fn format_file(file_path: &str, dry_run: bool) {
+    let source = match fs::read_to_string(file_path) {
+        Ok(source) => source,
+        Err(error) => {
+            eprintln!("{}: {}", "Error".red().bold(), error);
+            process::exit(1);
+        }
+    };
+
+    let formatted = match format_source(&source) {
+        Ok(formatted) => formatted,
+        Err(error) => {
+            eprintln!("{}", error.report(Some(&source)));
+            process::exit(1);
+        }
+    };
+
+    if dry_run {
+        print!("{}", formatted);
+        return;
+    }
+    // The AST has no comments, so writing the file back would lose them
+    if has_comments(&source) {
+        eprintln!(
+            "{}: {} contains comments, which formatting would remove (use --dry-run to preview)",
+            "Error".red().bold(),
+            file_path
+        );
+        process::exit(1);
+    }
+    if let Err(error) = fs::write(file_path, formatted) {
+        eprintln!("{}: {}", "Error".red().bold(), error);
+        process::exit(1);
+    }
+}
@@ -1,9 +1,15 @@
+use std::hash::{Hash, Hasher};
+use std::mem;
 use std::ops::Range;
 
-#[derive(Debug, Clone, PartialEq)]
+/// Byte offsets of a node in its source file
+pub type Span = Range<usize>;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Located<T> {
     pub node: T,
-    pub span: Range<usize>,
+    pub span: Span,
 }
 
 impl<T> Located<T> {
@@ -12,26 +18,41 @@ impl<T> Located<T> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Program {
     pub name: String,
+    /// Where `name` is written after `MainPrgm`
+    pub name_span: Span,
     pub declarations: Vec<Declaration>,
     pub statements: Vec<Statement>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum DeclarationKind {
-    Variable(Vec<String>, Type),
-    Array(Vec<String>, Type, usize),
-    VariableWithInit(Vec<String>, Type, Expression),
-    ArrayWithInit(Vec<String>, Type, usize, Vec<Expression>),
-    Constant(String, Type, Literal),
+    Variable(Vec<Name>, Type),
+    Array(Vec<Name>, Type, usize),
+    VariableWithInit(Vec<Name>, Type, Expression),
+    ArrayWithInit(Vec<Name>, Type, usize, Vec<Expression>),
+    Constant(Name, Type, Literal),
+    /// `import "file.ms";`, resolved by `ModuleLoader` before analysis
+    Import(String),
 }
 
 pub type Declaration = Located<DeclarationKind>;
 
+/// A declared name, located where it is written
+pub type Name = Located<String>;
+
+/// The text of each name in `names`
+pub fn name_texts(names: &[Name]) -> Vec<&str> {
+    names.iter().map(|name| name.node.as_str()).collect()
+}
+
 /// Data types in MiniSoft
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Type {
     Int,
     Float,
@@ -73,22 +94,92 @@ impl std::fmt::Display for Type {
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum StatementKind {
     Assignment(Expression, Expression),
     IfThen(Expression, Vec<Statement>),
-    IfThenElse(Expression, Vec<Statement>, Vec<Statement>),
+    /// The else block keeps its own span, which an empty one is reported at
+    IfThenElse(Expression, Vec<Statement>, Located<Vec<Statement>>),
     DoWhile(Vec<Statement>, Expression),
+    /// Checks the condition before each iteration, including the first
+    While(Expression, Vec<Statement>),
     For(Expression, Expression, Expression, Expression, Vec<Statement>),
+    /// Leaves the innermost enclosing loop
+    Break,
+    /// Skips to the step (for) or condition (while, do-while) of the innermost loop
+    Continue,
     Input(Expression),
     Output(Vec<Expression>),
+    /// Assembly lines copied verbatim into the output
+    InlineAsm(Vec<String>),
     Scope(Vec<Statement>),
     Empty,
 }
 
 pub type Statement = Located<StatementKind>;
 
-#[derive(Debug, Clone, PartialEq)]
+/// Piece of an `output` format string such as `"x = %d"`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FormatSegment {
+    Text(String),
+    /// `%d` or `%f`, replaced by the next argument
+    Placeholder(Type),
+}
+
+/// Splits the format string of `output(values)` into text and placeholders.
+/// Returns `None` unless the first value is a string literal containing
+/// `%d`, `%f` or `%%`, in which case the other values fill the placeholders.
+/// `%%` is a literal `%` and takes no value.
+pub fn format_segments(values: &[Expression]) -> Option<Vec<FormatSegment>> {
+    let ExpressionKind::Literal(literal) = &values.first()?.node else {
+        return None;
+    };
+    let LiteralKind::String(format) = &literal.node else {
+        return None;
+    };
+
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut escaped = false;
+    let mut chars = format.chars().peekable();
+    while let Some(c) = chars.next() {
+        let placeholder = match (c, chars.peek()) {
+            ('%', Some('d')) => Type::Int,
+            ('%', Some('f')) => Type::Float,
+            ('%', Some('%')) => {
+                chars.next();
+                text.push('%');
+                escaped = true;
+                continue;
+            }
+            _ => {
+                text.push(c);
+                continue;
+            }
+        };
+        chars.next();
+        if !text.is_empty() {
+            segments.push(FormatSegment::Text(mem::take(&mut text)));
+        }
+        segments.push(FormatSegment::Placeholder(placeholder));
+    }
+
+    if !escaped
+        && !segments
+            .iter()
+            .any(|segment| matches!(segment, FormatSegment::Placeholder(_)))
+    {
+        return None;
+    }
+    if !text.is_empty() {
+        segments.push(FormatSegment::Text(text));
+    }
+    Some(segments)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ExpressionKind {
     Identifier(String),
     ArrayAccess(String, Box<Expression>),
@@ -99,9 +190,11 @@ pub enum ExpressionKind {
 
 pub type Expression = Located<ExpressionKind>;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LiteralKind {
     Int(i32),
+    #[cfg_attr(feature = "serde", serde(with = "float_literal"))]
     Float(f32),
     String(String),
 }
@@ -124,16 +217,44 @@ impl LiteralKind {
     }
 }
 
+/// Floats compare and hash by bit pattern so that literals (and the AST
+/// around them) can be `Eq`: `NaN == NaN`, but `0.0 != -0.0`.
+impl PartialEq for LiteralKind {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (LiteralKind::Int(l), LiteralKind::Int(r)) => l == r,
+            (LiteralKind::Float(l), LiteralKind::Float(r)) => l.to_bits() == r.to_bits(),
+            (LiteralKind::String(l), LiteralKind::String(r)) => l == r,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for LiteralKind {}
+
+impl Hash for LiteralKind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        mem::discriminant(self).hash(state);
+        match self {
+            LiteralKind::Int(value) => value.hash(state),
+            LiteralKind::Float(value) => value.to_bits().hash(state),
+            LiteralKind::String(value) => value.hash(state),
+        }
+    }
+}
+
 pub type Literal = Located<LiteralKind>;
 
 /// Binary operators
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operator {
     // Arithmetic
     Add,
     Subtract,
     Multiply,
     Divide,
+    Modulo,
 
     // Comparison
     GreaterThan,
@@ -148,9 +269,11 @@ pub enum Operator {
     Or,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum UnaryOperator {
     Not,
+    Negate,
 }
 
 impl LiteralKind {
@@ -162,4 +285,35 @@ impl LiteralKind {
             LiteralKind::String(_) => Type::String,
         }
     }
-}
\ No newline at end of file
+}
+
+/// JSON has no NaN or infinity, so non-finite floats are written as the
+/// strings `"NaN"`, `"inf"` and `"-inf"`.
+#[cfg(feature = "serde")]
+mod float_literal {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+    #[derive(Serialize, Deserialize)]
+    #[serde(untagged)]
+    enum FloatRepr {
+        Number(f32),
+        Special(String),
+    }
+
+    pub fn serialize<S: Serializer>(value: &f32, serializer: S) -> Result<S::Ok, S::Error> {
+        if value.is_finite() {
+            FloatRepr::Number(*value).serialize(serializer)
+        } else {
+            FloatRepr::Special(value.to_string()).serialize(serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f32, D::Error> {
+        match FloatRepr::deserialize(deserializer)? {
+            FloatRepr::Number(value) => Ok(value),
+            FloatRepr::Special(text) => text
+                .parse()
+                .map_err(|_| de::Error::custom(format!("invalid float literal '{}'", text))),
+        }
+    }
+}
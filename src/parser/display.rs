@@ -0,0 +1,269 @@
+//! Prints the AST back as MiniSoft source.
+//!
+//! The output re-parses to an equivalent AST. Parentheses are only emitted
+//! where the grammar's precedence requires them, and negative literals use
+//! the parenthesized `(-n)` form the lexer expects.
+
+use super::ast::{
+    Declaration, DeclarationKind, Expression, ExpressionKind, LiteralKind, Operator, Program,
+    Statement, StatementKind, UnaryOperator, name_texts,
+};
+use std::fmt;
+
+const INDENT: &str = "    ";
+
+// Precedence levels, lowest to highest, matching grammar.lalrpop
+const LOGICAL: u8 = 1;
+const UNARY: u8 = 2;
+const COMPARISON: u8 = 3;
+const ADDITIVE: u8 = 4;
+const MULTIPLICATIVE: u8 = 5;
+const NEGATE: u8 = 6;
+const PRIMARY: u8 = 7;
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "MainPrgm {};", self.name)?;
+        writeln!(f, "Var")?;
+        // Types start in the same column, e.g. `let a, b:   Int;` above `let values: [Int; 3];`
+        let width = self
+            .declarations
+            .iter()
+            .filter_map(|decl| declaration_head(&decl.node))
+            .map(|head| head.len())
+            .max()
+            .unwrap_or(0);
+        for decl in &self.declarations {
+            write!(f, "{}", INDENT)?;
+            write_declaration(f, &decl.node, width)?;
+            writeln!(f)?;
+        }
+        writeln!(f, "BeginPg")?;
+        write_block(f, &self.statements, 0)?;
+        writeln!(f)?;
+        writeln!(f, "EndPg;")
+    }
+}
+
+impl fmt::Display for Declaration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_declaration(f, &self.node, 0)
+    }
+}
+
+/// The text before the type's colon, `None` for declarations without a type
+fn declaration_head(decl: &DeclarationKind) -> Option<String> {
+    match decl {
+        DeclarationKind::Variable(names, _)
+        | DeclarationKind::Array(names, _, _)
+        | DeclarationKind::VariableWithInit(names, _, _)
+        | DeclarationKind::ArrayWithInit(names, _, _, _) => {
+            Some(format!("let {}", name_texts(names).join(", ")))
+        }
+        DeclarationKind::Constant(name, _, _) => Some(format!("@define Const {}", name.node)),
+        DeclarationKind::Import(_) => None,
+    }
+}
+
+/// Writes `decl`, padding after the colon so the type starts `width` characters in
+fn write_declaration(f: &mut fmt::Formatter<'_>, decl: &DeclarationKind, width: usize) -> fmt::Result {
+    if let DeclarationKind::Import(path) = decl {
+        return write!(f, "import \"{}\";", path);
+    }
+    let head = declaration_head(decl).unwrap_or_default();
+    write!(f, "{}:{} ", head, " ".repeat(width.saturating_sub(head.len())))?;
+
+    match decl {
+        DeclarationKind::Variable(_, ty) => write!(f, "{};", ty),
+        DeclarationKind::Array(_, ty, size) => write!(f, "[{}; {}];", ty, size),
+        DeclarationKind::VariableWithInit(_, ty, expr) => write!(f, "{} = {};", ty, expr),
+        DeclarationKind::ArrayWithInit(_, ty, size, values) => {
+            write!(f, "[{}; {}] = {{{}}};", ty, size, join(values))
+        }
+        DeclarationKind::Constant(_, ty, literal) => {
+            write!(f, "{} = ", ty)?;
+            write_literal(f, &literal.node)?;
+            write!(f, ";")
+        }
+        DeclarationKind::Import(_) => Ok(()),
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_statement(f, self, 0)
+    }
+}
+
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_expression(f, self, LOGICAL)
+    }
+}
+
+fn write_block(f: &mut fmt::Formatter<'_>, statements: &[Statement], indent: usize) -> fmt::Result {
+    if statements.is_empty() {
+        return write!(f, "{{ }}");
+    }
+
+    writeln!(f, "{{")?;
+    for stmt in statements {
+        write_statement(f, stmt, indent + 1)?;
+    }
+    write!(f, "{}}}", INDENT.repeat(indent))
+}
+
+fn write_statement(f: &mut fmt::Formatter<'_>, stmt: &Statement, indent: usize) -> fmt::Result {
+    let pad = INDENT.repeat(indent);
+
+    match &stmt.node {
+        StatementKind::Assignment(target, value) => {
+            writeln!(f, "{}{} := {};", pad, target, value)
+        }
+        StatementKind::IfThen(condition, then_block) => {
+            write!(f, "{}if ({}) then ", pad, condition)?;
+            write_block(f, then_block, indent)?;
+            writeln!(f)
+        }
+        StatementKind::IfThenElse(condition, then_block, else_block) => {
+            write!(f, "{}if ({}) then ", pad, condition)?;
+            write_block(f, then_block, indent)?;
+            write!(f, " else ")?;
+            write_block(f, &else_block.node, indent)?;
+            writeln!(f)
+        }
+        StatementKind::DoWhile(body, condition) => {
+            write!(f, "{}do ", pad)?;
+            write_block(f, body, indent)?;
+            writeln!(f, " while ({});", condition)
+        }
+        StatementKind::While(condition, body) => {
+            write!(f, "{}while ({}) ", pad, condition)?;
+            write_block(f, body, indent)?;
+            writeln!(f)
+        }
+        StatementKind::For(var, from, to, step, body) => {
+            write!(
+                f,
+                "{}for {} from {} to {} step {} ",
+                pad, var, from, to, step
+            )?;
+            write_block(f, body, indent)?;
+            writeln!(f)
+        }
+        StatementKind::Break => writeln!(f, "{}break;", pad),
+        StatementKind::Continue => writeln!(f, "{}continue;", pad),
+        StatementKind::Input(target) => writeln!(f, "{}input({});", pad, target),
+        StatementKind::Output(values) => writeln!(f, "{}output({});", pad, join(values)),
+        StatementKind::InlineAsm(lines) => {
+            write!(f, "{}asm {{", pad)?;
+            for line in lines {
+                write!(f, " \"{}\";", line)?;
+            }
+            writeln!(f, " }}")
+        }
+        // The grammar has no nested block statement, so its contents are inlined
+        StatementKind::Scope(statements) => {
+            for stmt in statements {
+                write_statement(f, stmt, indent)?;
+            }
+            Ok(())
+        }
+        StatementKind::Empty => writeln!(f, "{};", pad),
+    }
+}
+
+fn write_expression(f: &mut fmt::Formatter<'_>, expr: &Expression, min_level: u8) -> fmt::Result {
+    let level = expression_level(&expr.node);
+    if level < min_level {
+        write!(f, "(")?;
+        write_expression(f, expr, LOGICAL)?;
+        return write!(f, ")");
+    }
+
+    match &expr.node {
+        ExpressionKind::Identifier(name) => write!(f, "{}", name),
+        ExpressionKind::ArrayAccess(name, index) => write!(f, "{}[{}]", name, index),
+        ExpressionKind::Literal(literal) => write_literal(f, &literal.node),
+        ExpressionKind::BinaryOp(left, op, right) => {
+            // Operators are left-associative: the right operand binds one level tighter
+            write_expression(f, left, level)?;
+            write!(f, " {} ", operator_symbol(op))?;
+            write_expression(f, right, level + 1)
+        }
+        ExpressionKind::UnaryOp(UnaryOperator::Not, operand) => {
+            // `!a > b` already means `!(a > b)`, the parentheses are for readers
+            write!(f, "!")?;
+            write_expression(f, operand, PRIMARY)
+        }
+        ExpressionKind::UnaryOp(UnaryOperator::Negate, operand) => {
+            write!(f, "-")?;
+            write_expression(f, operand, NEGATE)
+        }
+    }
+}
+
+fn expression_level(expr: &ExpressionKind) -> u8 {
+    match expr {
+        ExpressionKind::BinaryOp(_, op, _) => match op {
+            Operator::And | Operator::Or => LOGICAL,
+            Operator::Equal
+            | Operator::NotEqual
+            | Operator::LessThan
+            | Operator::GreaterThan
+            | Operator::LessEqual
+            | Operator::GreaterEqual => COMPARISON,
+            Operator::Add | Operator::Subtract => ADDITIVE,
+            Operator::Multiply | Operator::Divide | Operator::Modulo => MULTIPLICATIVE,
+        },
+        ExpressionKind::UnaryOp(UnaryOperator::Not, _) => UNARY,
+        ExpressionKind::UnaryOp(UnaryOperator::Negate, _) => NEGATE,
+        _ => PRIMARY,
+    }
+}
+
+pub(super) fn operator_symbol(op: &Operator) -> &'static str {
+    match op {
+        Operator::Add => "+",
+        Operator::Subtract => "-",
+        Operator::Multiply => "*",
+        Operator::Divide => "/",
+        Operator::Modulo => "%",
+        Operator::GreaterThan => ">",
+        Operator::LessThan => "<",
+        Operator::GreaterEqual => ">=",
+        Operator::LessEqual => "<=",
+        Operator::Equal => "==",
+        Operator::NotEqual => "!=",
+        Operator::And => "AND",
+        Operator::Or => "OR",
+    }
+}
+
+fn write_literal(f: &mut fmt::Formatter<'_>, literal: &LiteralKind) -> fmt::Result {
+    match literal {
+        LiteralKind::Int(value) if *value < 0 => write!(f, "({})", value),
+        LiteralKind::Int(value) => write!(f, "{}", value),
+        LiteralKind::Float(value) => {
+            // Float literals always need a fractional part to lex as floats
+            let mut digits = format!("{}", value.abs());
+            if !digits.contains('.') {
+                digits.push_str(".0");
+            }
+            if value.is_sign_negative() {
+                write!(f, "(-{})", digits)
+            } else {
+                write!(f, "{}", digits)
+            }
+        }
+        LiteralKind::String(value) => write!(f, "\"{}\"", value),
+    }
+}
+
+fn join(expressions: &[Expression]) -> String {
+    expressions
+        .iter()
+        .map(|expr| expr.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -1,4 +1,5 @@
-use crate::error_reporter::{ErrorReporter, format_code_context};
+use crate::error_reporter::{DEFAULT_TAB_WIDTH, ErrorReporter, format_code_context};
+use crate::lexer::token::Token;
 use colored::Colorize;
 use lalrpop_util::ParseError;
 use std::fmt;
@@ -68,7 +69,7 @@ impl ErrorReporter for SyntaxError {
                         }
                     })
                 }) {
-                    result.push_str(&format_code_context(&source, *column, 1));
+                    result.push_str(&format_code_context(&source, *column, 1, DEFAULT_TAB_WIDTH));
                 }
             }
             SyntaxError::UnexpectedEOF {
@@ -94,7 +95,7 @@ impl ErrorReporter for SyntaxError {
                     let lines: Vec<&str> = source.lines().collect();
                     if *line <= lines.len() {
                         let line_content = lines[line - 1];
-                        result.push_str(&format_code_context(line_content, *column, 1));
+                        result.push_str(&format_code_context(line_content, *column, 1, DEFAULT_TAB_WIDTH));
                     }
                 }
 
@@ -136,7 +137,7 @@ impl ErrorReporter for SyntaxError {
                         }
                     })
                 }) {
-                    result.push_str(&format_code_context(&source, *column, 1));
+                    result.push_str(&format_code_context(&source, *column, 1, DEFAULT_TAB_WIDTH));
                 }
 
                 if !expected.is_empty() {
@@ -175,7 +176,7 @@ impl ErrorReporter for SyntaxError {
                         }
                     })
                 }) {
-                    result.push_str(&format_code_context(&source, *column, 1));
+                    result.push_str(&format_code_context(&source, *column, 1, DEFAULT_TAB_WIDTH));
                 }
             }
             SyntaxError::Custom(message) => {
@@ -202,7 +203,7 @@ impl ErrorReporter for SyntaxError {
             }
             SyntaxError::UnexpectedEOF { expected, .. } => {
                 if expected.len() == 1 {
-                    Some(format!("Add a '{}' to complete the statement", expected[0]))
+                    Some(format!("Add {} to complete the statement", expected[0]))
                 } else if !expected.is_empty() {
                     Some(format!(
                         "File ends abruptly. Complete the code with one of: {}",
@@ -227,19 +228,11 @@ impl ErrorReporter for SyntaxError {
                             .to_string(),
                     )
                 } else if expected.len() == 1 {
-                    Some(format!(
-                        "Replace '{}' with '{}'",
-                        token,
-                        expected[0].trim_matches('\'')
-                    ))
+                    Some(format!("Replace '{}' with {}", token, expected[0]))
                 } else {
                     Some(format!(
                         "Expected one of [{}] instead of '{}'",
-                        expected
-                            .iter()
-                            .map(|s| s.trim_matches('\''))
-                            .collect::<Vec<_>>()
-                            .join(", "),
+                        expected.join(", "),
                         token
                     ))
                 }
@@ -323,7 +316,7 @@ where
             let (line, column, _) = get_position_info(location);
             SyntaxError::UnexpectedEOF {
                 position: location,
-                expected,
+                expected: display_expected(expected),
                 line,
                 column,
             }
@@ -345,7 +338,7 @@ where
                 SyntaxError::UnexpectedToken {
                     token: token.to_string(),
                     position: (start, end),
-                    expected,
+                    expected: display_expected(expected),
                     source_line,
                     line,
                     column,
@@ -368,3 +361,13 @@ where
     }
 }
 
+/// Replaces LALRPOP terminal names with `Token::display_name`s.
+fn display_expected(expected: Vec<String>) -> Vec<String> {
+    expected
+        .into_iter()
+        .map(|terminal| match Token::from_terminal(&terminal) {
+            Some(token) => token.display_name().to_string(),
+            None => terminal,
+        })
+        .collect()
+}
@@ -1,4 +1,6 @@
 pub mod ast;
+pub mod display;
+pub mod sexp;
 pub mod error;
 pub mod parser_core;
 pub mod pretty_print;
@@ -3,9 +3,10 @@ mod grammar_parser {
     include!(concat!(env!("OUT_DIR"), "/parser/grammar.rs"));
 }
 
-use crate::lexer::lexer_core::TokenWithMetaData;
+use crate::error_reporter::ErrorReporter;
+use crate::lexer::lexer_core::{TokenWithMetaData, tokenize_with};
 use crate::lexer::token::Token;
-use crate::parser::ast::Program;
+use crate::parser::ast::{Declaration, Program, Statement};
 use crate::parser::error::{
     SyntaxError,
     convert_lalrpop_error,
@@ -23,15 +24,112 @@ pub fn tokenize_for_lalrpop(tokens: Vec<TokenWithMetaData>) -> Vec<Result<(usize
 }
 
 
-/// Parses tokens into an AST
-pub fn parse(tokens: Vec<TokenWithMetaData>, source: &str) -> Result<Program, SyntaxError> {
-     let lalrpop_tokens = tokenize_for_lalrpop(tokens);
-    
-     // Create an iterator that LALRPOP can use
-     let token_iter = lalrpop_tokens.into_iter();
-     
+/// Parses tokens into an AST.
+///
+/// `tokens` can be the vector returned by `tokenize` or a lazy iterator such
+/// as [`LexerIter`](crate::lexer::lexer_core::LexerIter), which the parser
+/// then pulls tokens from as it needs them.
+pub fn parse(
+    tokens: impl IntoIterator<Item = TokenWithMetaData>,
+    source: &str,
+) -> Result<Program, SyntaxError> {
+    // LALRPOP takes (start, token, end) triples
+    let token_iter = tokens
+        .into_iter()
+        .map(|token| Ok::<_, String>((token.span.start, token.kind, token.span.end)));
+
     match grammar_parser::ProgramParser::new().parse(token_iter) {
         Ok(located_program) => Ok(located_program.into_inner()),
         Err(e) => Err(convert_lalrpop_error(e, Some(source))),
     }
-}
\ No newline at end of file
+}
+
+/// Tokenizes and parses `source` in one step.
+///
+/// The lexer drops invalid tokens, so the first lexical error (if any) is
+/// reported as `SyntaxError::InvalidToken` instead of being parsed around.
+///
+/// # Examples
+///
+/// ```
+/// use rust_compiler::parser::error::SyntaxError;
+/// use rust_compiler::parser::parser_core::parse_source;
+///
+/// let program = parse_source("MainPrgm demo; Var let x: Int; BeginPg { } EndPg;").unwrap();
+/// assert_eq!(program.name, "demo");
+///
+/// let error = parse_source("MainPrgm demo; Var BeginPg { x := ; } EndPg;").unwrap_err();
+/// assert!(matches!(error, SyntaxError::UnexpectedToken { .. }));
+/// ```
+pub fn parse_source(source: &str) -> Result<Program, SyntaxError> {
+    parse_source_with(source, false)
+}
+
+/// Like [`parse_source`], but lexes identifiers as [`tokenize_with`] does.
+pub fn parse_source_with(source: &str, relaxed_identifiers: bool) -> Result<Program, SyntaxError> {
+    parse(tokenize_source(source, relaxed_identifiers)?, source)
+}
+
+/// Parses `source` and prints it back in the canonical layout used by `msrc --format`.
+///
+/// Comments are not part of the AST, so they are dropped from the output.
+///
+/// # Examples
+///
+/// ```
+/// use rust_compiler::parser::parser_core::format_source;
+///
+/// let formatted = format_source("MainPrgm demo; Var let x: Int; BeginPg { x := 1+2; } EndPg;");
+/// assert!(formatted.unwrap().contains("    x := 1 + 2;"));
+/// ```
+pub fn format_source(source: &str) -> Result<String, SyntaxError> {
+    parse_source(source).map(|program| program.to_string())
+}
+
+/// Parses a single statement, such as a line typed in the REPL.
+pub fn parse_statement(source: &str) -> Result<Statement, SyntaxError> {
+    let tokens = tokenize_for_lalrpop(tokenize_source(source, false)?);
+    grammar_parser::StatementParser::new()
+        .parse(tokens)
+        .map_err(|e| convert_lalrpop_error(e, Some(source)))
+}
+
+/// Parses a single declaration, e.g. `let x : Int ;`.
+pub fn parse_declaration(source: &str) -> Result<Declaration, SyntaxError> {
+    let tokens = tokenize_for_lalrpop(tokenize_source(source, false)?);
+    grammar_parser::DeclarationParser::new()
+        .parse(tokens)
+        .map_err(|e| convert_lalrpop_error(e, Some(source)))
+}
+
+fn tokenize_source(
+    source: &str,
+    relaxed_identifiers: bool,
+) -> Result<Vec<TokenWithMetaData>, SyntaxError> {
+    let (tokens, lexical_errors) = tokenize_with(source, relaxed_identifiers);
+
+    if let Some(error) = lexical_errors.first() {
+        let line_start: usize = source
+            .split_inclusive('\n')
+            .take(error.line - 1)
+            .map(str::len)
+            .sum();
+
+        return Err(SyntaxError::InvalidToken {
+            position: line_start + error.column - 1,
+            message: error.get_message(),
+            source_line: source.lines().nth(error.line - 1).map(String::from),
+            line: error.line,
+            column: error.column,
+        });
+    }
+
+    Ok(tokens)
+}
+
+impl Program {
+    /// Builds the AST for a MiniSoft source string, see [`parse_source`].
+    pub fn from_source(source: &str) -> Result<Self, SyntaxError> {
+        parse_source(source)
+    }
+}
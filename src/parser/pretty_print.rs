@@ -1,4 +1,7 @@
-use super::ast::{Declaration, DeclarationKind, Expression, ExpressionKind, Program, Statement, StatementKind};
+use super::ast::{
+    Declaration, DeclarationKind, Expression, ExpressionKind, Program, Statement, StatementKind,
+    name_texts,
+};
 
 impl Program {
     pub fn pretty_print(&self) {
@@ -22,13 +25,13 @@ impl Declaration {
         let new_prefix = if is_last { format!("{}    ", prefix) } else { format!("{}│   ", prefix) };
         match &self.node {
             DeclarationKind::Variable(names, ty) => {
-                println!("{}{} Variable: {:?} : {}", prefix, branch, names, ty);
+                println!("{}{} Variable: {:?} : {}", prefix, branch, name_texts(names), ty);
             }
             DeclarationKind::Array(names, ty, size) => {
                 println!("{}{} Array: {:?} : {} [{}]", prefix, branch, names, ty, size);
             }
             DeclarationKind::VariableWithInit(names, ty, expr) => {
-                println!("{}{} VariableWithInit: {:?} : {}", prefix, branch, names, ty);
+                println!("{}{} VariableWithInit: {:?} : {}", prefix, branch, name_texts(names), ty);
                 expr.pretty_print(&new_prefix, true);
             }
             DeclarationKind::ArrayWithInit(names, ty, size, exprs) => {
@@ -38,7 +41,10 @@ impl Declaration {
                 }
             }
             DeclarationKind::Constant(name, ty, lit) => {
-                println!("{}{} Constant: {} : {} = {:?}", prefix, branch, name, ty, lit.node);
+                println!("{}{} Constant: {} : {} = {:?}", prefix, branch, name.node, ty, lit.node);
+            }
+            DeclarationKind::Import(path) => {
+                println!("{}{} Import: {:?}", prefix, branch, path);
             }
         }
     }
@@ -61,7 +67,8 @@ impl Statement {
                     stmt.pretty_print(&new_prefix, i == stmts.len() - 1);
                 }
             }
-            StatementKind::IfThenElse(cond, then_stmts, else_stmts) => {
+            StatementKind::IfThenElse(cond, then_stmts, else_block) => {
+                let else_stmts = &else_block.node;
                 println!("{}{} IfThenElse:", prefix, branch);
                 cond.pretty_print(&new_prefix, false);
 
@@ -92,6 +99,13 @@ impl Statement {
                 }
                 cond.pretty_print(&new_prefix, true);
             }
+            StatementKind::While(cond, stmts) => {
+                println!("{}{} While:", prefix, branch);
+                cond.pretty_print(&new_prefix, stmts.is_empty());
+                for (i, stmt) in stmts.iter().enumerate() {
+                    stmt.pretty_print(&new_prefix, i == stmts.len() - 1);
+                }
+            }
             StatementKind::For(init, cond, step, end, stmts) => {
                 println!("{}{} For:", prefix, branch);
                 init.pretty_print(&new_prefix, false);
@@ -102,6 +116,12 @@ impl Statement {
                     stmt.pretty_print(&new_prefix, i == stmts.len() - 1);
                 }
             }
+            StatementKind::Break => {
+                println!("{}{} Break", prefix, branch);
+            }
+            StatementKind::Continue => {
+                println!("{}{} Continue", prefix, branch);
+            }
             StatementKind::Input(expr) => {
                 println!("{}{} Input:", prefix, branch);
                 expr.pretty_print(&new_prefix, true);
@@ -112,6 +132,9 @@ impl Statement {
                     expr.pretty_print(&new_prefix, i == exprs.len() - 1);
                 }
             }
+            StatementKind::InlineAsm(lines) => {
+                println!("{}{} InlineAsm: {:?}", prefix, branch, lines);
+            }
             StatementKind::Scope(stmts) => {
                 println!("{}{} Scope:", prefix, branch);
                 for (i, stmt) in stmts.iter().enumerate() {
@@ -0,0 +1,172 @@
+//! Prints the AST as an indented S-expression, for tools and debugging.
+//!
+//! Each declaration and statement starts on its own line. Expressions are
+//! written inline in prefix form, e.g. `(:= x (+ 1 (* 2 y)))`.
+
+use super::ast::{
+    DeclarationKind, Expression, ExpressionKind, LiteralKind, Program, Statement, StatementKind,
+    UnaryOperator, name_texts,
+};
+use super::display::operator_symbol;
+
+const INDENT: &str = "  ";
+
+impl Program {
+    /// The program as `(program name (declarations ...) (statements ...))`
+    pub fn to_sexp(&self) -> String {
+        let mut out = format!("(program {}\n{}(declarations", self.name, INDENT);
+        for decl in &self.declarations {
+            out.push_str(&format!(
+                "\n{}{}",
+                INDENT.repeat(2),
+                declaration_sexp(&decl.node)
+            ));
+        }
+        out.push_str(&format!(")\n{}(statements", INDENT));
+        for stmt in &self.statements {
+            write_statement(&mut out, stmt, 2);
+        }
+        out.push_str("))\n");
+        out
+    }
+}
+
+fn declaration_sexp(decl: &DeclarationKind) -> String {
+    match decl {
+        DeclarationKind::Variable(names, ty) => {
+            format!("(let ({}) {})", name_texts(names).join(" "), ty)
+        }
+        DeclarationKind::Array(names, ty, size) => {
+            format!("(let ({}) (array {} {}))", name_texts(names).join(" "), ty, size)
+        }
+        DeclarationKind::VariableWithInit(names, ty, value) => {
+            format!(
+                "(let ({}) {} {})",
+                name_texts(names).join(" "),
+                ty,
+                expression_sexp(value)
+            )
+        }
+        DeclarationKind::ArrayWithInit(names, ty, size, values) => format!(
+            "(let ({}) (array {} {}) ({}))",
+            name_texts(names).join(" "),
+            ty,
+            size,
+            join(values)
+        ),
+        DeclarationKind::Constant(name, ty, literal) => {
+            format!("(const {} {} {})", name.node, ty, literal_sexp(&literal.node))
+        }
+        DeclarationKind::Import(path) => format!("(import {:?})", path),
+    }
+}
+
+/// Writes `stmt` on a new line `depth` levels in, with nested blocks one level deeper
+fn write_statement(out: &mut String, stmt: &Statement, depth: usize) {
+    let pad = INDENT.repeat(depth);
+    match &stmt.node {
+        StatementKind::Assignment(target, value) => out.push_str(&format!(
+            "\n{}(:= {} {})",
+            pad,
+            expression_sexp(target),
+            expression_sexp(value)
+        )),
+        StatementKind::IfThen(condition, then_block) => {
+            out.push_str(&format!("\n{}(if {}", pad, expression_sexp(condition)));
+            write_block(out, "then", then_block, depth + 1);
+            out.push(')');
+        }
+        StatementKind::IfThenElse(condition, then_block, else_block) => {
+            out.push_str(&format!("\n{}(if {}", pad, expression_sexp(condition)));
+            write_block(out, "then", then_block, depth + 1);
+            write_block(out, "else", &else_block.node, depth + 1);
+            out.push(')');
+        }
+        StatementKind::DoWhile(body, condition) => {
+            out.push_str(&format!(
+                "\n{}(do-while {}",
+                pad,
+                expression_sexp(condition)
+            ));
+            write_block(out, "body", body, depth + 1);
+            out.push(')');
+        }
+        StatementKind::While(condition, body) => {
+            out.push_str(&format!("\n{}(while {}", pad, expression_sexp(condition)));
+            write_block(out, "body", body, depth + 1);
+            out.push(')');
+        }
+        StatementKind::For(var, from, to, step, body) => {
+            out.push_str(&format!(
+                "\n{}(for {} {} {} {}",
+                pad,
+                expression_sexp(var),
+                expression_sexp(from),
+                expression_sexp(to),
+                expression_sexp(step)
+            ));
+            write_block(out, "body", body, depth + 1);
+            out.push(')');
+        }
+        StatementKind::Break => out.push_str(&format!("\n{}(break)", pad)),
+        StatementKind::Continue => out.push_str(&format!("\n{}(continue)", pad)),
+        StatementKind::Input(target) => {
+            out.push_str(&format!("\n{}(input {})", pad, expression_sexp(target)))
+        }
+        StatementKind::Output(values) => {
+            out.push_str(&format!("\n{}(output {})", pad, join(values)))
+        }
+        StatementKind::InlineAsm(lines) => {
+            let lines: Vec<String> = lines.iter().map(|line| format!("{:?}", line)).collect();
+            out.push_str(&format!("\n{}(asm {})", pad, lines.join(" ")));
+        }
+        StatementKind::Scope(statements) => write_block(out, "scope", statements, depth),
+        StatementKind::Empty => out.push_str(&format!("\n{}(empty)", pad)),
+    }
+}
+
+fn write_block(out: &mut String, name: &str, statements: &[Statement], depth: usize) {
+    out.push_str(&format!("\n{}({}", INDENT.repeat(depth), name));
+    for stmt in statements {
+        write_statement(out, stmt, depth + 1);
+    }
+    out.push(')');
+}
+
+fn expression_sexp(expr: &Expression) -> String {
+    match &expr.node {
+        ExpressionKind::Identifier(name) => name.clone(),
+        ExpressionKind::ArrayAccess(name, index) => {
+            format!("(index {} {})", name, expression_sexp(index))
+        }
+        ExpressionKind::Literal(literal) => literal_sexp(&literal.node),
+        ExpressionKind::BinaryOp(left, op, right) => format!(
+            "({} {} {})",
+            operator_symbol(op),
+            expression_sexp(left),
+            expression_sexp(right)
+        ),
+        ExpressionKind::UnaryOp(UnaryOperator::Not, operand) => {
+            format!("(! {})", expression_sexp(operand))
+        }
+        ExpressionKind::UnaryOp(UnaryOperator::Negate, operand) => {
+            format!("(- {})", expression_sexp(operand))
+        }
+    }
+}
+
+fn literal_sexp(literal: &LiteralKind) -> String {
+    match literal {
+        LiteralKind::Int(value) => value.to_string(),
+        LiteralKind::Float(value) => format!("{:?}", value),
+        LiteralKind::String(value) => format!("{:?}", value),
+    }
+}
+
+fn join(values: &[Expression]) -> String {
+    values
+        .iter()
+        .map(expression_sexp)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -2,40 +2,128 @@ mod declaration_analyzer;
 mod expression_analyzer;
 mod statement_analyzer;
 
-use crate::parser::ast::{Expression, ExpressionKind, LiteralKind, Operator, Program, Type};
+use crate::error_reporter::{Diagnostic, DiagnosticHandler, Severity};
+use crate::lexer::token::Token;
+use crate::parser::ast::{
+    Expression, ExpressionKind, LiteralKind, Operator, Program, Span, Type, UnaryOperator,
+};
 use crate::semantics::error::SemanticError;
 use crate::semantics::source_map::SourceMap;
-use crate::semantics::symbol_table::{SymbolKind, SymbolTable, SymbolValue};
-use std::collections::HashSet;
-use std::ops::Range;
+use crate::semantics::symbol_table::{Symbol, SymbolKind, SymbolTable, SymbolValue};
+use crate::semantics::warning::SemanticWarning;
+use std::collections::{HashMap, HashSet};
 
 pub struct SemanticAnalyzer {
     symbol_table: SymbolTable,
     errors: Vec<SemanticError>,
+    warnings: Vec<SemanticWarning>,
     reported_errors: HashSet<String>,
     source_map: SourceMap,
+    diagnostic_handlers: Vec<DiagnosticHandler>,
+    allow_unsafe: bool,
+    /// Number of loops around the statement being analyzed, for `break` and `continue`
+    loop_depth: usize,
+    /// Symbols read by some expression, for `UnusedVariable` warnings
+    used_symbols: HashSet<String>,
+    /// Where each name is declared, for `UnusedVariable` warnings
+    name_spans: HashMap<String, Span>,
+    warn_unused: bool,
 }
 
 impl SemanticAnalyzer {
-    pub fn new(source_code: &String) -> Self {
+    /// Analyzer for a program parsed from `source_code`, which is used to
+    /// turn spans into lines and columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_compiler::parser::parser_core::parse_source;
+    /// use rust_compiler::semantics::SemanticAnalyzer;
+    ///
+    /// let source = "MainPrgm demo; Var let x: Int; BeginPg { x := y; } EndPg;";
+    /// let program = parse_source(source).unwrap();
+    ///
+    /// let mut analyzer = SemanticAnalyzer::new(source);
+    /// analyzer.analyze(&program);
+    /// assert_eq!(analyzer.get_errors().len(), 1);
+    /// ```
+    pub fn new(source_code: &str) -> Self {
         SemanticAnalyzer {
             symbol_table: SymbolTable::new(),
             errors: Vec::new(),
+            warnings: Vec::new(),
             reported_errors: HashSet::new(),
             source_map: SourceMap::new(source_code),
+            diagnostic_handlers: Vec::new(),
+            allow_unsafe: false,
+            loop_depth: 0,
+            used_symbols: HashSet::new(),
+            name_spans: HashMap::new(),
+            warn_unused: false,
         }
     }
 
+    /// Analyze a new piece of source against the symbols declared so far.
+    /// Errors and warnings from earlier sources are dropped.
+    pub fn reset_source(&mut self, source_code: &str) {
+        self.source_map = SourceMap::new(source_code);
+        // Spans into the previous source no longer mean anything
+        self.name_spans.clear();
+        self.errors.clear();
+        self.warnings.clear();
+        self.reported_errors.clear();
+    }
+
+    /// Forget the symbols, errors and warnings of earlier programs, so that
+    /// the same analyzer can check another one. Settings are kept.
+    pub fn reset(&mut self) {
+        self.symbol_table = SymbolTable::new();
+        self.errors.clear();
+        self.warnings.clear();
+        self.reported_errors.clear();
+        self.loop_depth = 0;
+        self.used_symbols.clear();
+        self.name_spans.clear();
+    }
+
+    /// Accept inline assembly, with a warning instead of an error.
+    pub fn set_allow_unsafe(&mut self, allow: bool) {
+        self.allow_unsafe = allow;
+    }
+
+    /// Warn about declared names that are never read.
+    pub fn set_warn_unused(&mut self, warn: bool) {
+        self.warn_unused = warn;
+    }
+
+    /// Check `program` from scratch, see [`reset`](Self::reset).
     pub fn analyze(&mut self, program: &Program) {
+        self.reset();
+        self.check_program_name(&program.name, &program.name_span);
         if program.statements.is_empty() && program.declarations.is_empty() {
             self.empty_program();
         }
         // First pass: analyze declarations
+        self.analyze_declarations_only(program);
+
+        // Second pass: analyze statements
+        self.analyze_statements_only(program);
+
+        if self.warn_unused {
+            self.unused_variable_warnings();
+        }
+    }
+
+    /// Run only the declaration pass, filling the symbol table without
+    /// looking at the statements.
+    pub fn analyze_declarations_only(&mut self, program: &Program) {
         for decl in &program.declarations {
             self.analyze_declaration(decl);
         }
+    }
 
-        // Second pass: analyze statements
+    /// Run only the statement pass, against the symbols declared so far.
+    pub fn analyze_statements_only(&mut self, program: &Program) {
         for stmt in &program.statements {
             self.analyze_statement(stmt);
         }
@@ -46,9 +134,21 @@ impl SemanticAnalyzer {
         self.add_error(SemanticError::EmptyProgram);
     }
 
+    fn check_program_name(&mut self, name: &str, span: &Span) {
+        let Some(keyword) = Token::keyword(name) else {
+            return;
+        };
+        self.add_error(SemanticError::InvalidProgramName {
+            name: name.to_string(),
+            reason: format!("it is the {}", keyword.display_name()),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
     fn array_size_mismatch_error(
         &mut self,
-        span: &Range<usize>,
+        span: &Span,
         name: &str,
         expected: usize,
         actual: usize,
@@ -62,9 +162,25 @@ impl SemanticAnalyzer {
         });
     }
 
+    fn array_too_many_initializers_error(
+        &mut self,
+        span: &Span,
+        name: &str,
+        declared_size: usize,
+        actual_count: usize,
+    ) {
+        self.add_error(SemanticError::ArrayTooManyInitializers {
+            name: name.to_string(),
+            declared_size,
+            actual_count,
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
     fn type_mismatch_error(
         &mut self,
-        span: &Range<usize>,
+        span: &Span,
         expected: &Type,
         found: &Type,
         context: Option<&str>,
@@ -78,7 +194,23 @@ impl SemanticAnalyzer {
         });
     }
 
-    fn undeclared_identifier_error(&mut self, span: &Range<usize>, name: &str) {
+    fn invalid_constant_initializer_error(
+        &mut self,
+        span: &Span,
+        name: &str,
+        expected: &Type,
+        found: &Type,
+    ) {
+        self.add_error(SemanticError::InvalidConstantInitializer {
+            name: name.to_string(),
+            expected_type: expected.to_string(),
+            found_type: found.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn undeclared_identifier_error(&mut self, span: &Span, name: &str) {
         self.add_error(SemanticError::UndeclaredIdentifier {
             name: name.to_string(),
             line: self.source_map.get_line(span),
@@ -86,7 +218,7 @@ impl SemanticAnalyzer {
         });
     }
 
-    fn constant_modification_error(&mut self, span: &Range<usize>, name: &str) {
+    fn constant_modification_error(&mut self, span: &Span, name: &str) {
         self.add_error(SemanticError::ConstantModification {
             name: name.to_string(),
             line: self.source_map.get_line(span),
@@ -96,7 +228,7 @@ impl SemanticAnalyzer {
 
     fn array_index_out_of_bounds_error(
         &mut self,
-        span: &Range<usize>,
+        span: &Span,
         name: &str,
         index: usize,
         size: usize,
@@ -109,7 +241,7 @@ impl SemanticAnalyzer {
             column: self.source_map.get_column(span),
         });
     }
-    fn non_array_indexing(&mut self, span: &Range<usize>, name: &str) {
+    fn non_array_indexing(&mut self, span: &Span, name: &str) {
         self.add_error(SemanticError::NonArrayIndexing {
             var_name: name.to_string(),
             line: self.source_map.get_line(span),
@@ -117,30 +249,51 @@ impl SemanticAnalyzer {
         });
     }
 
-    fn division_by_zero_error(&mut self, span: &Range<usize>) {
+    fn division_by_zero_error(&mut self, span: &Span) {
         self.add_error(SemanticError::DivisionByZero {
             line: self.source_map.get_line(span),
             column: self.source_map.get_column(span),
         });
     }
 
+    fn integer_overflow_error(&mut self, span: &Span) {
+        self.add_error(SemanticError::IntegerOverflow {
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
     fn duplicate_declaration_error(
         &mut self,
-        span: &Range<usize>,
+        span: &Span,
         name: &str,
         original_line: usize,
         original_column: usize,
     ) {
-        self.add_error(SemanticError::DuplicateDeclaration {
-            name: name.to_string(),
-            line: self.source_map.get_line(span),
-            column: self.source_map.get_column(span),
-            original_line,
-            original_column,
-        });
+        let line = self.source_map.get_line(span);
+        let column = self.source_map.get_column(span);
+
+        // Shadowing a constant is reported separately from a plain duplicate
+        let error = match self.symbol_table.get(name) {
+            Some(existing) if existing.is_constant => SemanticError::ConstantRedeclaration {
+                name: name.to_string(),
+                line,
+                column,
+                original_line,
+                original_column,
+            },
+            _ => SemanticError::DuplicateDeclaration {
+                name: name.to_string(),
+                line,
+                column,
+                original_line,
+                original_column,
+            },
+        };
+        self.add_error(error);
     }
 
-    fn condition_value_error(&mut self, span: &Range<usize>, found: String) {
+    fn condition_value_error(&mut self, span: &Span, found: String) {
         self.add_error(SemanticError::InvalidConditionValue {
             found,
             line: self.source_map.get_line(span),
@@ -148,7 +301,7 @@ impl SemanticAnalyzer {
         });
     }
 
-    fn invalid_array_size_error(&mut self, span: &Range<usize>, name: &str, size: i32) {
+    fn invalid_array_size_error(&mut self, span: &Span, name: &str, size: i32) {
         self.add_error(SemanticError::InvalidArraySize {
             name: name.to_string(),
             size,
@@ -157,7 +310,7 @@ impl SemanticAnalyzer {
         });
     }
 
-    fn assignement_to_array_error(&mut self, span: &Range<usize>, name: &str) {
+    fn assignement_to_array_error(&mut self, span: &Span, name: &str) {
         self.add_error(SemanticError::AssignmentToArray {
             name: name.to_string(),
             line: self.source_map.get_line(span),
@@ -165,23 +318,157 @@ impl SemanticAnalyzer {
         });
     }
 
+    fn constant_condition_warning(&mut self, span: &Span, value: bool, context: &str) {
+        self.add_warning(SemanticWarning::ConstantCondition {
+            value,
+            context: context.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn for_loop_never_executes_warning(
+        &mut self,
+        span: &Span,
+        var: &str,
+        from_val: i32,
+        to_val: i32,
+        step_val: i32,
+    ) {
+        self.add_warning(SemanticWarning::ForLoopWillNeverExecute {
+            var: var.to_string(),
+            from_val,
+            to_val,
+            step_val,
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn empty_body_warning(&mut self, span: &Span, construct: &str) {
+        self.add_warning(SemanticWarning::EmptyBody {
+            construct: construct.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn format_argument_count_error(&mut self, span: &Span, expected: usize, found: usize) {
+        self.add_error(SemanticError::FormatArgumentCount {
+            expected,
+            found,
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn uninitialized_read_warning(&mut self, span: &Span, name: &str) {
+        self.add_warning(SemanticWarning::UninitializedRead {
+            name: name.to_string(),
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    /// One warning per symbol that no expression read, in declaration order
+    fn unused_variable_warnings(&mut self) {
+        let mut unused: Vec<(usize, usize, String)> = self
+            .symbol_table
+            .get_all()
+            .into_iter()
+            .filter(|symbol| !self.used_symbols.contains(&symbol.name))
+            .map(|symbol| match self.name_spans.get(&symbol.name) {
+                Some(span) => {
+                    let (line, column) = self.source_map.get_line_column(span);
+                    (line, column, symbol.name.clone())
+                }
+                None => (symbol.line, symbol.column, symbol.name.clone()),
+            })
+            .collect();
+        unused.sort();
+
+        for (line, column, name) in unused {
+            self.add_warning(SemanticWarning::UnusedVariable { name, line, column });
+        }
+    }
+
+    fn break_outside_loop_error(&mut self, span: &Span) {
+        self.add_error(SemanticError::BreakOutsideLoop {
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn continue_outside_loop_error(&mut self, span: &Span) {
+        self.add_error(SemanticError::ContinueOutsideLoop {
+            line: self.source_map.get_line(span),
+            column: self.source_map.get_column(span),
+        });
+    }
+
+    fn inline_assembly(&mut self, span: &Span) {
+        let line = self.source_map.get_line(span);
+        let column = self.source_map.get_column(span);
+        if self.allow_unsafe {
+            self.add_warning(SemanticWarning::InlineAssembly { line, column });
+        } else {
+            self.add_error(SemanticError::UnsafeNotAllowed { line, column });
+        }
+    }
+
     pub fn add_error(&mut self, error: SemanticError) {
         // Only add the error if it hasn't been reported yet
         let error_key = format!("{:?}", error);
         if !self.reported_errors.contains(&error_key) {
             self.reported_errors.insert(error_key);
+            self.notify(|| Diagnostic::error(&error));
             self.errors.push(error);
         }
     }
 
+    /// Call `handler` with every error and warning as it is reported.
+    pub fn add_diagnostic_handler(&mut self, handler: DiagnosticHandler) {
+        self.diagnostic_handlers.push(handler);
+    }
+
+    /// Diagnostics are only built when someone is listening.
+    fn notify(&self, diagnostic: impl FnOnce() -> Diagnostic) {
+        if self.diagnostic_handlers.is_empty() {
+            return;
+        }
+        let diagnostic = diagnostic();
+        for handler in &self.diagnostic_handlers {
+            handler(diagnostic.clone());
+        }
+    }
+
     pub fn get_errors(&self) -> &Vec<SemanticError> {
         &self.errors
     }
 
+    pub fn add_warning(&mut self, warning: SemanticWarning) {
+        self.notify(|| Diagnostic::new(Severity::Warning, &warning).with_code(warning.code()));
+        self.warnings.push(warning);
+    }
+
+    pub fn get_warnings(&self) -> &Vec<SemanticWarning> {
+        &self.warnings
+    }
+
     pub fn get_symbol_table(&self) -> &SymbolTable {
         &self.symbol_table
     }
 
+    /// Looks up a single symbol, e.g. for a hover provider
+    pub fn get_symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbol_table.get(name)
+    }
+
+    /// Every declared symbol, in no particular order (the table is a `HashMap`)
+    pub fn get_all_symbols(&self) -> Vec<&Symbol> {
+        self.symbol_table.get_all()
+    }
+
     pub fn evaluate_constant_expression(&mut self, expr: &Expression) -> Option<LiteralKind> {
         match &expr.node {
             ExpressionKind::Literal(lit) => Some(lit.node.clone()),
@@ -192,7 +479,7 @@ impl SemanticAnalyzer {
                         match &symbol.value {
                             SymbolValue::Single(lit) => return Some(lit.clone()),
                             SymbolValue::Array(_) => return None, // Array as a whole isn't a literal value
-                            SymbolValue::Uninitialized => return None,
+                            SymbolValue::Assigned | SymbolValue::Uninitialized => return None,
                         }
                     }
                 }
@@ -203,20 +490,24 @@ impl SemanticAnalyzer {
                 let right_val = self.evaluate_constant_expression(right)?;
 
                 match (left_val, right_val) {
-                    (LiteralKind::Int(l), LiteralKind::Int(r)) => match op {
-                        Operator::Add => Some(LiteralKind::Int(l + r)),
-                        Operator::Subtract => Some(LiteralKind::Int(l - r)),
-                        Operator::Multiply => Some(LiteralKind::Int(l * r)),
-                        Operator::Divide => {
-                            if r == 0 {
+                    (LiteralKind::Int(l), LiteralKind::Int(r)) => {
+                        let result = match op {
+                            Operator::Add => l.checked_add(r),
+                            Operator::Subtract => l.checked_sub(r),
+                            Operator::Multiply => l.checked_mul(r),
+                            Operator::Divide | Operator::Modulo if r == 0 => {
                                 self.division_by_zero_error(&right.span);
-                                None
-                            } else {
-                                Some(LiteralKind::Int(l / r))
+                                return None;
                             }
+                            Operator::Divide => l.checked_div(r),
+                            Operator::Modulo => l.checked_rem(r),
+                            _ => return None,
+                        };
+                        if result.is_none() {
+                            self.integer_overflow_error(&expr.span);
                         }
-                        _ => None,
-                    },
+                        result.map(LiteralKind::Int)
+                    }
                     (LiteralKind::Float(l), LiteralKind::Float(r)) => match op {
                         Operator::Add => Some(LiteralKind::Float(l + r)),
                         Operator::Subtract => Some(LiteralKind::Float(l - r)),
@@ -234,6 +525,19 @@ impl SemanticAnalyzer {
                     _ => None,
                 }
             }
+            ExpressionKind::UnaryOp(UnaryOperator::Negate, operand) => {
+                match self.evaluate_constant_expression(operand)? {
+                    LiteralKind::Int(value) => match value.checked_neg() {
+                        Some(negated) => Some(LiteralKind::Int(negated)),
+                        None => {
+                            self.integer_overflow_error(&expr.span);
+                            None
+                        }
+                    },
+                    LiteralKind::Float(value) => Some(LiteralKind::Float(-value)),
+                    LiteralKind::String(_) => None,
+                }
+            }
             ExpressionKind::ArrayAccess(name, index_expr) => {
                 // Handle array access for constant expressions
                 // First evaluate the index expression to avoid borrowing conflicts
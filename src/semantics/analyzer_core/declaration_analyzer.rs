@@ -1,8 +1,4 @@
-use std::ops::Range;
-
-use crate::parser::ast::{
-    Declaration, DeclarationKind, Expression, Literal, LiteralKind, Type,
-};
+use crate::parser::ast::{Declaration, DeclarationKind, Expression, Literal, Name, Span, Type};
 use crate::semantics::analyzer_core::SemanticAnalyzer;
 use crate::semantics::symbol_table::{Symbol, SymbolKind, SymbolValue};
 
@@ -11,18 +7,21 @@ impl SemanticAnalyzer {
         match &declaration.node {
             DeclarationKind::Variable(items, typ) => {
                 for item in items {
-                    self.handle_variable_declaration(item, typ, &declaration.span);
+                    self.declare_name(item);
+                    self.handle_variable_declaration(&item.node, typ, &declaration.span);
                 }
             }
             DeclarationKind::Array(items, typ, size) => {
                 for item in items {
-                    self.handle_array_declaration(item, typ, *size, &declaration.span);
+                    self.declare_name(item);
+                    self.handle_array_declaration(&item.node, typ, *size, &declaration.span);
                 }
             }
             DeclarationKind::VariableWithInit(items, typ, expression) => {
                 for item in items {
+                    self.declare_name(item);
                     self.handle_variable_declaration_with_init(
-                        item,
+                        &item.node,
                         typ,
                         expression,
                         &declaration.span,
@@ -31,8 +30,9 @@ impl SemanticAnalyzer {
             }
             DeclarationKind::ArrayWithInit(items, typ, size, expressions) => {
                 for item in items {
+                    self.declare_name(item);
                     self.handle_array_declaration_with_init(
-                        item,
+                        &item.node,
                         typ,
                         *size,
                         expressions,
@@ -41,17 +41,27 @@ impl SemanticAnalyzer {
                 }
             }
             DeclarationKind::Constant(value, typ, literal) => {
-                self.handle_constant_declaration(value, typ, literal, &declaration.span);
+                self.declare_name(value);
+                self.handle_constant_declaration(&value.node, typ, literal, &declaration.span);
             }
+            // Inlined by ModuleLoader before analysis
+            DeclarationKind::Import(_) => {}
         }
     }
 
+    /// Remember where `name` is first declared, for `UnusedVariable` warnings
+    fn declare_name(&mut self, name: &Name) {
+        self.name_spans
+            .entry(name.node.clone())
+            .or_insert_with(|| name.span.clone());
+    }
+
     fn handle_constant_declaration(
         &mut self,
         value: &str,
         typ: &Type,
         literal: &Literal,
-        span: &Range<usize>,
+        span: &Span,
     ) {
         // Check for duplicate declaration
         if self.symbol_table.contains(value) {
@@ -60,14 +70,10 @@ impl SemanticAnalyzer {
             return;
         }
         
-        match &literal.node {
-            LiteralKind::Int(_) if !typ.is_compatible_with(&Type::Int) => {
-                self.type_mismatch_error(span, typ, &Type::Int, Some("constant"));
-            },
-            LiteralKind::Float(_) if !typ.is_compatible_with(&Type::Float) => {
-                self.type_mismatch_error(span, typ, &Type::Float, Some("constant"));
-            },
-            _ => {}
+        // Constants take no conversions: the literal must have the declared type
+        let literal_type = literal.node.literal_kind_to_type();
+        if literal_type != *typ {
+            self.invalid_constant_initializer_error(span, value, typ, &literal_type);
         }
 
         let line = self.source_map.get_line(span);
@@ -86,7 +92,7 @@ impl SemanticAnalyzer {
         self.symbol_table.add_symbol(symbol);
     }
 
-    fn handle_variable_declaration(&mut self, name: &str, typ: &Type, span: &Range<usize>) {
+    fn handle_variable_declaration(&mut self, name: &str, typ: &Type, span: &Span) {
         // Check for duplicate declaration
         if self.symbol_table.contains(name) {
             let existing = self.symbol_table.get(name).unwrap();
@@ -109,7 +115,7 @@ impl SemanticAnalyzer {
         self.symbol_table.add_symbol(symbol);
     }
 
-    fn handle_array_declaration(&mut self, name: &str, typ: &Type, size: usize, span: &Range<usize>) {
+    fn handle_array_declaration(&mut self, name: &str, typ: &Type, size: usize, span: &Span) {
         // Check for valid array size
         if size == 0 || (size as i32) < 0 {
             self.invalid_array_size_error(span, name, size as i32);
@@ -144,7 +150,7 @@ impl SemanticAnalyzer {
         name: &str,
         typ: &Type,
         expr: &Expression,
-        span: &Range<usize>,
+        span: &Span,
     ) {
         // First, check the expression
         let expr_type = self.analyze_expression(expr);
@@ -190,7 +196,7 @@ impl SemanticAnalyzer {
         typ: &Type,
         size: usize,
         exprs: &[Expression],
-        span: &Range<usize>,
+        span: &Span,
     ) {
         // Check for valid array size
         if size == 0 || (size as i32) < 0 {
@@ -199,7 +205,9 @@ impl SemanticAnalyzer {
         }
         
         // Check that array size matches number of initializers
-        if exprs.len() != size {
+        if exprs.len() > size {
+            self.array_too_many_initializers_error(span, name, size, exprs.len());
+        } else if exprs.len() != size {
             self.array_size_mismatch_error(span, name, size, exprs.len());
         }
 
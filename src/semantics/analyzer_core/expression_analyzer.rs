@@ -1,7 +1,6 @@
-use std::ops::Range;
-
 use crate::parser::ast::{
-    Expression, ExpressionKind, Literal, LiteralKind, Located, Operator, Type, UnaryOperator,
+    Expression, ExpressionKind, Literal, LiteralKind, Located, Operator, Span, Type,
+    UnaryOperator,
 };
 use crate::semantics::{
     analyzer_core::SemanticAnalyzer,
@@ -55,20 +54,29 @@ impl SemanticAnalyzer {
         }
     }
 
-    fn handle_identifier(&mut self, name: &str, span: &Range<usize>) -> Option<ValueType> {
+    fn handle_identifier(&mut self, name: &str, span: &Span) -> Option<ValueType> {
         if !self.symbol_table.contains(name) {
             self.undeclared_identifier_error(span, name);
             return None;
         }
-
-        let symbol = self.symbol_table.get(name).unwrap();
+        self.used_symbols.insert(name.to_string());
+
+        let Some(symbol) = self.symbol_table.get_initialized(name) else {
+            let symbol = self.symbol_table.get(name).unwrap();
+            let symbol_type = symbol.symbol_type.clone();
+            // Arrays are read element by element, so only scalars are checked
+            if symbol.kind == SymbolKind::Variable {
+                self.uninitialized_read_warning(span, name);
+            }
+            return Some(ValueType::new(symbol_type, None));
+        };
         let value = match &symbol.value {
             SymbolValue::Single(lit) => match lit {
                 LiteralKind::Float(f) => Some(*f),
                 LiteralKind::Int(i) => Some(*i as f32),
                 _ => None,
             },
-            SymbolValue::Uninitialized => None,
+            SymbolValue::Assigned | SymbolValue::Uninitialized => None,
             SymbolValue::Array(_) => None, // Array as a whole doesn't have a single value
         };
 
@@ -79,12 +87,13 @@ impl SemanticAnalyzer {
         &mut self,
         name: &str,
         index_expression: &Expression,
-        span: &Range<usize>,
+        span: &Span,
     ) -> Option<ValueType> {
         if !self.symbol_table.contains(name) {
             self.undeclared_identifier_error(span, name);
             return None;
         }
+        self.used_symbols.insert(name.to_string());
 
         let symbol = self.symbol_table.get(name).unwrap();
         match &symbol.kind {
@@ -142,11 +151,10 @@ impl SemanticAnalyzer {
                 // (since we can't determine at compile time which element will be accessed)
                 Some(ValueType::new(symbol_type, None))
             }
-            SymbolKind::Variable => {
+            SymbolKind::Variable | SymbolKind::Constant => {
                 self.non_array_indexing(&index_expression.span, name);
                 None
             }
-            _ => None,
         }
     }
 
@@ -154,7 +162,7 @@ impl SemanticAnalyzer {
         match literal.node {
             LiteralKind::Int(value) => Some(ValueType::new(Type::Int, Some(value as f32))),
             LiteralKind::Float(value) => Some(ValueType::new(Type::Float, Some(value))),
-            _ => None,
+            LiteralKind::String(_) => Some(ValueType::new(Type::String, None)),
         }
     }
 
@@ -225,6 +233,39 @@ impl SemanticAnalyzer {
                     Some(ValueType::new(Type::Int, result_value))
                 }
             }
+            Operator::Modulo => {
+                // The remainder is only defined on integers
+                if left_type.typ != Type::Int {
+                    self.type_mismatch_error(
+                        &(left.span.start..right.span.end),
+                        &Type::Int,
+                        &left_type.typ,
+                        Some("arithmetic"),
+                    );
+                    return None;
+                }
+                if right_type.typ != Type::Int {
+                    self.type_mismatch_error(
+                        &(left.span.start..right.span.end),
+                        &Type::Int,
+                        &right_type.typ,
+                        Some("arithmetic"),
+                    );
+                    return None;
+                }
+
+                if let Some(LiteralKind::Int(0)) = self.evaluate_constant_expression(right) {
+                    self.division_by_zero_error(&right.span);
+                    return None;
+                }
+
+                let result_value = match (left_type.value, right_type.value) {
+                    (Some(l), Some(r)) if r != 0.0 => Some(l % r),
+                    _ => None,
+                };
+
+                Some(ValueType::new(Type::Int, result_value))
+            }
             Operator::GreaterThan
             | Operator::LessThan
             | Operator::GreaterEqual
@@ -312,7 +353,7 @@ impl SemanticAnalyzer {
         &mut self,
         unary_operator: &UnaryOperator,
         expression: &Expression,
-        span: &Range<usize>,
+        span: &Span,
     ) -> Option<ValueType> {
         let expression_type = self.analyze_expression(expression)?;
 
@@ -344,6 +385,21 @@ impl SemanticAnalyzer {
                 };
                 Some(ValueType::new(Type::Int, Some(negated_value)))
             }
+            UnaryOperator::Negate => {
+                if expression_type.typ != Type::Int && expression_type.typ != Type::Float {
+                    self.type_mismatch_error(
+                        span,
+                        &Type::Int,
+                        &expression_type.typ,
+                        Some("arithmetic"),
+                    );
+                    return None;
+                }
+                Some(ValueType::new(
+                    expression_type.typ,
+                    expression_type.value.map(|value| -value),
+                ))
+            }
         }
     }
 }
@@ -1,6 +1,10 @@
-use crate::parser::ast::{Expression, ExpressionKind, Statement, StatementKind, Type};
+use crate::parser::ast::{
+    Expression, ExpressionKind, FormatSegment, LiteralKind, Operator, Span, Statement,
+    StatementKind, Type, format_segments,
+};
 use crate::semantics::analyzer_core::SemanticAnalyzer;
-use crate::semantics::symbol_table::SymbolKind;
+use crate::semantics::analyzer_core::expression_analyzer::ValueType;
+use crate::semantics::symbol_table::{Symbol, SymbolKind};
 
 impl SemanticAnalyzer {
     pub fn analyze_statement(&mut self, stmt: &Statement) {
@@ -11,24 +15,56 @@ impl SemanticAnalyzer {
 
             StatementKind::IfThen(condition, then_block) => {
                 self.handle_condition(condition, Some("if condition"));
+                self.check_empty_body(then_block, "if", &stmt.span);
+                let uninitialized = self.symbol_table.uninitialized();
                 self.handle_scope(then_block);
+                self.symbol_table.mark_uninitialized(&uninitialized);
             }
 
             StatementKind::IfThenElse(condition, then_block, else_block) => {
                 self.handle_condition(condition, Some("if-else condition"));
+                self.check_empty_body(then_block, "if", &stmt.span);
+                self.check_empty_body(&else_block.node, "else", &else_block.span);
+                // Only variables assigned by both branches are initialized afterwards
+                let uninitialized = self.symbol_table.uninitialized();
                 self.handle_scope(then_block);
-                self.handle_scope(else_block);
+                let uninitialized_after_then = self.symbol_table.uninitialized();
+                self.symbol_table.mark_uninitialized(&uninitialized);
+                self.handle_scope(&else_block.node);
+                self.symbol_table.mark_uninitialized(&uninitialized_after_then);
             }
 
             StatementKind::DoWhile(body, condition) => {
-                self.handle_scope(body);
+                self.check_empty_body(body, "do-while loop", &stmt.span);
+                self.handle_loop_body(body);
                 self.analyze_expression(condition);
 
                 self.handle_condition(condition, Some("do-while condition"));
             }
 
+            StatementKind::While(condition, body) => {
+                self.handle_condition(condition, Some("while condition"));
+                self.check_empty_body(body, "while loop", &stmt.span);
+                let uninitialized = self.symbol_table.uninitialized();
+                self.handle_loop_body(body);
+                self.symbol_table.mark_uninitialized(&uninitialized);
+            }
+
             StatementKind::For(iterator, init, end, step, body) => {
-                self.handle_forloop(iterator, init, end, step, body);
+                self.check_empty_body(body, "for loop", &stmt.span);
+                self.handle_forloop(iterator, init, end, step, body, &stmt.span);
+            }
+
+            StatementKind::Break => {
+                if self.loop_depth == 0 {
+                    self.break_outside_loop_error(&stmt.span);
+                }
+            }
+
+            StatementKind::Continue => {
+                if self.loop_depth == 0 {
+                    self.continue_outside_loop_error(&stmt.span);
+                }
             }
 
             StatementKind::Input(target) => {
@@ -39,6 +75,10 @@ impl SemanticAnalyzer {
                 self.handle_output(expressions);
             }
 
+            StatementKind::InlineAsm(_) => {
+                self.inline_assembly(&stmt.span);
+            }
+
             StatementKind::Scope(statements) => {
                 self.handle_scope(statements);
             }
@@ -59,16 +99,25 @@ impl SemanticAnalyzer {
                     self.constant_modification_error(&left_expression.span, name);
                 }
 
-                // Check for direct assignment to array
-                if let SymbolKind::Array(_) = symbol.kind {
-                    self.assignement_to_array_error(&left_expression.span, name);
+                // Arrays can only be assigned whole from another array
+                if let SymbolKind::Array(size) = symbol.kind {
+                    self.handle_array_copy(
+                        name,
+                        size,
+                        &symbol.symbol_type,
+                        left_expression,
+                        right_expression,
+                    );
+                    return;
                 }
             }
         }
 
-        // Analyze both sides of the assignment
-        let left_type = self.analyze_expression(left_expression);
+        // Analyze both sides of the assignment. The value is read before the
+        // target is stored to, so `x := x + 1` still reads an unset `x`.
         let right_type = self.analyze_expression(right_expression);
+        self.mark_initialized(left_expression);
+        let left_type = self.analyze_store_target(left_expression);
 
         if let (Some(left_type), Some(right_type)) = (left_type, right_type) {
             if !right_type
@@ -85,6 +134,50 @@ impl SemanticAnalyzer {
         }
     }
 
+    /// `target := source` where `target` is an array of `size` elements
+    fn handle_array_copy(
+        &mut self,
+        target: &str,
+        size: usize,
+        element_type: &Type,
+        left_expression: &Expression,
+        right_expression: &Expression,
+    ) {
+        let source = match &right_expression.node {
+            ExpressionKind::Identifier(source) => self.symbol_table.get(source).cloned(),
+            _ => None,
+        };
+
+        match source {
+            Some(Symbol {
+                kind: SymbolKind::Array(source_size),
+                symbol_type,
+                ..
+            }) => {
+                if source_size != size {
+                    self.array_size_mismatch_error(
+                        &right_expression.span,
+                        target,
+                        size,
+                        source_size,
+                    );
+                }
+                if symbol_type != *element_type {
+                    self.type_mismatch_error(
+                        &right_expression.span,
+                        element_type,
+                        &symbol_type,
+                        Some("array assignment"),
+                    );
+                }
+            }
+            _ => {
+                self.assignement_to_array_error(&left_expression.span, target);
+                self.analyze_expression(right_expression);
+            }
+        }
+    }
+
     fn handle_condition(&mut self, condition: &Expression, context: Option<&str>) {
         // Analyze the condition expression
         let condition_type = self.analyze_expression(condition);
@@ -98,16 +191,63 @@ impl SemanticAnalyzer {
                     cond_type.get_type(),
                     context,
                 );
+            } else if let Some(value) = self.constant_condition(condition) {
+                self.constant_condition_warning(
+                    &condition.span,
+                    value,
+                    context.unwrap_or("condition"),
+                );
             }
         }
     }
 
+    /// Value of a condition built only from literals and constants
+    fn constant_condition(&mut self, condition: &Expression) -> Option<bool> {
+        if let ExpressionKind::BinaryOp(left, operator, right) = &condition.node {
+            // Int operands are compared as integers, so that large values do not
+            // round together; only mixed operands are widened
+            return match (
+                self.evaluate_constant_expression(left)?,
+                self.evaluate_constant_expression(right)?,
+            ) {
+                (LiteralKind::Int(l), LiteralKind::Int(r)) => compare(operator, l, r, 0),
+                (LiteralKind::Float(l), LiteralKind::Float(r)) => compare(operator, l, r, 0.0),
+                (LiteralKind::Int(l), LiteralKind::Float(r)) => {
+                    compare(operator, l as f64, r as f64, 0.0)
+                }
+                (LiteralKind::Float(l), LiteralKind::Int(r)) => {
+                    compare(operator, l as f64, r as f64, 0.0)
+                }
+                _ => None,
+            };
+        }
+
+        match self.evaluate_constant_expression(condition)? {
+            LiteralKind::Int(value) => Some(value != 0),
+            _ => None,
+        }
+    }
+
+    /// Warns about a body without any statement, usually a forgotten one
+    fn check_empty_body(&mut self, body: &[Statement], construct: &str, span: &Span) {
+        if is_empty_body(body) {
+            self.empty_body_warning(span, construct);
+        }
+    }
+
     fn handle_scope(&mut self, then_block: &Vec<Statement>) {
         for stmt in then_block {
             self.analyze_statement(stmt);
         }
     }
 
+    /// Analyzes the body of a loop, where `break` and `continue` are allowed
+    fn handle_loop_body(&mut self, body: &Vec<Statement>) {
+        self.loop_depth += 1;
+        self.handle_scope(body);
+        self.loop_depth -= 1;
+    }
+
     fn handle_forloop(
         &mut self,
         iterator: &Expression,
@@ -115,8 +255,10 @@ impl SemanticAnalyzer {
         end: &Expression,
         step: &Expression,
         body: &Vec<Statement>,
+        span: &Span,
     ) {
         // Check for duplicate iterator declaration
+        self.mark_initialized(iterator);
         let iterator_type = self.analyze_expression(iterator);
         if let Some(iterator_type) = iterator_type {
             if iterator_type != Type::Int {
@@ -131,6 +273,7 @@ impl SemanticAnalyzer {
 
         // Analyze initialization
         let init_type = self.analyze_expression(init);
+        let from_value = init_type.as_ref().and_then(|t| t.get_value());
         if let Some(init_type) = init_type {
             if init_type != Type::Int {
                 self.type_mismatch_error(
@@ -143,6 +286,7 @@ impl SemanticAnalyzer {
         }
 
         let end_type = self.analyze_expression(end);
+        let to_value = end_type.as_ref().and_then(|t| t.get_value());
         if let Some(end_type) = end_type {
             if end_type != Type::Int {
                 self.type_mismatch_error(
@@ -155,6 +299,7 @@ impl SemanticAnalyzer {
         }
 
         let step_type = self.analyze_expression(step);
+        let step_value = step_type.as_ref().and_then(|t| t.get_value());
         if let Some(step_type) = step_type {
             if step_type != Type::Int {
                 self.type_mismatch_error(
@@ -166,13 +311,29 @@ impl SemanticAnalyzer {
             }
         }
 
-        // Analyze loop body
-        self.handle_scope(body);
+        // With known bounds, a step away from the end skips the body entirely
+        if let (Some(from), Some(to), Some(step)) = (from_value, to_value, step_value)
+            && ((from > to && step > 0.0) || (from < to && step < 0.0))
+        {
+            self.for_loop_never_executes_warning(
+                span,
+                &iterator.to_string(),
+                from as i32,
+                to as i32,
+                step as i32,
+            );
+        }
+
+        // Analyze loop body, which may not run at all
+        let uninitialized = self.symbol_table.uninitialized();
+        self.handle_loop_body(body);
+        self.symbol_table.mark_uninitialized(&uninitialized);
     }
 
     fn handle_input(&mut self, target: &Expression) {
         // Analyze the target expression
-        let _target_type = self.analyze_expression(target);
+        self.mark_initialized(target);
+        let _target_type = self.analyze_store_target(target);
 
         // Check if the target is a valid identifier
         if let ExpressionKind::Identifier(name) | ExpressionKind::ArrayAccess(name, _) =
@@ -186,10 +347,85 @@ impl SemanticAnalyzer {
         }
     }
 
+    /// Analyzes the target of a store, which does not count as reading the
+    /// variable (the index of an array element does)
+    fn analyze_store_target(&mut self, target: &Expression) -> Option<ValueType> {
+        let name = match &target.node {
+            ExpressionKind::Identifier(name) | ExpressionKind::ArrayAccess(name, _) => name,
+            _ => return self.analyze_expression(target),
+        };
+        let was_used = self.used_symbols.contains(name);
+        let target_type = self.analyze_expression(target);
+        if !was_used {
+            self.used_symbols.remove(name);
+        }
+        target_type
+    }
+
+    /// Records a store to `target` when it names a variable
+    fn mark_initialized(&mut self, target: &Expression) {
+        if let ExpressionKind::Identifier(name) = &target.node {
+            self.symbol_table.mark_initialized(name);
+        }
+    }
+
     fn handle_output(&mut self, expressions: &Vec<Expression>) {
-        for expr in expressions {
-            // Analyze the expression
-            let _expr_type = self.analyze_expression(expr);
+        let Some(segments) = format_segments(expressions) else {
+            for expr in expressions {
+                // Analyze the expression
+                let _expr_type = self.analyze_expression(expr);
+            }
+            return;
+        };
+
+        let placeholders: Vec<&Type> = segments
+            .iter()
+            .filter_map(|segment| match segment {
+                FormatSegment::Placeholder(typ) => Some(typ),
+                FormatSegment::Text(_) => None,
+            })
+            .collect();
+        let values = &expressions[1..];
+        if placeholders.len() != values.len() {
+            self.format_argument_count_error(
+                &expressions[0].span,
+                placeholders.len(),
+                values.len(),
+            );
+        }
+
+        for (index, expr) in values.iter().enumerate() {
+            let expr_type = self.analyze_expression(expr);
+            if let (Some(expected), Some(found)) = (placeholders.get(index), expr_type)
+                && found.get_type() != *expected
+            {
+                self.type_mismatch_error(&expr.span, expected, found.get_type(), Some("format string"));
+            }
         }
     }
 }
+
+/// Result of a comparison or logical `operator` on two constants, with
+/// `zero` as false
+fn compare<T: PartialOrd>(operator: &Operator, left: T, right: T, zero: T) -> Option<bool> {
+    match operator {
+        Operator::GreaterThan => Some(left > right),
+        Operator::LessThan => Some(left < right),
+        Operator::GreaterEqual => Some(left >= right),
+        Operator::LessEqual => Some(left <= right),
+        Operator::Equal => Some(left == right),
+        Operator::NotEqual => Some(left != right),
+        Operator::And => Some(left != zero && right != zero),
+        Operator::Or => Some(left != zero || right != zero),
+        _ => None,
+    }
+}
+
+/// True if `body` has nothing to run: no statements, or only empty ones
+fn is_empty_body(body: &[Statement]) -> bool {
+    body.iter().all(|stmt| match &stmt.node {
+        StatementKind::Empty => true,
+        StatementKind::Scope(statements) => is_empty_body(statements),
+        _ => false,
+    })
+}
@@ -1,4 +1,4 @@
-use crate::error_reporter::format_code_context;
+use crate::error_reporter::{DEFAULT_TAB_WIDTH, format_code_context};
 use crate::error_reporter::ErrorReporter;
 use colored::Colorize;
 use std::fmt;
@@ -21,6 +21,15 @@ pub enum SemanticError {
         column: usize,
     },
 
+    /// Array initializer list longer than the declared size
+    ArrayTooManyInitializers {
+        name: String,
+        declared_size: usize,
+        actual_count: usize,
+        line: usize,
+        column: usize,
+    },
+
     /// Variable not declared before use
     UndeclaredIdentifier {
         name: String,
@@ -37,6 +46,15 @@ pub enum SemanticError {
         original_column: usize,
     },
 
+    /// Name already bound to a constant declared again
+    ConstantRedeclaration {
+        name: String,
+        line: usize,
+        column: usize,
+        original_line: usize,
+        original_column: usize,
+    },
+
     /// Type mismatch in operations or assignments
     TypeMismatch {
         expected: String,
@@ -46,12 +64,27 @@ pub enum SemanticError {
         context: Option<String>,
     },
 
+    /// Constant initialized with a literal of another type
+    InvalidConstantInitializer {
+        name: String,
+        expected_type: String,
+        found_type: String,
+        line: usize,
+        column: usize,
+    },
+
     /// Division by zero
     DivisionByZero {
         line: usize,
         column: usize,
     },
 
+    /// Constant integer expression outside the Int range
+    IntegerOverflow {
+        line: usize,
+        column: usize,
+    },
+
     /// Attempt to modify a constant
     ConstantModification {
         name: String,
@@ -81,6 +114,38 @@ pub enum SemanticError {
         line: usize,
         column: usize,
     },
+    /// `output` format string with more or fewer placeholders than values
+    FormatArgumentCount {
+        expected: usize,
+        found: usize,
+        line: usize,
+        column: usize,
+    },
+    /// Inline assembly used without `--allow-unsafe`
+    UnsafeNotAllowed {
+        line: usize,
+        column: usize,
+    },
+
+    /// `break` that is not inside a `for` or `do ... while` loop
+    BreakOutsideLoop {
+        line: usize,
+        column: usize,
+    },
+
+    /// `continue` that is not inside a `for` or `do ... while` loop
+    ContinueOutsideLoop {
+        line: usize,
+        column: usize,
+    },
+
+    /// Program name that cannot be used as one, e.g. a keyword such as `Int`
+    InvalidProgramName {
+        name: String,
+        reason: String,
+        line: usize,
+        column: usize,
+    },
     EmptyProgram,
 }
 
@@ -107,11 +172,14 @@ impl ErrorReporter for SemanticError {
             if line <= lines.len() && line > 0 {
                 let line_content = lines[line - 1];
 
-                if let SemanticError::DuplicateDeclaration { original_line, .. } = self {
+                if let SemanticError::DuplicateDeclaration { original_line, .. }
+                | SemanticError::ConstantRedeclaration { original_line, .. } = self
+                {
                     result.push_str(&format_code_context(
                         line_content,
                         column,
                         self.get_token_length(),
+                        DEFAULT_TAB_WIDTH,
                     ));
 
                     if *original_line <= lines.len() {
@@ -128,6 +196,7 @@ impl ErrorReporter for SemanticError {
                         line_content,
                         column,
                         self.get_token_length(),
+                        DEFAULT_TAB_WIDTH,
                     ));
                 }
             }
@@ -154,6 +223,9 @@ impl ErrorReporter for SemanticError {
                 "Consider adjusting '{}' from size {} to {}.",
                 expected, actual, name
             )),
+            SemanticError::ArrayTooManyInitializers { .. } => Some(
+                "Either increase the array size or reduce the initializer list".to_string(),
+            ),
             SemanticError::UndeclaredIdentifier { name, .. } => {
                 Some(format!("Declare variable '{}' before using it", name))
             }
@@ -161,6 +233,10 @@ impl ErrorReporter for SemanticError {
                 "Use a different name for the second declaration of '{}'",
                 name
             )),
+            SemanticError::ConstantRedeclaration { name, .. } => Some(format!(
+                "'{}' is already a constant. Choose another name for this declaration",
+                name
+            )),
             SemanticError::TypeMismatch {
                 expected,
                 found,
@@ -184,9 +260,23 @@ impl ErrorReporter for SemanticError {
                     expected, found
                 )),
             },
+            SemanticError::InvalidConstantInitializer {
+                name,
+                expected_type,
+                found_type,
+                ..
+            } => Some(format!(
+                "Initialize '{}' with a {} literal, or declare it as {}",
+                name, expected_type, found_type
+            )),
             SemanticError::DivisionByZero { .. } => {
                 Some("Check for division by zero or ensure denominators are non-zero".to_string())
             }
+            SemanticError::IntegerOverflow { .. } => Some(format!(
+                "Keep constant integer expressions between {} and {}",
+                i32::MIN,
+                i32::MAX
+            )),
             SemanticError::ConstantModification { name, .. } => Some(format!(
                 "'{}' is a constant and cannot be modified. Consider using a variable instead",
                 name
@@ -207,6 +297,22 @@ impl ErrorReporter for SemanticError {
             SemanticError::InvalidArraySize { name, .. } => {
                 Some(format!("Declare array '{}' with a positive size", name))
             }
+            SemanticError::FormatArgumentCount { expected, .. } => Some(format!(
+                "Pass exactly {} value(s) after the format string, one per %d or %f",
+                expected
+            )),
+            SemanticError::UnsafeNotAllowed { .. } => Some(
+                "Compile with --allow-unsafe to enable inline assembly".to_string(),
+            ),
+            SemanticError::BreakOutsideLoop { .. } => Some(
+                "Move 'break' into the body of a for or do-while loop".to_string(),
+            ),
+            SemanticError::ContinueOutsideLoop { .. } => Some(
+                "Move 'continue' into the body of a for or do-while loop".to_string(),
+            ),
+            SemanticError::InvalidProgramName { .. } => Some(
+                "Name the program with an identifier that is not a keyword".to_string(),
+            ),
             SemanticError::EmptyProgram => Some("Program is empty. Add some code.".to_string()),
         }
     }
@@ -215,19 +321,32 @@ impl ErrorReporter for SemanticError {
         "Semantic Error".to_string()
     }
 
+    fn get_message(&self) -> String {
+        self.get_detailed_message()
+    }
+
     fn get_location_info(&self) -> (usize, usize) {
         match self {
             SemanticError::AssignmentToArray { line, column, .. } => (*line, *column),
             SemanticError::ArraySizeMismatch { line, column, .. } => (*line, *column),
+            SemanticError::ArrayTooManyInitializers { line, column, .. } => (*line, *column),
             SemanticError::UndeclaredIdentifier { line, column, .. } => (*line, *column),
             SemanticError::DuplicateDeclaration { line, column, .. } => (*line, *column),
+            SemanticError::ConstantRedeclaration { line, column, .. } => (*line, *column),
             SemanticError::TypeMismatch { line, column, .. } => (*line, *column),
+            SemanticError::InvalidConstantInitializer { line, column, .. } => (*line, *column),
             SemanticError::DivisionByZero { line, column } => (*line, *column),
+            SemanticError::IntegerOverflow { line, column } => (*line, *column),
             SemanticError::ConstantModification { line, column, .. } => (*line, *column),
             SemanticError::ArrayIndexOutOfBounds { line, column, .. } => (*line, *column),
             SemanticError::InvalidConditionValue { line, column, .. } => (*line, *column),
             SemanticError::NonArrayIndexing { line, column, .. } => (*line, *column),
             SemanticError::InvalidArraySize { line, column, .. } => (*line, *column),
+            SemanticError::FormatArgumentCount { line, column, .. } => (*line, *column),
+            SemanticError::UnsafeNotAllowed { line, column } => (*line, *column),
+            SemanticError::BreakOutsideLoop { line, column } => (*line, *column),
+            SemanticError::ContinueOutsideLoop { line, column } => (*line, *column),
+            SemanticError::InvalidProgramName { line, column, .. } => (*line, *column),
             SemanticError::EmptyProgram => (0, 0),
         }
     }
@@ -248,6 +367,15 @@ impl SemanticError {
                 "Array size mismatch for '{}': expected {}, found {}",
                 name, expected, actual
             ),
+            SemanticError::ArrayTooManyInitializers {
+                name,
+                declared_size,
+                actual_count,
+                ..
+            } => format!(
+                "Array '{}' declared with size {} but initialized with {} values",
+                name, declared_size, actual_count
+            ),
             SemanticError::UndeclaredIdentifier { name, .. } => {
                 format!("Undeclared identifier '{}'", name)
             }
@@ -262,6 +390,17 @@ impl SemanticError {
                     name, original_line, original_column
                 )
             }
+            SemanticError::ConstantRedeclaration {
+                name,
+                original_line,
+                original_column,
+                ..
+            } => {
+                format!(
+                    "Redeclaration of constant '{}' (defined at line {}, column {})",
+                    name, original_line, original_column
+                )
+            }
             SemanticError::TypeMismatch {
                 expected,
                 found,
@@ -274,7 +413,17 @@ impl SemanticError {
                 ),
                 None => format!("Type mismatch: expected {}, found {}", expected, found),
             },
+            SemanticError::InvalidConstantInitializer {
+                name,
+                expected_type,
+                found_type,
+                ..
+            } => format!(
+                "Constant '{}' declared as {} but initialized with a {} literal",
+                name, expected_type, found_type
+            ),
             SemanticError::DivisionByZero { .. } => "Division by zero detected".to_string(),
+            SemanticError::IntegerOverflow { .. } => "Integer overflow detected".to_string(),
             SemanticError::ConstantModification { name, .. } => {
                 format!("Attempt to modify constant '{}'", name)
             }
@@ -301,6 +450,22 @@ impl SemanticError {
                     size, name
                 )
             }
+            SemanticError::FormatArgumentCount {
+                expected, found, ..
+            } => format!(
+                "Format string has {} placeholder(s) but {} value(s) were given",
+                expected, found
+            ),
+            SemanticError::UnsafeNotAllowed { .. } => {
+                "Inline assembly is not allowed".to_string()
+            }
+            SemanticError::BreakOutsideLoop { .. } => "'break' outside of a loop".to_string(),
+            SemanticError::ContinueOutsideLoop { .. } => {
+                "'continue' outside of a loop".to_string()
+            }
+            SemanticError::InvalidProgramName { name, reason, .. } => {
+                format!("Invalid program name '{}': {}", name, reason)
+            }
             SemanticError::EmptyProgram => "Program is empty. Add some code.".to_string(),
         }
     }
@@ -309,15 +474,24 @@ impl SemanticError {
         match self {
             SemanticError::AssignmentToArray { name, .. } => name.len(),
             SemanticError::ArraySizeMismatch { name, .. } => name.len(),
+            SemanticError::ArrayTooManyInitializers { name, .. } => name.len(),
             SemanticError::UndeclaredIdentifier { name, .. } => name.len(),
             SemanticError::DuplicateDeclaration { name, .. } => name.len(),
+            SemanticError::ConstantRedeclaration { name, .. } => name.len(),
             SemanticError::TypeMismatch { .. } => 1, // Default token length
+            SemanticError::InvalidConstantInitializer { name, .. } => name.len(),
             SemanticError::DivisionByZero { .. } => 1,
+            SemanticError::IntegerOverflow { .. } => 1,
             SemanticError::ConstantModification { name, .. } => name.len(),
             SemanticError::ArrayIndexOutOfBounds { name, .. } => name.len(),
             SemanticError::InvalidConditionValue { found, .. } => found.len(),
             SemanticError::NonArrayIndexing { var_name, .. } => var_name.len(),
             SemanticError::InvalidArraySize { name, .. } => name.len(),
+            SemanticError::FormatArgumentCount { .. } => 1,
+            SemanticError::UnsafeNotAllowed { .. } => "asm".len(),
+            SemanticError::BreakOutsideLoop { .. } => "break".len(),
+            SemanticError::ContinueOutsideLoop { .. } => "continue".len(),
+            SemanticError::InvalidProgramName { name, .. } => name.len(),
             SemanticError::EmptyProgram => 0,
         }
     }
@@ -329,4 +503,32 @@ impl fmt::Display for SemanticError {
     }
 }
 
-impl std::error::Error for SemanticError {}
+impl std::error::Error for SemanticError {
+    // None of the current variants wrap another error. The match is kept
+    // exhaustive so that a future wrapping variant has to expose its cause here.
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SemanticError::AssignmentToArray { .. }
+            | SemanticError::ArraySizeMismatch { .. }
+            | SemanticError::ArrayTooManyInitializers { .. }
+            | SemanticError::UndeclaredIdentifier { .. }
+            | SemanticError::DuplicateDeclaration { .. }
+            | SemanticError::ConstantRedeclaration { .. }
+            | SemanticError::TypeMismatch { .. }
+            | SemanticError::InvalidConstantInitializer { .. }
+            | SemanticError::DivisionByZero { .. }
+            | SemanticError::IntegerOverflow { .. }
+            | SemanticError::ConstantModification { .. }
+            | SemanticError::ArrayIndexOutOfBounds { .. }
+            | SemanticError::InvalidConditionValue { .. }
+            | SemanticError::NonArrayIndexing { .. }
+            | SemanticError::InvalidArraySize { .. }
+            | SemanticError::FormatArgumentCount { .. }
+            | SemanticError::UnsafeNotAllowed { .. }
+            | SemanticError::BreakOutsideLoop { .. }
+            | SemanticError::ContinueOutsideLoop { .. }
+            | SemanticError::InvalidProgramName { .. }
+            | SemanticError::EmptyProgram => None,
+        }
+    }
+}
@@ -2,5 +2,6 @@ pub mod analyzer_core;
 pub mod error;
 pub mod symbol_table;
 pub mod source_map;
+pub mod warning;
 
 pub use analyzer_core::SemanticAnalyzer;
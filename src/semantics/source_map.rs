@@ -1,15 +1,17 @@
-use std::ops::Range;
+use crate::parser::ast::Span;
 
+/// Converts byte offsets in a source file to 1-based lines and columns
 pub struct SourceMap {
+    /// Byte offset where each line starts, in increasing order
     line_starts: Vec<usize>,
 }
 
 impl SourceMap {
-    pub fn new(source: &String) -> Self {
-        let line_starts = Self::compute_line_starts(&source);
+    pub fn new(source: &str) -> Self {
+        let line_starts = Self::compute_line_starts(source);
         Self { line_starts }
     }
-    
+
     fn compute_line_starts(source: &str) -> Vec<usize> {
         let mut starts = vec![0];
         for (i, c) in source.char_indices() {
@@ -19,27 +21,28 @@ impl SourceMap {
         }
         starts
     }
-    
-    pub fn get_line_column(&self, span: &Range<usize>) -> (usize, usize) {
-        // Binary search to find the line
-        let pos = span.start;
-        let line_idx = match self.line_starts.binary_search(&pos) {
+
+    /// Line and column of the byte at `offset`, found by binary search over
+    /// the line starts. Both are 1-based; the column counts bytes.
+    pub fn line_col(&self, offset: usize) -> (usize, usize) {
+        let line_idx = match self.line_starts.binary_search(&offset) {
             Ok(idx) => idx,
             Err(idx) => idx - 1,
         };
-        
-        let line = line_idx + 1; // 1-based line number
-        let column = pos - self.line_starts[line_idx] + 1; // 1-based column
-        
-        (line, column)
+
+        (line_idx + 1, offset - self.line_starts[line_idx] + 1)
     }
-    
-    pub fn get_line(&self, span: &Range<usize>) -> usize {
+
+    pub fn get_line_column(&self, span: &Span) -> (usize, usize) {
+        self.line_col(span.start)
+    }
+
+    pub fn get_line(&self, span: &Span) -> usize {
         let (line, _) = self.get_line_column(span);
         line
     }
-    
-    pub fn get_column(&self, span: &Range<usize>) -> usize {
+
+    pub fn get_column(&self, span: &Span) -> usize {
         let (_, column) = self.get_line_column(span);
         column
     }
@@ -1,7 +1,10 @@
 use crate::parser::ast::{LiteralKind, Type};
-use std::{collections::HashMap, default};
+use std::{
+    collections::{HashMap, HashSet},
+    default,
+};
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum SymbolKind {
     Variable,
     Constant,
@@ -12,6 +15,8 @@ pub enum SymbolKind {
 pub enum SymbolValue {
     Single(LiteralKind),
     Array(Vec<LiteralKind>),
+    /// Assigned by the program, with a value only known at run time
+    Assigned,
     Uninitialized,
 }
 
@@ -51,11 +56,59 @@ impl SymbolTable {
         self.symbols.contains_key(name)
     }
 
+    /// Checks if a symbol was declared in the innermost scope. MiniSoft only
+    /// declares symbols in the `Var` section, so there is a single scope.
+    pub fn contains_in_current_scope(&self, name: &str) -> bool {
+        self.symbols.contains_key(name)
+    }
+
+    /// Removes a symbol from the innermost scope and returns it
+    pub fn remove(&mut self, name: &str) -> Option<Symbol> {
+        self.symbols.remove(name)
+    }
+
     /// Gets a symbol by name
     pub fn get(&self, name: &str) -> Option<&Symbol> {
         self.symbols.get(name)
     }
 
+    /// Gets a symbol by name, unless nothing was stored in it yet
+    pub fn get_initialized(&self, name: &str) -> Option<&Symbol> {
+        self.symbols
+            .get(name)
+            .filter(|symbol| symbol.value != SymbolValue::Uninitialized)
+    }
+
+    /// Records that the program stores a value in `name`
+    pub fn mark_initialized(&mut self, name: &str) {
+        if let Some(symbol) = self.symbols.get_mut(name)
+            && symbol.value == SymbolValue::Uninitialized
+        {
+            symbol.value = SymbolValue::Assigned;
+        }
+    }
+
+    /// Names of the symbols nothing was stored in yet
+    pub fn uninitialized(&self) -> HashSet<String> {
+        self.symbols
+            .values()
+            .filter(|symbol| symbol.value == SymbolValue::Uninitialized)
+            .map(|symbol| symbol.name.clone())
+            .collect()
+    }
+
+    /// Undoes `mark_initialized` for `names`, when the stores happened on a
+    /// path that may not run
+    pub fn mark_uninitialized(&mut self, names: &HashSet<String>) {
+        for name in names {
+            if let Some(symbol) = self.symbols.get_mut(name)
+                && symbol.value == SymbolValue::Assigned
+            {
+                symbol.value = SymbolValue::Uninitialized;
+            }
+        }
+    }
+
     /// Gets all symbols
     pub fn get_all(&self) -> Vec<&Symbol> {
         self.symbols.values().collect()
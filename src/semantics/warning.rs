@@ -0,0 +1,175 @@
+use crate::error_reporter::{DEFAULT_TAB_WIDTH, format_code_context};
+use crate::error_reporter::ErrorReporter;
+use colored::Colorize;
+use std::fmt;
+
+/// Diagnostics that do not stop compilation.
+///
+/// Each variant has a stable code (`W0001`, ...) that can be used to select
+/// warnings on the command line with `--warnings=<codes>`.
+#[derive(Debug, Clone)]
+pub enum SemanticWarning {
+    /// Condition whose value is known at compile time
+    ConstantCondition {
+        value: bool,
+        context: String,
+        line: usize,
+        column: usize,
+    },
+    /// Inline assembly, which the compiler cannot check
+    InlineAssembly { line: usize, column: usize },
+    /// `for` loop whose constant bounds and step skip the body
+    ForLoopWillNeverExecute {
+        var: String,
+        from_val: i32,
+        to_val: i32,
+        step_val: i32,
+        line: usize,
+        column: usize,
+    },
+    /// Variable read before the program stores anything in it
+    UninitializedRead {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+    /// Declared name the program never reads, only reported with `--warn-unused`
+    UnusedVariable {
+        name: String,
+        line: usize,
+        column: usize,
+    },
+    /// `if`, `else` or loop body without any statement
+    EmptyBody {
+        construct: String,
+        line: usize,
+        column: usize,
+    },
+}
+
+impl SemanticWarning {
+    pub fn code(&self) -> &'static str {
+        match self {
+            SemanticWarning::ConstantCondition { .. } => "W0001",
+            SemanticWarning::InlineAssembly { .. } => "W0002",
+            SemanticWarning::ForLoopWillNeverExecute { .. } => "W0003",
+            SemanticWarning::UninitializedRead { .. } => "W0004",
+            SemanticWarning::UnusedVariable { .. } => "W0005",
+            SemanticWarning::EmptyBody { .. } => "W0006",
+        }
+    }
+
+    fn get_detailed_message(&self) -> String {
+        match self {
+            SemanticWarning::ConstantCondition { value, context, .. } => {
+                format!("The {} is always {}", context, value)
+            }
+            SemanticWarning::InlineAssembly { .. } => {
+                "Inline assembly is not checked by the compiler".to_string()
+            }
+            SemanticWarning::ForLoopWillNeverExecute {
+                var,
+                from_val,
+                to_val,
+                step_val,
+                ..
+            } => format!(
+                "The loop over '{}' never executes: it goes from {} to {} with step {}",
+                var, from_val, to_val, step_val
+            ),
+            SemanticWarning::UninitializedRead { name, .. } => {
+                format!("'{}' is read before it is assigned a value", name)
+            }
+            SemanticWarning::UnusedVariable { name, .. } => {
+                format!("'{}' is declared but never read", name)
+            }
+            SemanticWarning::EmptyBody { construct, .. } => {
+                format!("The body of this {} is empty", construct)
+            }
+        }
+    }
+}
+
+impl ErrorReporter for SemanticWarning {
+    fn report(&self, source_code: Option<&str>) -> String {
+        let mut result = String::new();
+
+        result.push_str(&format!(
+            "{}: {}\n",
+            format!("Warning[{}]", self.code()).yellow().bold(),
+            self.get_detailed_message()
+        ));
+
+        let (line, column) = self.get_location_info();
+        result.push_str(&format!(
+            "{} line {}, column {}\n",
+            "-->".blue(),
+            line,
+            column
+        ));
+
+        if let Some(source) = source_code {
+            let lines: Vec<&str> = source.lines().collect();
+            if line <= lines.len() && line > 0 {
+                result.push_str(&format_code_context(lines[line - 1], column, 1, DEFAULT_TAB_WIDTH));
+            }
+        }
+
+        if let Some(suggestion) = self.get_suggestion() {
+            result.push_str(&format!("{}: {}\n", "Suggestion".cyan().bold(), suggestion));
+        }
+
+        result
+    }
+
+    fn get_suggestion(&self) -> Option<String> {
+        match self {
+            SemanticWarning::ConstantCondition { .. } => Some(
+                "Make the condition depend on a runtime value, or remove the check".to_string(),
+            ),
+            SemanticWarning::InlineAssembly { .. } => Some(
+                "Make sure the instructions preserve the registers and stack the program uses"
+                    .to_string(),
+            ),
+            SemanticWarning::ForLoopWillNeverExecute { .. } => {
+                Some("Swap the bounds or change the sign of the step".to_string())
+            }
+            SemanticWarning::UninitializedRead { name, .. } => Some(format!(
+                "Give '{}' an initial value in its declaration, or assign it first",
+                name
+            )),
+            SemanticWarning::UnusedVariable { name, .. } => {
+                Some(format!("Remove the declaration of '{}' if it is not needed", name))
+            }
+            SemanticWarning::EmptyBody { construct, .. } => Some(format!(
+                "Add the statements the {} should run, or remove it",
+                construct
+            )),
+        }
+    }
+
+    fn get_error_name(&self) -> String {
+        "Warning".to_string()
+    }
+
+    fn get_message(&self) -> String {
+        self.get_detailed_message()
+    }
+
+    fn get_location_info(&self) -> (usize, usize) {
+        match self {
+            SemanticWarning::ConstantCondition { line, column, .. } => (*line, *column),
+            SemanticWarning::InlineAssembly { line, column } => (*line, *column),
+            SemanticWarning::ForLoopWillNeverExecute { line, column, .. } => (*line, *column),
+            SemanticWarning::UninitializedRead { line, column, .. } => (*line, *column),
+            SemanticWarning::UnusedVariable { line, column, .. } => (*line, *column),
+            SemanticWarning::EmptyBody { line, column, .. } => (*line, *column),
+        }
+    }
+}
+
+impl fmt::Display for SemanticWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.report(None))
+    }
+}
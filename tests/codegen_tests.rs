@@ -0,0 +1,1455 @@
+#[cfg(test)]
+mod codegen_tests {
+    use rust_compiler::codegen::assembly_gen::{AssemblyGenerator, Target};
+    use rust_compiler::codegen::generator::CodeGenerator;
+    use rust_compiler::lexer::lexer_core::tokenize;
+    use rust_compiler::parser::parser_core::parse;
+
+    /// Helper function to compile source code all the way down to assembly text
+    fn generate_assembly(source: &str) -> String {
+        let tokens = tokenize(source);
+        let program = match parse(tokens.0, source) {
+            Ok(program) => program,
+            Err(e) => panic!("Parse error: {}", e),
+        };
+
+        let quadruples = CodeGenerator::new()
+            .generate_code(&program)
+            .expect("Code generation failed");
+
+        AssemblyGenerator::new(quadruples).generate_to_string()
+    }
+
+    #[test]
+    fn test_sections_and_entry_point() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                x := 1;
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(
+            asm.starts_with("bits 64\ndefault rel\nglobal _start\n"),
+            "{}",
+            asm
+        );
+
+        let data = asm.find("section .data").expect("missing .data");
+        let bss = asm.find("section .bss").expect("missing .bss");
+        let text = asm.find("section .text").expect("missing .text");
+        assert!(data < bss && bss < text, "Sections out of order:\n{}", asm);
+        assert!(asm.contains("v_x: resq 1"));
+        assert!(asm.contains("mov [v_x], rax"));
+    }
+
+    #[test]
+    fn test_default_rel_replaces_explicit_rel() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let arr: [Int; 3];
+            let x: Int;
+            BeginPg
+            {
+                arr[1] := 5;
+                x := arr[1];
+                output("done");
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("default rel"));
+        assert!(asm.contains("lea rbx, [v_arr]"), "{}", asm);
+        assert!(!asm.contains("[rel "), "{}", asm);
+    }
+
+    #[test]
+    fn test_start_entry_point_and_exit_syscall() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            BeginPg
+            {
+                output("hi");
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("global _start"));
+        assert!(asm.contains("\n_start:\n"));
+
+        // The exit sequence must come after the program body and before the helpers
+        let exit = asm
+            .find("mov eax, 60\n    xor edi, edi\n    syscall")
+            .expect("missing exit syscall");
+        assert!(exit > asm.find("call print_string").unwrap());
+        assert!(exit < asm.find("print_int:").unwrap());
+    }
+
+    #[test]
+    fn test_initialized_declarations_in_data_section() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let count: Int = 42;
+            let ratio: Float = 2.5;
+            @define Const Limit: Int = 10;
+            BeginPg
+            {
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("v_count: dq 42"), "{}", asm);
+        assert!(asm.contains("v_ratio: dq 0x4004000000000000"), "{}", asm);
+        assert!(asm.contains("v_Limit: dq 10"), "{}", asm);
+    }
+
+    #[test]
+    fn test_output_uses_string_literals_and_print_helpers() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int = 3;
+            BeginPg
+            {
+                output("x = ", x);
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("str_0: db \"x = \", 0"), "{}", asm);
+        assert!(asm.contains("str_1: db 10, 0"), "{}", asm);
+        assert!(asm.contains("call print_string"));
+        assert!(asm.contains("call print_int"));
+        assert!(asm.contains("print_int:"));
+    }
+
+    #[test]
+    fn test_float_output_prints_fraction() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let y: Float = 3.14;
+            BeginPg
+            {
+                output(y, 1.5);
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert_eq!(asm.matches("call print_float").count(), 2, "{}", asm);
+        // Doubles are passed in xmm0, as in the System V ABI
+        assert_eq!(asm.matches("movq xmm0, rax\n    call print_float").count(), 2, "{}", asm);
+
+        // The helper writes the fraction after the integer part
+        let helper = &asm[asm.find("print_float:").unwrap()..];
+        let helper = &helper[..helper.find("\nprint_string:").unwrap()];
+        assert!(helper.contains("call print_int"), "{}", helper);
+        assert!(helper.contains("mov byte [rbp - 32], '.'"), "{}", helper);
+    }
+
+    #[test]
+    fn test_float_input_keeps_fraction() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let y: Float;
+            BeginPg
+            {
+                input(y);
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("call read_float"), "{}", asm);
+
+        // The helper parses the digits after the point instead of reading an integer
+        let helper = &asm[asm.find("read_float:").unwrap()..];
+        assert!(!helper.contains("call read_int"), "{}", helper);
+        assert!(helper.contains("cmp rcx, '.'"), "{}", helper);
+        assert!(helper.contains("divsd xmm0, xmm1"), "{}", helper);
+    }
+
+    #[test]
+    fn test_string_input_reads_into_a_buffer() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let name: String;
+            let greeting: String = "Hello, ";
+            BeginPg
+            {
+                input(name);
+                output(greeting, name);
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("input_0: resb 256"), "{}", asm);
+        assert!(asm.contains("lea rdi, [input_0]\n    call read_string"), "{}", asm);
+        assert!(asm.contains("mov [v_name], rax"), "{}", asm);
+        assert!(asm.contains("read_string:"), "{}", asm);
+    }
+
+    #[test]
+    fn test_labels_carry_names() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, x: Int;
+            BeginPg
+            {
+                for i from 0 to 3 step 1 {
+                    if (i > 1) then {
+                        x := i;
+                    }
+                }
+            }
+            EndPg;
+        "#;
+
+        let quads: Vec<String> = generate_quadruples(source)
+            .quadruples
+            .iter()
+            .map(|quad| quad.to_string())
+            .collect();
+        assert!(quads.contains(&"(LABEL_1(for_loop_start), _, _, _)".to_string()), "{:?}", quads);
+        assert!(quads.contains(&"(LABEL_3(if_end), _, _, _)".to_string()), "{:?}", quads);
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("\nfor_loop_start_L1:\n"), "{}", asm);
+        assert!(asm.contains("jmp for_loop_start_L1\n"), "{}", asm);
+        assert!(asm.contains("je for_loop_end_L2\n"), "{}", asm);
+        assert!(asm.contains("je if_end_L3\n"), "{}", asm);
+    }
+
+    #[test]
+    fn test_profile_counts_cycles_per_block() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                do {
+                    x := x + 1;
+                } while (x < 3);
+            }
+            EndPg;
+        "#;
+
+        let plain = generate_assembly(source);
+        assert!(!plain.contains("prof_"), "{}", plain);
+
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        let quadruples = CodeGenerator::new().generate_code(&program).unwrap();
+        let mut generator = AssemblyGenerator::new(quadruples);
+        generator.set_profile(true);
+        let asm = generator.generate_to_string();
+
+        assert!(asm.contains("prof_count: dq 2"), "{}", asm);
+        assert!(asm.contains("prof_cycles: resq 2"), "{}", asm);
+        assert!(asm.contains("str_0: db \"entry: \", 0"), "{}", asm);
+        assert!(
+            asm.contains("do_while_start_L1:\n    mov rdi, 1\n    call profile_enter"),
+            "{}",
+            asm
+        );
+        assert!(asm.contains("call profile_report\n    mov eax, 60"), "{}", asm);
+        assert!(asm.contains("profile_enter:\n    rdtsc"), "{}", asm);
+    }
+
+    #[test]
+    fn test_modulo_uses_idiv_remainder() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: Int;
+            BeginPg
+            {
+                a := a % 4;
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("; (MOD, a, 4, t1)"), "{}", asm);
+        assert!(asm.contains("idiv rcx\n    mov rax, rdx"), "{}", asm);
+    }
+
+    #[test]
+    fn test_unary_minus_emits_negate() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: Int;
+            let f: Float;
+            BeginPg
+            {
+                a := -a;
+                f := -f;
+            }
+            EndPg;
+        "#;
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("; (NEG, a, _, t1)"), "{}", asm);
+        assert!(asm.contains("neg rax"), "{}", asm);
+        assert!(asm.contains("btc rax, 63"), "{}", asm);
+    }
+
+    #[test]
+    fn test_pretty_print_three_address_code() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            let v: [Int; 2];
+            BeginPg
+            {
+                while (i < 2) {
+                    v[i] := -i;
+                    i := i + 1;
+                }
+                output(v[1]);
+            }
+            EndPg;
+        "#;
+
+        let expected = "    decl i: Int
+    decl v: [Int; 2]
+L1:  ; while_condition
+    t1 := i < 2
+    iffalse t1 goto L2
+    t2 := -i
+    v[i] := t2
+    t3 := i + 1
+    i := t3
+    goto L1
+L2:  ; while_end
+    t4 := v[1]
+    output t4
+    output \"\\n\"
+";
+        assert_eq!(generate_quadruples(source).pretty_print(), expected);
+    }
+
+    #[test]
+    fn test_array_initializers_are_evaluated_once() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: Int = 4;
+            let v, w: [Int; 2] = {a * 2, 7};
+            BeginPg
+            {
+                a := v[0] + w[0];
+            }
+            EndPg;
+        "#;
+
+        let quadruples: Vec<String> = generate_quadruples(source)
+            .quadruples
+            .iter()
+            .map(|quad| quad.to_string())
+            .collect();
+        assert_eq!(
+            quadruples[1..8],
+            [
+                "(MUL, a, 2, t1)",
+                "(DECL_Int[2], _, _, v)",
+                "(ASTORE, t1, 0, v)",
+                "(ASTORE, 7, 1, v)",
+                "(DECL_Int[2], _, _, w)",
+                "(ASTORE, t1, 0, w)",
+                "(ASTORE, 7, 1, w)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_literal_arithmetic_is_folded() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: Int;
+            let x: Float;
+            let v: [Int; 4];
+            BeginPg
+            {
+                a := 1 + 2 * 3;
+                v[10 / 5] := a + 7 % 4;
+                a := 32767 * 32767 * 32767;
+                x := 1.5 * 2.0 - 0.5;
+                a := a / (2 - 2);
+                output(a, v[2], x);
+            }
+            EndPg;
+        "#;
+        let program = rust_compiler::parser::parser_core::parse_source(source).unwrap();
+        let dump = |level| -> Vec<String> {
+            CodeGenerator::new_with_optimization(level)
+                .generate_code(&program)
+                .unwrap()
+                .quadruples
+                .iter()
+                .map(|quad| quad.to_string())
+                .collect()
+        };
+
+        // Level 0 leaves the arithmetic as written
+        let unoptimized = dump(0);
+        for expected in ["(MUL, 2, 3, t1)", "(ADD, 1, t1, t2)", "(MUL, 1.5, 2, t8)"] {
+            assert!(unoptimized.contains(&expected.to_string()), "{:?}", unoptimized);
+        }
+
+        let quadruples = dump(1);
+        for expected in [
+            "(ASSIGN, 7, _, a)",
+            "(ADD, a, 3, t1)",
+            "(ASTORE, t1, 2, v)",
+            // Overflowing i32 is left to run time
+            "(MUL, 1073676289, 32767, t2)",
+            "(ASSIGN, 2.5, _, x)",
+            // So is division by zero
+            "(DIV, a, 0, t3)",
+        ] {
+            assert!(quadruples.contains(&expected.to_string()), "{:?}", quadruples);
+        }
+    }
+
+    #[test]
+    fn test_folded_floats_keep_run_time_precision() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Float;
+            BeginPg
+            {
+                x := 16777216.0 + 1.0;
+                output(x);
+            }
+            EndPg;
+        "#;
+        let program = rust_compiler::parser::parser_core::parse_source(source).unwrap();
+        let run = |level| {
+            let quadruples = CodeGenerator::new_with_optimization(level)
+                .generate_code(&program)
+                .unwrap();
+            let mut output = Vec::new();
+            QuadrupleInterpreter::new()
+                .run(&quadruples.quadruples, &mut "".as_bytes(), &mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        // 2^24 + 1 is not an f32
+        assert_eq!(run(1), run(0));
+        assert_eq!(run(1), "16777217.0\n");
+    }
+
+    #[test]
+    fn test_input_into_float_array_element_reads_a_float() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: [Float; 3];
+            let i: Int;
+            BeginPg
+            {
+                i := 1;
+                input(a[i]);
+                output(a[1]);
+            }
+            EndPg;
+        "#;
+        let quadruples = generate_quadruples(source);
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&quadruples.quadruples, &mut "2.5\n".as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "2.5\n");
+    }
+
+    #[test]
+    fn test_level_two_propagates_constants_through_ssa_form() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, n, acc: Int;
+            let f: Float;
+            BeginPg
+            {
+                n := 4;
+                f := 1.5;
+                acc := 0;
+                for i from 1 to n step 1 {
+                    if (i > 2) then {
+                        acc := acc + i;
+                    }
+                    if (i < 3) then {
+                        f := f * 2.0;
+                    }
+                }
+                output(acc, " ", f, " ");
+                input(n);
+                output(n);
+            }
+            EndPg;
+        "#;
+        let program = rust_compiler::parser::parser_core::parse_source(source).unwrap();
+        let generate =
+            |level| CodeGenerator::new_with_optimization(level).generate_code(&program).unwrap();
+
+        let unoptimized = generate(0);
+        let optimized = generate(2);
+        let dump = optimized.dump_annotated();
+        // The loop bound is a literal, the variable read after input() is not
+        assert!(dump.contains(", 4, t"), "{}", dump);
+        assert!(!dump.contains("PHI"), "{}", dump);
+
+        let run = |quadruples: &rust_compiler::codegen::quadruple::QuadrupleProgram| {
+            let mut output = Vec::new();
+            QuadrupleInterpreter::new()
+                .run(&quadruples.quadruples, &mut "9\n".as_bytes(), &mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(run(&optimized), run(&unoptimized));
+        assert_eq!(run(&optimized), "3 6.0 \n9\n");
+    }
+
+    #[test]
+    fn test_optimization_removes_dead_stores() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, y, i: Int;
+            let v: [Int; 3];
+            BeginPg
+            {
+                x := 5;
+                x := 10;
+                y := x * 2;
+                v[0] := 1;
+                i := 0;
+                while (i < 3) {
+                    output(i);
+                    i := i + 1;
+                }
+                output(x);
+            }
+            EndPg;
+        "#;
+        let program = rust_compiler::parser::parser_core::parse_source(source).unwrap();
+        let generate =
+            |level| CodeGenerator::new_with_optimization(level).generate_code(&program).unwrap();
+
+        let unoptimized = generate(0);
+        let optimized = generate(1);
+        let dump = optimized.dump_annotated();
+        // Overwritten before any read, never read, and in an array never read
+        assert!(unoptimized.dump_annotated().contains("(ASSIGN, 5, _, x)"));
+        assert!(!dump.contains("(ASSIGN, 5, _, x)"), "{}", dump);
+        assert!(unoptimized.dump_annotated().contains("(ASSIGN, t1, _, y)"));
+        assert!(!dump.contains("(ASSIGN, t1, _, y)"), "{}", dump);
+        assert!(!dump.contains("ASTORE"), "{}", dump);
+        // Read by the next iteration through the jump back to the condition
+        assert!(dump.contains("(ASSIGN, 10, _, x)"), "{}", dump);
+        assert!(dump.contains("(ADD, i, 1, t3)"), "{}", dump);
+        assert!(dump.contains("(ASSIGN, t3, _, i)"), "{}", dump);
+
+        let run = |quadruples: &rust_compiler::codegen::quadruple::QuadrupleProgram| {
+            let mut output = Vec::new();
+            QuadrupleInterpreter::new()
+                .run(&quadruples.quadruples, &mut "".as_bytes(), &mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(run(&optimized), run(&unoptimized));
+        assert_eq!(run(&optimized), "0\n1\n2\n10\n");
+    }
+
+    #[test]
+    fn test_optimization_removes_unreachable_code() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                while (i < 5) {
+                    output(i);
+                    break;
+                    i := i + 1;
+                    output(i);
+                }
+                output(i + 10);
+            }
+            EndPg;
+        "#;
+        let program = rust_compiler::parser::parser_core::parse_source(source).unwrap();
+        let generate =
+            |level| CodeGenerator::new_with_optimization(level).generate_code(&program).unwrap();
+
+        let unoptimized = generate(0);
+        let optimized = generate(1);
+        let dump = optimized.dump_annotated();
+        assert!(unoptimized.dump_annotated().contains("(ADD, i, 1, t2)"));
+        assert!(!dump.contains("(ADD, i, 1, t2)"), "{}", dump);
+        // The jump back to the condition is only reachable after the dead code
+        assert!(!dump.contains("JUMP_1(while_condition)"), "{}", dump);
+        assert!(dump.contains("(LABEL_2(while_end), _, _, _)"), "{}", dump);
+
+        let run = |quadruples: &rust_compiler::codegen::quadruple::QuadrupleProgram| {
+            let mut output = Vec::new();
+            QuadrupleInterpreter::new()
+                .run(&quadruples.quadruples, &mut "".as_bytes(), &mut output)
+                .unwrap();
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(run(&optimized), run(&unoptimized));
+        assert_eq!(run(&optimized), "0\n10\n");
+    }
+
+    #[test]
+    fn test_generate_to_string_is_repeatable() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b: Int;
+            BeginPg
+            {
+                a := 1;
+                b := a * 2 + 3;
+            }
+            EndPg;
+        "#;
+
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        let quadruples = CodeGenerator::new().generate_code(&program).unwrap();
+        let mut generator = AssemblyGenerator::new(quadruples);
+
+        assert_eq!(
+            generator.generate_to_string(),
+            generator.generate_to_string()
+        );
+    }
+
+    #[test]
+    fn test_reused_generator_numbers_temporaries_from_one() {
+        let first = r#"
+            MainPrgm first;
+            Var
+            let a, b: Int;
+            BeginPg
+            {
+                a := 1;
+                b := a * 2 + 3;
+            }
+            EndPg;
+        "#;
+        let second = r#"
+            MainPrgm second;
+            Var
+            let c: Int;
+            BeginPg
+            {
+                c := c - 1;
+            }
+            EndPg;
+        "#;
+        let parse_program = |source| parse(tokenize(source).0, source).expect("Parse error");
+
+        let mut generator = CodeGenerator::new();
+        let first = generator.generate_code(&parse_program(first)).unwrap();
+        let second = generator.generate_code(&parse_program(second)).unwrap();
+
+        assert_eq!(first.temp_count(), 2);
+        assert_eq!(second.temp_count(), 1);
+        assert_eq!(
+            second
+                .quadruples
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>(),
+            ["(DECL_Int, _, _, c)", "(SUB, c, 1, t1)", "(ASSIGN, t1, _, c)"]
+        );
+    }
+
+    #[test]
+    fn test_empty_statement_is_a_nop() {
+        use rust_compiler::parser::ast::{Located, StatementKind};
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a: Int;
+            BeginPg
+            {
+                a := 1;
+            }
+            EndPg;
+        "#;
+        let mut program = parse(tokenize(source).0, source).expect("Parse error");
+        program.statements.push(Located {
+            node: StatementKind::Empty,
+            span: 0..0,
+        });
+
+        let quadruples = CodeGenerator::new().generate_code(&program).unwrap();
+        let last = quadruples.quadruples.last().unwrap();
+        assert_eq!(last.to_string(), "(NOP, _, _, _)");
+        assert!(quadruples.pretty_print().ends_with("    nop\n"));
+
+        let asm = AssemblyGenerator::new(quadruples).generate_to_string();
+        assert!(asm.contains("    nop\n    mov eax, 60"), "{}", asm);
+    }
+
+    #[test]
+    fn test_empty_statements_compile_and_run() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = "MainPrgm test; Var let a: Int; BeginPg { ; a := 1; ; output(a); } EndPg;";
+        let program = rust_compiler::parser::parser_core::parse_source(source).unwrap();
+        let quadruples = CodeGenerator::new().generate_code(&program).unwrap();
+        let nops = quadruples.quadruples.iter().filter(|q| q.to_string() == "(NOP, _, _, _)");
+        assert_eq!(nops.count(), 2);
+
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&quadruples.quadruples, &mut "".as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "1\n");
+
+        let asm = AssemblyGenerator::new(quadruples).generate_to_string();
+        assert_eq!(asm.matches("    nop\n").count(), 2, "{}", asm);
+    }
+
+    #[test]
+    fn test_quadruples_are_hashable() {
+        use rust_compiler::codegen::quadruple::{Operand, Operation};
+        use rust_compiler::parser::ast::Type;
+        use std::collections::HashMap;
+
+        let mut seen: HashMap<(Operation, Operand, Operand), usize> = HashMap::new();
+        let key = (
+            Operation::Add,
+            Operand::Variable("a".to_string()),
+            Operand::FloatLiteral(1.5),
+        );
+        seen.insert(key.clone(), 1);
+        seen.insert(
+            (
+                Operation::DeclareVariable(Type::Float),
+                Operand::Empty,
+                Operand::Empty,
+            ),
+            2,
+        );
+
+        assert_eq!(seen.get(&key), Some(&1));
+        assert!(!seen.contains_key(&(
+            Operation::Add,
+            Operand::Variable("a".to_string()),
+            Operand::FloatLiteral(-1.5),
+        )));
+    }
+
+    fn generate_quadruples(source: &str) -> rust_compiler::codegen::QuadrupleProgram {
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        CodeGenerator::new().generate_code(&program).unwrap()
+    }
+
+    #[test]
+    fn test_output_format_string_is_split() {
+        use rust_compiler::codegen::{Operand, Operation};
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                output("x = %d!", x, 7);
+            }
+            EndPg;
+        "#;
+
+        let outputs: Vec<Operand> = generate_quadruples(source)
+            .quadruples
+            .into_iter()
+            .filter(|quad| quad.operation == Operation::Output)
+            .map(|quad| quad.operand1)
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                Operand::StringLiteral("x = ".to_string()),
+                Operand::Variable("x".to_string()),
+                Operand::StringLiteral("!".to_string()),
+                Operand::IntLiteral(7),
+                Operand::StringLiteral("\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_output_format_string_prints_escaped_percent() {
+        use rust_compiler::codegen::{Operand, Operation};
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                output("%d%% of 100%%", x);
+            }
+            EndPg;
+        "#;
+
+        let outputs: Vec<Operand> = generate_quadruples(source)
+            .quadruples
+            .into_iter()
+            .filter(|quad| quad.operation == Operation::Output)
+            .map(|quad| quad.operand1)
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                Operand::Variable("x".to_string()),
+                Operand::StringLiteral("% of 100%".to_string()),
+                Operand::StringLiteral("\n".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_for_loop_counts_down_with_negative_step() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, s: Int;
+            BeginPg
+            {
+                for i from 0 to 6 step 2 { output(i); }
+                for i from 5 to 2 step (-1) { output(i); }
+                s := -3;
+                for i from 9 to 0 step s { output(i); }
+                s := 4;
+                for i from 0 to 9 step s { output(i); }
+            }
+            EndPg;
+        "#;
+
+        let program = generate_quadruples(source);
+        let conditions: Vec<String> = program
+            .quadruples
+            .iter()
+            .filter(|quad| quad.to_string().contains(", i, "))
+            .map(|quad| quad.to_string())
+            .collect();
+        assert!(conditions.contains(&"(LT, i, 6, t1)".to_string()), "{:?}", conditions);
+        assert!(conditions.contains(&"(GT, i, 2, t3)".to_string()), "{:?}", conditions);
+
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&program.quadruples, &mut "".as_bytes(), &mut output)
+            .unwrap();
+        let printed = String::from_utf8(output).unwrap().replace('\n', " ");
+        assert_eq!(printed, "0 2 4 5 4 3 9 6 3 0 4 8 ");
+    }
+
+    #[test]
+    fn test_for_loop_counter_can_be_an_array_element() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let v: [Int; 2];
+            BeginPg
+            {
+                v[1] := 7;
+                for v[0] from 1 to 4 step 1 { output(v[0]); }
+                output(v[1]);
+            }
+            EndPg;
+        "#;
+        let program = generate_quadruples(source);
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&program.quadruples, &mut "".as_bytes(), &mut output)
+            .unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "1\n2\n3\n7\n");
+    }
+
+    #[test]
+    fn test_break_jumps_past_the_loop() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, j: Int;
+            BeginPg
+            {
+                for i from 0 to 10 step 1 {
+                    for j from 0 to 10 step 1 {
+                        if (j == 2) then { break; }
+                        output(i, j);
+                    }
+                    if (i == 1) then { break; }
+                }
+                j := 0;
+                do {
+                    j := j + 1;
+                    if (j == 3) then { break; }
+                } while (j < 10);
+                output(j);
+            }
+            EndPg;
+        "#;
+
+        let program = generate_quadruples(source);
+        let dump = program.dump_annotated();
+        assert!(dump.contains("(JUMP_2(for_loop_end), _, _, _)"), "{}", dump);
+        assert!(dump.contains("(LABEL_9(do_while_end), _, _, _)"), "{}", dump);
+
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&program.quadruples, &mut "".as_bytes(), &mut output)
+            .unwrap();
+        let printed = String::from_utf8(output).unwrap().replace('\n', " ");
+        assert_eq!(printed, "00 01 10 11 3 ");
+    }
+
+    #[test]
+    fn test_while_loop_checks_condition_first() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                i := 5;
+                while (i < 5) {
+                    output(i);
+                }
+                i := 0;
+                while (i < 3) {
+                    output(i);
+                    i := i + 1;
+                }
+            }
+            EndPg;
+        "#;
+
+        let program = generate_quadruples(source);
+        let dump = program.dump_annotated();
+        assert!(dump.contains("(JMPF_2(while_end), t1, _, _)"), "{}", dump);
+        assert!(dump.contains("(JUMP_1(while_condition), _, _, _)"), "{}", dump);
+
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&program.quadruples, &mut "".as_bytes(), &mut output)
+            .unwrap();
+        let printed = String::from_utf8(output).unwrap().replace('\n', " ");
+        assert_eq!(printed, "0 1 2 ");
+    }
+
+    #[test]
+    fn test_continue_skips_to_step_or_condition() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, j: Int;
+            BeginPg
+            {
+                for i from 0 to 5 step 1 {
+                    if (i % 2 == 0) then { continue; }
+                    output(i);
+                }
+                j := 0;
+                do {
+                    j := j + 1;
+                    if (j < 4) then { continue; }
+                    output(j);
+                } while (j < 5);
+            }
+            EndPg;
+        "#;
+
+        let program = generate_quadruples(source);
+        let dump = program.dump_annotated();
+        assert!(dump.contains("(LABEL_4(for_loop_step), _, _, _)"), "{}", dump);
+        assert!(dump.contains("(LABEL_7(do_while_condition), _, _, _)"), "{}", dump);
+
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&program.quadruples, &mut "".as_bytes(), &mut output)
+            .unwrap();
+        let printed = String::from_utf8(output).unwrap().replace('\n', " ");
+        assert_eq!(printed, "1 3 4 5 ");
+    }
+
+    #[test]
+    fn test_if_then_else_runs_only_one_branch() {
+        use rust_compiler::codegen::interpreter::QuadrupleInterpreter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                for i from 0 to 4 step 1 {
+                    if (i < 2) then {
+                        output("then");
+                    } else {
+                        output("else");
+                    }
+                }
+            }
+            EndPg;
+        "#;
+
+        let program = generate_quadruples(source);
+        let dump = program.dump_annotated();
+        assert!(dump.contains("(JUMP_4(if_end), _, _, _)"), "{}", dump);
+        assert!(dump.contains("(LABEL_4(if_end), _, _, _)"), "{}", dump);
+
+        let mut output = Vec::new();
+        QuadrupleInterpreter::new()
+            .run(&program.quadruples, &mut "".as_bytes(), &mut output)
+            .unwrap();
+        let printed = String::from_utf8(output).unwrap().replace('\n', " ");
+        assert_eq!(printed, "then then else else ");
+    }
+
+    #[test]
+    fn test_array_assignment_copies_memory() {
+        use rust_compiler::codegen::{Operand, Operation};
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b: [Int; 4];
+            BeginPg
+            {
+                a := b;
+            }
+            EndPg;
+        "#;
+
+        let quads = generate_quadruples(source).quadruples;
+        let copy = quads.last().unwrap();
+        assert_eq!(copy.operation, Operation::MemCopy(4));
+        assert_eq!(copy.operand1, Operand::Variable("b".to_string()));
+        assert_eq!(copy.result, Operand::Variable("a".to_string()));
+
+        let asm = generate_assembly(source);
+        assert!(asm.contains("lea rsi, [v_b]"));
+        assert!(asm.contains("lea rdi, [v_a]"));
+        assert!(asm.contains("mov rcx, 4"));
+        assert!(asm.contains("rep movsq"));
+    }
+
+    #[test]
+    fn test_quadruples_carry_source_lines() {
+        let source = "MainPrgm test;\nVar\nlet x: Int;\nBeginPg\n{\n  if (x > 0) then {\n    x := x - 1;\n  }\n  output(x);\n}\nEndPg;\n";
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).unwrap();
+        let quadruples = CodeGenerator::new()
+            .with_source(source)
+            .generate_code(&program)
+            .unwrap();
+
+        let lines: Vec<Option<usize>> = quadruples
+            .quadruples
+            .iter()
+            .map(|quad| quad.source_line())
+            .collect();
+        // Nested statements keep their own line
+        assert_eq!(lines.first(), Some(&Some(3)));
+        assert!(lines.contains(&Some(6)));
+        assert!(lines.contains(&Some(7)));
+        assert_eq!(lines.last(), Some(&Some(9)));
+        assert!(quadruples.dump_annotated().contains("; line 7"));
+        // Columns point at the start of the statement
+        assert!(quadruples.quadruples.iter().any(|q| q.source_location == Some((7, 5))));
+        let text = quadruples.pretty_print_with_locations();
+        assert!(text.contains(" := x - 1  ; 7:5\n"), "{}", text);
+        assert!(!quadruples.pretty_print().contains("; 7:5"));
+
+        // A directive each time the line changes, just before that line's code
+        let asm = AssemblyGenerator::new(quadruples).generate_to_string();
+        let directives: Vec<&str> = asm
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("%line"))
+            .collect();
+        assert_eq!(
+            directives,
+            vec!["%line 3+0", "%line 6+0", "%line 7+0", "%line 6+0", "%line 9+0"]
+        );
+        let sub = asm.find("; (SUB, x, 1, t2)").unwrap();
+        assert!(asm[..sub].trim_end().ends_with("%line 7+0"), "{}", asm);
+
+        // Without the source, nothing is tagged
+        assert!(generate_quadruples(source).quadruples.iter().all(|q| q.source_location.is_none()));
+    }
+
+    #[test]
+    fn test_interpreter_runs_quadruples() {
+        use rust_compiler::codegen::interpreter::{QuadrupleInterpreter, RuntimeError, Value};
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, sum: Int;
+            let values: [Float; 3];
+            BeginPg
+            {
+                input(sum);
+                for i from 0 to 3 step 1 {
+                    values[i] := i * 1.5;
+                    sum := sum + i;
+                }
+                if (sum > 10) then {
+                    output("big ", sum);
+                } else {
+                    output("small ", sum, " ", values[2], " ", 7 / 2);
+                }
+            }
+            EndPg;
+        "#;
+
+        let program = generate_quadruples(source);
+        let mut interpreter = QuadrupleInterpreter::new();
+        let mut output = Vec::new();
+        interpreter
+            .run(&program.quadruples, &mut "4\n".as_bytes(), &mut output)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "small 7 3.0 3\n");
+        assert_eq!(interpreter.get_variable("sum"), Some(&Value::Int(7)));
+
+        let program = generate_quadruples(
+            "MainPrgm test; Var let x: Int; BeginPg { input(x); x := 1 / x; } EndPg;",
+        );
+        let error = QuadrupleInterpreter::new()
+            .run(&program.quadruples, &mut "0\n".as_bytes(), &mut Vec::new())
+            .unwrap_err();
+        assert!(matches!(error, RuntimeError::DivisionByZero));
+    }
+
+    #[test]
+    fn test_inline_assembly_is_copied_verbatim() {
+        use rust_compiler::codegen::Operation;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            BeginPg
+            {
+                asm { "nop"; "mov rax, 60"; }
+            }
+            EndPg;
+        "#;
+
+        let quadruples = generate_quadruples(source);
+        assert_eq!(
+            quadruples.quadruples[0].operation,
+            Operation::InlineAsm(vec!["nop".to_string(), "mov rax, 60".to_string()])
+        );
+
+        let asm = AssemblyGenerator::new(quadruples).generate_to_string();
+        assert!(asm.contains("    nop\n    mov rax, 60\n"), "{}", asm);
+    }
+
+    #[test]
+    fn test_ssa_form_assigns_each_variable_once() {
+        use rust_compiler::codegen::{Operand, Operation};
+        use std::collections::HashSet;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, i: Int;
+            BeginPg
+            {
+                x := 1;
+                for i from 0 to 3 step 1 {
+                    x := x + i;
+                }
+                output(x);
+            }
+            EndPg;
+        "#;
+
+        let ssa = generate_quadruples(source).to_ssa_form();
+
+        let mut defined = HashSet::new();
+        for quad in &ssa.quadruples {
+            if let Operation::DeclareVariable(_) = quad.operation {
+                continue;
+            }
+            if let Operand::Variable(name) = &quad.result {
+                assert!(defined.insert(name.clone()), "{} assigned twice", name);
+            }
+        }
+
+        // The loop header merges the value from before the loop and from the body
+        let phis: Vec<_> = ssa
+            .quadruples
+            .iter()
+            .filter_map(|quad| match (&quad.operation, &quad.result) {
+                (Operation::Phi(sources), Operand::Variable(name)) => Some((name, sources)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(phis.len(), 2, "{:?}", phis);
+        for (name, sources) in phis {
+            assert_eq!(sources.len(), 2, "{}: {:?}", name, sources);
+            assert_ne!(sources[0].1, sources[1].1);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "phi-node reached the assembly generator")]
+    fn test_assembly_generator_rejects_phi_nodes() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                do {
+                    x := x + 1;
+                } while (x < 3);
+            }
+            EndPg;
+        "#;
+
+        let ssa = generate_quadruples(source).to_ssa_form();
+        AssemblyGenerator::new(ssa).generate_to_string();
+    }
+
+    #[test]
+    fn test_from_ssa_form_replaces_phi_nodes_with_copies() {
+        use rust_compiler::codegen::{Operand, Operation};
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            let f: Float = 0.5;
+            BeginPg
+            {
+                do {
+                    x := x + 1;
+                    f := f * 2.0;
+                } while (x < 3);
+                output(x, f);
+            }
+            EndPg;
+        "#;
+
+        let program = generate_quadruples(source).to_ssa_form().from_ssa_form();
+        assert!(
+            !program
+                .quadruples
+                .iter()
+                .any(|quad| matches!(quad.operation, Operation::Phi(_)))
+        );
+
+        // The back edge is a conditional jump into the join point, so its
+        // copies live in a split block that jumps back to the loop
+        let back_edge = program
+            .quadruples
+            .iter()
+            .position(|quad| matches!(quad.operation, Operation::JumpIfTrue { .. }))
+            .unwrap();
+        let Operation::JumpIfTrue { id: split, .. } = program.quadruples[back_edge].operation else {
+            unreachable!()
+        };
+        let split_start = program
+            .quadruples
+            .iter()
+            .position(|quad| matches!(quad.operation, Operation::Label { id, .. } if id == split))
+            .unwrap();
+        let copies: Vec<String> = program.quadruples[split_start + 1..split_start + 3]
+            .iter()
+            .map(|quad| quad.to_string())
+            .collect();
+        assert!(
+            copies.iter().all(|copy| copy.starts_with("(ASSIGN")),
+            "{:?}",
+            copies
+        );
+        assert!(matches!(
+            program.quadruples[split_start + 3].operation,
+            Operation::Jump { .. }
+        ));
+        assert!(
+            program
+                .quadruples
+                .iter()
+                .any(|quad| quad.result == Operand::Variable("x.1".to_string()))
+        );
+
+        AssemblyGenerator::new(program).generate_to_string();
+    }
+
+    #[test]
+    fn test_from_ssa_form_breaks_copy_cycles() {
+        use rust_compiler::codegen::{Operand, Operation, Quadruple, QuadrupleProgram};
+        use rust_compiler::parser::ast::Type;
+
+        let quad = |operation, operand1, result| {
+            Quadruple::new(operation, operand1, Operand::Empty, result)
+        };
+        let var = |name: &str| Operand::Variable(name.to_string());
+
+        // a.1 and b.1 swap on every iteration of the loop
+        let mut program = QuadrupleProgram::new();
+        program.next_label = 5;
+        for q in [
+            quad(Operation::Label { id: 1, name: None }, Operand::Empty, Operand::Empty),
+            quad(
+                Operation::DeclareVariable(Type::Int),
+                Operand::IntLiteral(1),
+                var("a"),
+            ),
+            quad(
+                Operation::DeclareVariable(Type::Int),
+                Operand::IntLiteral(2),
+                var("b"),
+            ),
+            quad(Operation::Label { id: 2, name: None }, Operand::Empty, Operand::Empty),
+            quad(
+                Operation::Phi(vec![(1, var("a")), (3, var("b.1"))]),
+                Operand::Empty,
+                var("a.1"),
+            ),
+            quad(
+                Operation::Phi(vec![(1, var("b")), (3, var("a.1"))]),
+                Operand::Empty,
+                var("b.1"),
+            ),
+            quad(Operation::JumpIfFalse { id: 4, name: None }, var("a.1"), Operand::Empty),
+            quad(Operation::Label { id: 3, name: None }, Operand::Empty, Operand::Empty),
+            quad(Operation::Jump { id: 2, name: None }, Operand::Empty, Operand::Empty),
+            quad(Operation::Label { id: 4, name: None }, Operand::Empty, Operand::Empty),
+        ] {
+            program.add(q);
+        }
+
+        let quads: Vec<String> = program
+            .from_ssa_form()
+            .quadruples
+            .iter()
+            .map(|quad| quad.to_string())
+            .collect();
+        assert_eq!(
+            quads,
+            [
+                "(LABEL_1, _, _, _)",
+                "(DECL_Int, 1, _, a)",
+                "(DECL_Int, 2, _, b)",
+                "(ASSIGN, a, _, a.1)",
+                "(ASSIGN, b, _, b.1)",
+                "(LABEL_2, _, _, _)",
+                "(JMPF_4, a.1, _, _)",
+                "(LABEL_3, _, _, _)",
+                "(ASSIGN, a.1, _, t1)",
+                "(ASSIGN, b.1, _, a.1)",
+                "(ASSIGN, t1, _, b.1)",
+                "(JUMP_2, _, _, _)",
+                "(LABEL_4, _, _, _)",
+            ]
+        );
+    }
+
+    fn generate_for_target(source: &str, target: Target) -> String {
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        let quadruples = CodeGenerator::new()
+            .generate_code(&program)
+            .expect("Code generation failed");
+
+        let mut generator = AssemblyGenerator::new(quadruples);
+        generator.set_target(target);
+        generator.generate_to_string()
+    }
+
+    const TARGET_SOURCE: &str = r#"
+        MainPrgm test;
+        Var
+        let x: Int = 5;
+        let f: Float = 1.5;
+        BeginPg
+        {
+            if (x > 2) then {
+                output(x, f);
+            }
+        }
+        EndPg;
+    "#;
+
+    #[test]
+    fn test_gas_target() {
+        let assembly = generate_for_target(TARGET_SOURCE, Target::Gas);
+
+        assert!(assembly.contains(".intel_syntax noprefix"), "{}", assembly);
+        assert!(assembly.contains("v_x: .quad 5"), "{}", assembly);
+        assert!(assembly.contains("[rip + v_x]"), "{}", assembly);
+        assert!(assembly.contains("qword ptr ["), "{}", assembly);
+        assert!(!assembly.contains("section "), "{}", assembly);
+        assert!(!assembly.contains("default rel"), "{}", assembly);
+    }
+
+    #[test]
+    fn test_llvm_ir_target() {
+        let ir = generate_for_target(TARGET_SOURCE, Target::LlvmIr);
+
+        assert!(ir.contains("define i32 @main()"), "{}", ir);
+        assert!(ir.contains("@v_x = global i64 5"), "{}", ir);
+        assert!(ir.contains("br i1"), "{}", ir);
+        assert!(ir.contains("call void @print_float"), "{}", ir);
+    }
+}
@@ -0,0 +1,77 @@
+#[cfg(test)]
+mod fixture_tests {
+    use rust_compiler::error_reporter::ErrorReporter;
+    use rust_compiler::parser::parser_core::parse_source;
+    use rust_compiler::semantics::analyzer_core::SemanticAnalyzer;
+    use std::fmt::Debug;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    /// `Variant: message`, e.g. `UndeclaredIdentifier: Undeclared identifier 'y'`
+    fn error_line(error: &(impl ErrorReporter + Debug)) -> String {
+        let debug = format!("{:?}", error);
+        let variant: String = debug
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        format!("{}: {}", variant, error.get_message())
+    }
+
+    fn fixtures(dir: &str) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = fs::read_dir(Path::new("tests/fixtures").join(dir))
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ms"))
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Compares `actual` with the `.expected-errors` file next to `source`.
+    /// Run with `UPDATE_FIXTURES=1` to rewrite the expected files instead.
+    fn check(source: &Path, actual: Vec<String>, failures: &mut Vec<String>) {
+        let expected_path = source.with_extension("expected-errors");
+        let actual = actual.join("\n") + "\n";
+        if std::env::var("UPDATE_FIXTURES").is_ok_and(|value| value == "1") {
+            fs::write(&expected_path, &actual).unwrap();
+            return;
+        }
+
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if expected != actual {
+            failures.push(format!(
+                "{}\nexpected:\n{}actual:\n{}",
+                source.display(),
+                expected,
+                actual
+            ));
+        }
+    }
+
+    #[test]
+    fn test_semantic_error_fixtures() {
+        let mut failures = Vec::new();
+        for path in fixtures("semantic") {
+            let source = fs::read_to_string(&path).unwrap();
+            let program = parse_source(&source)
+                .unwrap_or_else(|e| panic!("{} does not parse: {}", path.display(), e));
+            let mut analyzer = SemanticAnalyzer::new(&source);
+            analyzer.analyze(&program);
+
+            let errors = analyzer.get_errors().iter().map(error_line).collect();
+            check(&path, errors, &mut failures);
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn test_syntax_error_fixtures() {
+        let mut failures = Vec::new();
+        for path in fixtures("syntax") {
+            let source = fs::read_to_string(&path).unwrap();
+            let errors = parse_source(&source).err().iter().map(error_line).collect();
+            check(&path, errors, &mut failures);
+        }
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+}
@@ -46,6 +46,125 @@ mod integration_test {
         }
     }
 
+    #[test]
+    fn test_all_valid_examples_assemble_and_link() {
+        use rust_compiler::compiler::Compiler;
+        use std::process::Command;
+
+        // Linking needs NASM and binutils; skip where they are not installed
+        if Command::new("nasm").arg("-v").output().is_err()
+            || Command::new("ld").arg("--version").output().is_err()
+        {
+            return;
+        }
+
+        let dir = std::env::temp_dir().join("msrc_link_test");
+        fs::create_dir_all(&dir).unwrap();
+        for entry in fs::read_dir("examples/valid").unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().map(|s| s == "ms").unwrap_or(false) {
+                let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+                let assembly = dir.join(format!("{}.asm", stem));
+                let object = dir.join(format!("{}.o", stem));
+                let binary = dir.join(&stem);
+
+                let mut compiler = Compiler::new(path.to_str().unwrap()).unwrap();
+                compiler.with_output(&assembly);
+                let result = compiler.compile();
+                assert!(result.is_success(), "File {:?} failed: {:?}", path, result.diagnostics);
+                fs::write(&assembly, result.assembly.unwrap()).unwrap();
+
+                let assembled = Command::new("nasm")
+                    .args(["-f", "elf64"])
+                    .arg(&assembly)
+                    .arg("-o")
+                    .arg(&object)
+                    .output()
+                    .unwrap();
+                assert!(
+                    assembled.status.success(),
+                    "File {:?} did not assemble: {}",
+                    path,
+                    String::from_utf8_lossy(&assembled.stderr)
+                );
+
+                let linked = Command::new("ld")
+                    .arg(&object)
+                    .arg("-o")
+                    .arg(&binary)
+                    .output()
+                    .unwrap();
+                assert!(
+                    linked.status.success(),
+                    "File {:?} did not link: {}",
+                    path,
+                    String::from_utf8_lossy(&linked.stderr)
+                );
+            }
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_all_valid_examples_assemble_and_link_with_gas() {
+        use rust_compiler::codegen::assembly_gen::Target;
+        use rust_compiler::compiler::Compiler;
+        use std::process::Command;
+
+        // Linking needs binutils; skip where they are not installed
+        if Command::new("as").arg("--version").output().is_err()
+            || Command::new("ld").arg("--version").output().is_err()
+        {
+            return;
+        }
+
+        let dir = std::env::temp_dir().join("msrc_gas_link_test");
+        fs::create_dir_all(&dir).unwrap();
+        for entry in fs::read_dir("examples/valid").unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().map(|s| s == "ms").unwrap_or(false) {
+                let stem = path.file_stem().unwrap().to_string_lossy().to_string();
+                let assembly = dir.join(format!("{}.s", stem));
+                let object = dir.join(format!("{}.o", stem));
+                let binary = dir.join(&stem);
+
+                let mut compiler = Compiler::new(path.to_str().unwrap()).unwrap();
+                compiler.with_output(&assembly);
+                compiler.set_target(Target::Gas);
+                let result = compiler.compile();
+                assert!(result.is_success(), "File {:?} failed: {:?}", path, result.diagnostics);
+                fs::write(&assembly, result.assembly.unwrap()).unwrap();
+
+                let assembled = Command::new("as")
+                    .arg(&assembly)
+                    .arg("-o")
+                    .arg(&object)
+                    .output()
+                    .unwrap();
+                assert!(
+                    assembled.status.success(),
+                    "File {:?} did not assemble: {}",
+                    path,
+                    String::from_utf8_lossy(&assembled.stderr)
+                );
+
+                let linked = Command::new("ld")
+                    .arg(&object)
+                    .arg("-o")
+                    .arg(&binary)
+                    .output()
+                    .unwrap();
+                assert!(
+                    linked.status.success(),
+                    "File {:?} did not link: {}",
+                    path,
+                    String::from_utf8_lossy(&linked.stderr)
+                );
+            }
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
     #[test]
     fn test_all_invalid_examples() {
         let paths = fs::read_dir("examples/invalid").unwrap();
@@ -62,4 +181,361 @@ mod integration_test {
             }
         }
     }
+
+    #[test]
+    fn test_compiler_with_output_path() {
+        use rust_compiler::compiler::Compiler;
+        use std::path::Path;
+
+        let output = std::env::temp_dir().join("msrc_with_output_test.asm");
+        let _ = fs::remove_file(&output);
+
+        let mut compiler = Compiler::new("examples/valid/sample_program.ms").unwrap();
+        compiler.with_output(&output).with_source_name("sample.ms");
+        assert!(compiler.run().is_ok());
+
+        let asm = fs::read_to_string(&output).expect("assembly was not written");
+        assert!(asm.contains("_start:"));
+        assert!(!Path::new("examples/valid/sample_program.asm").exists());
+        let _ = fs::remove_file(&output);
+    }
+
+    #[test]
+    fn test_compile_file_valid_example() {
+        use std::path::Path;
+
+        let result = rust_compiler::compile_file(Path::new("examples/valid/sample_program.ms"));
+        assert!(result.is_success(), "{:?}", result.diagnostics);
+        assert!(result.quadruples.is_some());
+        assert!(result.assembly.is_some());
+    }
+
+    #[test]
+    fn test_compile_file_missing_file() {
+        use std::path::Path;
+
+        let result = rust_compiler::compile_file(Path::new("examples/does_not_exist.ms"));
+        assert!(!result.is_success());
+        assert_eq!(result.errors().next().unwrap().kind, "IO Error");
+    }
+
+    #[test]
+    fn test_compile_source_diagnostics() {
+        let result = rust_compiler::compile_source("MainPrgm test; Var BeginPg { x := ; } EndPg;");
+        assert!(!result.is_success());
+        assert_eq!(result.errors().next().unwrap().kind, "Syntax Error");
+        assert!(result.assembly.is_none());
+
+        let result = rust_compiler::compile_source(
+            "MainPrgm test; Var let x: Int; BeginPg { if (1 > 2) then { x := 1; } } EndPg;",
+        );
+        assert!(result.is_success());
+        let warning = result.warnings().next().unwrap();
+        assert_eq!(warning.code.as_deref(), Some("W0001"));
+        assert_eq!((warning.line, warning.column), (1, 46));
+    }
+
+    #[test]
+    fn test_run_writes_the_compiled_assembly() {
+        use rust_compiler::compiler::Compiler;
+
+        let output = std::env::temp_dir().join("msrc_run_compile_test.asm");
+        let source = "MainPrgm test; Var let x: Int; BeginPg { x := 1; } EndPg;";
+
+        let mut compiler = Compiler::from_source(source);
+        compiler.with_output(&output);
+        assert!(compiler.run().is_ok());
+
+        let written = fs::read_to_string(&output).expect("assembly was not written");
+        assert_eq!(Some(written), rust_compiler::compile_source(source).assembly);
+        let _ = fs::remove_file(&output);
+
+        let mut compiler = Compiler::from_source("MainPrgm test; Var BeginPg { x := 1; } EndPg;");
+        compiler.with_output(&output);
+        assert_eq!(compiler.run(), Err(1));
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn test_diagnostics_to_json() {
+        use rust_compiler::error_reporter::ErrorReporter;
+        use rust_compiler::parser::parser_core::parse_source;
+
+        let source = "MainPrgm test; Var let x: Int;\nBeginPg { if (1 > 2) then { x := y; } } EndPg;";
+        let result = rust_compiler::compile_source(source);
+        let error = result.errors().next().unwrap().to_json(Some(source));
+        assert_eq!(error["type"], "Semantic Error");
+        assert_eq!(error["message"], "Undeclared identifier 'y'");
+        assert_eq!((error["line"].as_u64(), error["column"].as_u64()), (Some(2), Some(34)));
+        assert_eq!(error["suggestion"], "Declare variable 'y' before using it");
+        assert_eq!(error["source_snippet"], source.lines().nth(1).unwrap());
+        assert!(error.get("code").is_none());
+
+        let warning = result.warnings().next().unwrap().to_json(None);
+        assert_eq!(warning["code"], "W0001");
+        assert!(warning["source_snippet"].is_null());
+
+        let syntax_error = parse_source("MainPrgm test; Var BeginPg { x := ; } EndPg;").unwrap_err();
+        assert_eq!(syntax_error.to_json(None)["type"], "Syntax Error");
+    }
+
+    #[test]
+    fn test_diagnostic_handler() {
+        use rust_compiler::compiler::Compiler;
+        use rust_compiler::error_reporter::Diagnostic;
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let received: Rc<RefCell<Vec<Diagnostic>>> = Rc::default();
+        let mut compiler = Compiler::from_source(
+            "MainPrgm test; Var let x: Int; BeginPg { if (1 > 2) then { x := y; } } EndPg;",
+        );
+        let sink = received.clone();
+        compiler.add_diagnostic_handler(Box::new(move |d| sink.borrow_mut().push(d)));
+        let result = compiler.compile();
+
+        assert_eq!(received.borrow().len(), result.diagnostics.len());
+        let received = received.borrow();
+        assert!(received.iter().any(|d| d.is_error()));
+        assert!(received.iter().any(|d| d.code.as_deref() == Some("W0001")));
+
+        let received: Rc<RefCell<Vec<Diagnostic>>> = Rc::default();
+        let mut compiler = Compiler::from_source("MainPrgm test; Var BeginPg { x := ; } EndPg;");
+        let sink = received.clone();
+        compiler.add_diagnostic_handler(Box::new(move |d| sink.borrow_mut().push(d)));
+        compiler.compile();
+        assert_eq!(received.borrow()[0].kind, "Syntax Error");
+    }
+
+    #[test]
+    fn test_json_output_writes_requested_files() {
+        use rust_compiler::compiler::{Compiler, OutputFormat};
+
+        let ir = std::env::temp_dir().join("msrc_json_ir_test.ir");
+        let asm = std::env::temp_dir().join("msrc_json_ir_test.asm");
+        let _ = fs::remove_file(&ir);
+
+        let mut compiler =
+            Compiler::from_source("MainPrgm test; Var let x: Int; BeginPg { x := 1; } EndPg;");
+        compiler.with_output(&asm);
+        compiler.set_output_format(OutputFormat::Json);
+        compiler.set_ir_output(&ir);
+        assert!(compiler.run().is_ok());
+
+        let text = fs::read_to_string(&ir).expect("IR was not written");
+        assert!(text.contains("x := 1"), "{}", text);
+        let _ = fs::remove_file(&ir);
+        let _ = fs::remove_file(&asm);
+    }
+
+    #[test]
+    fn test_syntax_only_skips_semantic_analysis() {
+        use rust_compiler::compiler::{Compiler, Phase};
+
+        let mut compiler = Compiler::from_source("MainPrgm test; Var BeginPg { x := y; } EndPg;");
+        compiler.set_last_phase(Phase::Syntax);
+        let result = compiler.compile();
+        assert!(result.is_success(), "{:?}", result.diagnostics);
+        assert!(result.diagnostics.is_empty());
+        assert!(result.quadruples.is_none());
+
+        let mut compiler = Compiler::from_source("MainPrgm test; Var BeginPg { x := ; } EndPg;");
+        compiler.set_last_phase(Phase::Syntax);
+        assert_eq!(compiler.compile().errors().next().unwrap().kind, "Syntax Error");
+    }
+
+    #[test]
+    fn test_code_context_expands_tabs() {
+        use rust_compiler::error_reporter::format_code_context;
+
+        // Colors, if any, only start at the caret
+        let caret_offset = |context: &str| {
+            let pointer = context.lines().nth(1).unwrap().trim_start_matches(" | ");
+            pointer.len() - pointer.trim_start_matches(' ').len()
+        };
+
+        let context = format_code_context("\tx := y;", 2, 1, 4);
+        assert!(context.contains("    x := y;"), "{:?}", context);
+        assert_eq!(caret_offset(&context), 4);
+
+        let context = format_code_context("ab\tc", 4, 1, 8);
+        assert!(context.contains("ab      c"), "{:?}", context);
+        assert_eq!(caret_offset(&context), 8);
+
+        // Columns past the end of the line point just after it
+        assert_eq!(caret_offset(&format_code_context("\tx;", 40, 1, 4)), 6);
+    }
+
+    #[test]
+    fn test_check_stops_after_semantic_analysis() {
+        use rust_compiler::compiler::{Compiler, Phase};
+
+        let mut compiler = Compiler::from_source(
+            "MainPrgm test; Var let x: Int; BeginPg { x := 1 / 0; y := 2; } EndPg;",
+        );
+        compiler.set_last_phase(Phase::Semantic);
+        let result = compiler.compile();
+        assert_eq!(compiler.summary(&result), "2 errors, 0 warnings");
+
+        let mut compiler = Compiler::from_source(
+            "MainPrgm test; Var let x: Int; let unused: Int; BeginPg { x := 1; } EndPg;",
+        );
+        compiler.set_last_phase(Phase::Semantic);
+        compiler.set_warn_unused(true);
+        let result = compiler.compile();
+        assert_eq!(compiler.summary(&result), "0 errors, 2 warnings");
+        compiler.set_show_warnings(false);
+        assert_eq!(compiler.summary(&result), "0 errors, 0 warnings");
+
+        let mut compiler =
+            Compiler::from_source("MainPrgm test; Var let x: Int; BeginPg { x := 1; } EndPg;");
+        compiler.set_last_phase(Phase::Semantic);
+        let result = compiler.compile();
+        assert!(result.is_success(), "{:?}", result.diagnostics);
+        assert!(result.quadruples.is_none());
+        assert!(result.assembly.is_none());
+    }
+
+    #[test]
+    fn test_max_errors_keeps_true_error_count() {
+        use rust_compiler::compiler::Compiler;
+
+        let source = "MainPrgm test; Var let x: Int; BeginPg { x := 1 / 0; y := 2; } EndPg;";
+        let mut compiler = Compiler::from_source(source);
+        compiler.with_output(&std::env::temp_dir().join("msrc_max_errors_test.asm"));
+        compiler.set_max_errors(1);
+        assert_eq!(compiler.run(), Err(1));
+        let result = compiler.compile();
+        assert_eq!(compiler.summary(&result), "2 errors, 0 warnings");
+    }
+
+    #[test]
+    fn test_repl_keeps_declarations_between_lines() {
+        use rust_compiler::compiler::repl::Repl;
+
+        // `input` reads the line after it, nothing runs after `:quit`
+        let session = "let x : Int ;\nx := 20 ;\ninput(x) ;\n21\noutput(x * 2) ;\n:quit\noutput(1) ;\n";
+        let mut output = Vec::new();
+        Repl::new().run(&mut session.as_bytes(), &mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "> > > > 42\n> ");
+
+        let mut output = Vec::new();
+        Repl::new().run(&mut "y := 1 ;\nlet\n".as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("Undeclared identifier 'y'"), "{}", output);
+        assert!(output.contains("Syntax Error"), "{}", output);
+    }
+
+    /// Writes `files` to a fresh directory under the system temp dir
+    fn write_modules(dir_name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(dir_name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for (name, source) in files {
+            fs::write(dir.join(name), source).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn test_import_merges_declarations_and_bodies() {
+        let dir = write_modules(
+            "msrc_import_test",
+            &[
+                (
+                    "lib.ms",
+                    "MainPrgm lib; Var let base: Int = 40; BeginPg { output(\"lib\"); } EndPg;",
+                ),
+                (
+                    "main.ms",
+                    "MainPrgm main; Var import \"lib.ms\"; import \"lib.ms\"; let x: Int;
+                     BeginPg { x := base + 2; } EndPg;",
+                ),
+            ],
+        );
+
+        let result = rust_compiler::compile_file(&dir.join("main.ms"));
+        assert!(result.is_success(), "{:?}", result.diagnostics);
+
+        // The module is only included once, and its body runs first
+        let quads: Vec<String> = result
+            .quadruples
+            .unwrap()
+            .quadruples
+            .iter()
+            .map(|quad| quad.to_string())
+            .collect();
+        let base = quads.iter().filter(|q| q.contains("base")).count();
+        assert_eq!(base, 2, "{:?}", quads);
+        let output = quads.iter().position(|q| q.contains("\"lib\"")).unwrap();
+        let assign = quads.iter().position(|q| q.contains("ADD")).unwrap();
+        assert!(output < assign, "{:?}", quads);
+    }
+
+    #[test]
+    fn test_import_errors() {
+        let dir = write_modules(
+            "msrc_import_errors_test",
+            &[
+                (
+                    "a.ms",
+                    "MainPrgm a; Var import \"b.ms\"; BeginPg { } EndPg;",
+                ),
+                (
+                    "b.ms",
+                    "MainPrgm b; Var import \"a.ms\"; BeginPg { } EndPg;",
+                ),
+                (
+                    "missing.ms",
+                    "MainPrgm m; Var import \"nope.ms\"; BeginPg { } EndPg;",
+                ),
+                ("bad.ms", "MainPrgm bad; Var let x: Int BeginPg { } EndPg;"),
+                (
+                    "uses_bad.ms",
+                    "MainPrgm u; Var import \"bad.ms\"; BeginPg { } EndPg;",
+                ),
+            ],
+        );
+
+        for (file, message) in [
+            ("a.ms", "Circular import of 'a.ms'"),
+            ("missing.ms", "Cannot import 'nope.ms'"),
+            ("uses_bad.ms", "Error in imported module"),
+        ] {
+            let result = rust_compiler::compile_file(&dir.join(file));
+            let error = result.errors().next().expect(file);
+            assert_eq!(error.kind, "Module Error");
+            assert!(error.message.starts_with(message), "{}", error.message);
+        }
+    }
+
+    #[test]
+    fn test_imported_modules_follow_relaxed_identifiers() {
+        use rust_compiler::compiler::Compiler;
+
+        let dir = write_modules(
+            "msrc_import_relaxed_test",
+            &[
+                (
+                    "lib.ms",
+                    "MainPrgm lib; Var let legacyCounter: Int = 1; BeginPg { } EndPg;",
+                ),
+                (
+                    "main.ms",
+                    "MainPrgm main; Var import \"lib.ms\"; let x: Int;
+                     BeginPg { x := legacyCounter; } EndPg;",
+                ),
+            ],
+        );
+        let main = dir.join("main.ms");
+
+        let result = Compiler::new(main.to_str().unwrap()).unwrap().compile();
+        let error = result.errors().next().expect("strict identifiers");
+        assert!(error.message.contains("legacyCounter"), "{}", error.message);
+
+        let mut compiler = Compiler::new(main.to_str().unwrap()).unwrap();
+        compiler.set_relaxed_identifiers(true);
+        let result = compiler.compile();
+        assert!(result.is_success(), "{:?}", result.diagnostics);
+    }
 }
@@ -0,0 +1,161 @@
+#[cfg(test)]
+mod lexer_proptests {
+    use logos::Logos;
+    use rust_compiler::lexer::token::Token;
+
+    /// Source text that should lex back into `token`. The match has no
+    /// wildcard, so a new variant does not compile until it is listed here.
+    fn canonical(token: &Token) -> String {
+        let text = match token {
+            Token::MainPrgm => "MainPrgm",
+            Token::Var => "Var",
+            Token::BeginPg => "BeginPg",
+            Token::EndPg => "EndPg",
+            Token::Let => "let",
+            Token::Int => "Int",
+            Token::Float => "Float",
+            Token::String => "String",
+            Token::If => "if",
+            Token::Then => "then",
+            Token::Else => "else",
+            Token::While => "while",
+            Token::For => "for",
+            Token::Do => "do",
+            Token::From => "from",
+            Token::To => "to",
+            Token::Step => "step",
+            Token::Break => "break",
+            Token::Continue => "continue",
+            Token::Input => "input",
+            Token::Output => "output",
+            Token::Define => "@define",
+            Token::Const => "Const",
+            Token::Import => "import",
+            Token::Asm => "asm",
+            Token::Semicolon => ";",
+            Token::Comma => ",",
+            Token::Colon => ":",
+            Token::OpenBracket => "[",
+            Token::CloseBracket => "]",
+            Token::OpenBrace => "{",
+            Token::CloseBrace => "}",
+            Token::OpenParen => "(",
+            Token::CloseParen => ")",
+            Token::Assign => ":=",
+            Token::Equals => "=",
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Multiply => "*",
+            Token::Divide => "/",
+            Token::Modulo => "%",
+            Token::GreaterThan => ">",
+            Token::LessThan => "<",
+            Token::GreaterEqual => ">=",
+            Token::LessEqual => "<=",
+            Token::Equal => "==",
+            Token::NotEqual => "!=",
+            Token::And => "AND",
+            Token::Or => "OR",
+            Token::Not => "!",
+            Token::Identifier(name) => name,
+            // Signed literals are written in parentheses
+            Token::IntLiteral(n) if *n < 0 => return format!("({})", n),
+            Token::IntLiteral(n) => return n.to_string(),
+            Token::FloatLiteral(x) if *x < 0.0 => return format!("({:?})", x),
+            Token::FloatLiteral(x) => return format!("{:?}", x),
+            Token::StringLiteral(s) => return format!("\"{}\"", s),
+            Token::Comment | Token::Error => unreachable!("{:?} has no source text", token),
+        };
+        text.to_string()
+    }
+
+    fn tokens() -> Vec<Token> {
+        let mut tokens = vec![
+            Token::MainPrgm,
+            Token::Var,
+            Token::BeginPg,
+            Token::EndPg,
+            Token::Let,
+            Token::Int,
+            Token::Float,
+            Token::String,
+            Token::If,
+            Token::Then,
+            Token::Else,
+            Token::While,
+            Token::For,
+            Token::Do,
+            Token::From,
+            Token::To,
+            Token::Step,
+            Token::Break,
+            Token::Continue,
+            Token::Input,
+            Token::Output,
+            Token::Define,
+            Token::Const,
+            Token::Import,
+            Token::Asm,
+            Token::Semicolon,
+            Token::Comma,
+            Token::Colon,
+            Token::OpenBracket,
+            Token::CloseBracket,
+            Token::OpenBrace,
+            Token::CloseBrace,
+            Token::OpenParen,
+            Token::CloseParen,
+            Token::Assign,
+            Token::Equals,
+            Token::Plus,
+            Token::Minus,
+            Token::Multiply,
+            Token::Divide,
+            Token::Modulo,
+            Token::GreaterThan,
+            Token::LessThan,
+            Token::GreaterEqual,
+            Token::LessEqual,
+            Token::Equal,
+            Token::NotEqual,
+            Token::And,
+            Token::Or,
+            Token::Not,
+        ];
+        tokens.extend(["x", "total", "value_2"].map(|name| Token::Identifier(name.to_string())));
+        tokens.extend([0, 7, 42, 32767, -1, -32768].map(Token::IntLiteral));
+        tokens.extend([0.0, 1.5, 45.67, 123.456, -0.5, -56.78].map(Token::FloatLiteral));
+        tokens.extend(["", "hello", "a b, c"].map(|s| Token::StringLiteral(s.to_string())));
+        tokens
+    }
+
+    fn lex(source: &str) -> Vec<Token> {
+        Token::lexer(source)
+            .map(|token| token.unwrap_or_else(|e| panic!("{:?} failed to lex: {:?}", source, e)))
+            .collect()
+    }
+
+    #[test]
+    fn test_each_token_round_trips() {
+        for token in tokens() {
+            let source = canonical(&token);
+            assert_eq!(lex(&source), vec![token], "source {:?}", source);
+        }
+    }
+
+    #[test]
+    fn test_token_pairs_round_trip() {
+        let tokens = tokens();
+        for first in &tokens {
+            for second in &tokens {
+                let source = format!("{} {}", canonical(first), canonical(second));
+                assert_eq!(
+                    lex(&source),
+                    vec![first.clone(), second.clone()],
+                    "source {:?}",
+                    source
+                );
+            }
+        }
+    }
+}
@@ -2,12 +2,13 @@
 mod lexer_tests {
     use logos::Logos;
     use rust_compiler::lexer::error::LexicalErrorType;
-    use rust_compiler::lexer::lexer_core::tokenize;
+    use rust_compiler::error_reporter::ErrorReporter;
+    use rust_compiler::lexer::lexer_core::{LexerIter, tokenize, tokenize_with};
     use rust_compiler::lexer::token::Token;
 
     #[test]
     fn test_keywords() {
-        let mut lexer = Token::lexer("MainPrgm Var BeginPg EndPg let Int Float if then else while for do from to step input output @define Const");
+        let mut lexer = Token::lexer("MainPrgm Var BeginPg EndPg let Int Float String if then else while for do from to step input output @define Const");
         assert_eq!(lexer.next(), Some(Ok(Token::MainPrgm)));
         assert_eq!(lexer.next(), Some(Ok(Token::Var)));
         assert_eq!(lexer.next(), Some(Ok(Token::BeginPg)));
@@ -15,6 +16,7 @@ mod lexer_tests {
         assert_eq!(lexer.next(), Some(Ok(Token::Let)));
         assert_eq!(lexer.next(), Some(Ok(Token::Int)));
         assert_eq!(lexer.next(), Some(Ok(Token::Float)));
+        assert_eq!(lexer.next(), Some(Ok(Token::String)));
         assert_eq!(lexer.next(), Some(Ok(Token::If)));
         assert_eq!(lexer.next(), Some(Ok(Token::Then)));
         assert_eq!(lexer.next(), Some(Ok(Token::Else)));
@@ -48,11 +50,12 @@ mod lexer_tests {
 
     #[test]
     fn test_operators() {
-        let mut lexer = Token::lexer("+ - * / > < >= <= == != := = AND OR !");
+        let mut lexer = Token::lexer("+ - * / % > < >= <= == != := = AND OR !");
         assert_eq!(lexer.next(), Some(Ok(Token::Plus)));
         assert_eq!(lexer.next(), Some(Ok(Token::Minus)));
         assert_eq!(lexer.next(), Some(Ok(Token::Multiply)));
         assert_eq!(lexer.next(), Some(Ok(Token::Divide)));
+        assert_eq!(lexer.next(), Some(Ok(Token::Modulo)));
         assert_eq!(lexer.next(), Some(Ok(Token::GreaterThan)));
         assert_eq!(lexer.next(), Some(Ok(Token::LessThan)));
         assert_eq!(lexer.next(), Some(Ok(Token::GreaterEqual)));
@@ -154,6 +157,16 @@ mod lexer_tests {
         assert_eq!(lexer.next(), None);
     }
 
+    #[test]
+    fn test_has_comments() {
+        use rust_compiler::lexer::lexer_core::has_comments;
+
+        assert!(has_comments("a <!- note -!> b"));
+        assert!(has_comments("a := 1;\n{-- trailing --}\n"));
+        assert!(!has_comments("output(\"<!- not a comment -!>\");"));
+        assert!(!has_comments("output(\"{-- nor this --}\");\n"));
+    }
+
     #[test]
     fn test_invalid_identifiers() {
         // Test identifiers exceeding 14 chars
@@ -173,6 +186,96 @@ mod lexer_tests {
         assert_eq!(lexer.next(), Some(Err(())));
     }
 
+    #[test]
+    fn test_identifier_rules() {
+        let cases = [
+            // First character: any ASCII letter, never a digit or underscore
+            ("a", true),
+            ("Z", true),
+            ("Counter", true),
+            ("_x", false),
+            ("1x", false),
+            // Later characters: lowercase letters, digits, single underscores
+            ("x1_y2", true),
+            ("a_b_c", true),
+            ("aB", false),
+            ("counterX", false),
+            // Length: at most 14 characters
+            ("abcdefghijklmn", true),
+            ("abcdefghijklmno", false),
+            ("A_123456789_bc", true),
+            // Underscores: never doubled, never last
+            ("a__b", false),
+            ("a_", false),
+            ("a_b_", false),
+        ];
+
+        for (source, valid) in cases {
+            let tokens: Vec<_> = Token::lexer(source).collect();
+            if valid {
+                assert_eq!(
+                    tokens,
+                    vec![Ok(Token::Identifier(source.to_string()))],
+                    "{:?} should be an identifier",
+                    source
+                );
+            } else {
+                assert!(
+                    !matches!(tokens.as_slice(), [Ok(Token::Identifier(_))]),
+                    "{:?} should not be an identifier, got {:?}",
+                    source,
+                    tokens
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lexer_iter_matches_tokenize() {
+        let source = "MainPrgm demo;\nVar\nlet x: Int;\nBeginPg { x := 1 # 2; } EndPg;";
+        let (tokens, errors) = tokenize(source);
+
+        let lazy: Vec<_> = LexerIter::new(source).collect();
+        assert_eq!(lazy.len(), tokens.len() + errors.len());
+        let lazy_tokens: Vec<_> = lazy.iter().filter_map(|t| t.as_ref().ok()).cloned().collect();
+        assert_eq!(lazy_tokens, tokens);
+        let lazy_error = lazy.iter().find_map(|t| t.as_ref().err()).unwrap();
+        assert_eq!((lazy_error.line, lazy_error.column), (4, 18));
+        assert_eq!(lazy_error.invalid_token, errors[0].invalid_token);
+    }
+
+    #[test]
+    fn test_relaxed_identifiers() {
+        let (tokens, errors) = tokenize_with("invalidIdentifier averyverylongidentifier", true);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(
+            tokens.iter().map(|t| &t.kind).collect::<Vec<_>>(),
+            [
+                &Token::Identifier("invalidIdentifier".to_string()),
+                &Token::Identifier("averyverylongidentifier".to_string()),
+            ]
+        );
+
+        // The underscore rules still apply
+        let (_, errors) = tokenize_with("a__b a_", true);
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn test_identifier_error_messages() {
+        let (_, errors) = tokenize("counterX thisidentifieristoolong");
+        assert_eq!(errors[0].error_type, LexicalErrorType::InvalidIdentifier);
+        assert_eq!(
+            errors[0].get_message(),
+            "Uppercase letter not allowed after position 1 in identifier 'counterX'"
+        );
+        assert_eq!(errors[1].error_type, LexicalErrorType::IdentifierTooLong);
+        assert_eq!(
+            errors[1].get_message(),
+            "Identifier 'thisidentifieristoolong' exceeds 14 characters"
+        );
+    }
+
     #[test]
     fn test_integer_out_of_range() {
         // Test integer literal above max i16
@@ -345,4 +448,23 @@ MainPrgm
         assert_eq!(tokens[0].kind, Token::MainPrgm);
         assert_eq!(tokens[1].kind, Token::Var);
     }
+
+    #[test]
+    fn test_token_display_names() {
+        assert_eq!(Token::MainPrgm.display_name(), "'MainPrgm' keyword");
+        assert_eq!(Token::Semicolon.display_name(), "';'");
+        assert_eq!(Token::Identifier("x".to_string()).display_name(), "identifier");
+        assert_eq!(Token::IntLiteral(1).display_name(), "integer literal");
+
+        // Terminal names as they appear in the generated parser
+        assert_eq!(Token::from_terminal("\"MainPrgm\""), Some(Token::MainPrgm));
+        assert_eq!(Token::from_terminal("\":=\""), Some(Token::Assign));
+        assert_eq!(Token::from_terminal("\"Int\""), Some(Token::Int));
+        assert_eq!(Token::from_terminal("Int"), Some(Token::IntLiteral(0)));
+        assert_eq!(
+            Token::from_terminal("Idf"),
+            Some(Token::Identifier(String::new()))
+        );
+        assert_eq!(Token::from_terminal("Unknown"), None);
+    }
 }
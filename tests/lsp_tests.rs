@@ -0,0 +1,50 @@
+#[cfg(all(test, feature = "lsp"))]
+mod lsp_tests {
+    use lsp_types::{DiagnosticSeverity, NumberOrString, Position, Uri};
+    use rust_compiler::lsp::MiniSoftLanguageServer;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_semantic_error_maps_to_lsp_diagnostic() {
+        let server = MiniSoftLanguageServer::new();
+        let diagnostics =
+            server.diagnostics("MainPrgm test; Var let x: Int; BeginPg { y := 1; } EndPg;");
+
+        assert_eq!(diagnostics.len(), 1);
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(diagnostic.range.start, Position::new(0, 41));
+        assert!(diagnostic.message.starts_with("Undeclared identifier 'y'"));
+    }
+
+    #[test]
+    fn test_warning_keeps_its_code() {
+        let server = MiniSoftLanguageServer::new();
+        let diagnostics = server.diagnostics(
+            "MainPrgm test; Var let x: Int; BeginPg { if (1 > 2) then { x := 1; } } EndPg;",
+        );
+
+        assert_eq!(diagnostics[0].severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diagnostics[0].code,
+            Some(NumberOrString::String("W0001".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_publish_diagnostics_json() {
+        let server = MiniSoftLanguageServer::new();
+        let uri = Uri::from_str("file:///tmp/test.ms").unwrap();
+        let json = server.publish_diagnostics_json(
+            uri,
+            "MainPrgm test; Var BeginPg { x := ; } EndPg;",
+            Some(3),
+        );
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["method"], "textDocument/publishDiagnostics");
+        assert_eq!(value["params"]["uri"], "file:///tmp/test.ms");
+        assert_eq!(value["params"]["version"], 3);
+        assert_eq!(value["params"]["diagnostics"][0]["source"], "msrc");
+    }
+}
@@ -1,10 +1,12 @@
 #[cfg(test)]
 mod parser_tests {
     use rust_compiler::parser::ast::{
-        DeclarationKind, ExpressionKind, Operator, Program, StatementKind, Type,
+        DeclarationKind, ExpressionKind, LiteralKind, Located, Operator, Program, StatementKind, Type,
+        UnaryOperator,
     };
     use rust_compiler::lexer::lexer_core::tokenize;
     use rust_compiler::parser::parser_core::parse;
+    use rust_compiler::parser::error::SyntaxError;
 
     /// Helper function to parse a source string and return the AST
     fn parse_test(source: &str) -> Program {
@@ -22,6 +24,24 @@ mod parser_tests {
         parse(tokens, source).is_err()
     }
 
+    // Helper function to test that parsing fails with a specific error, e.g. "UnexpectedToken"
+    fn expect_parse_error_kind(source: &str, kind: &str) -> bool {
+        let (tokens, _) = tokenize(source);
+        match parse(tokens, source) {
+            Ok(_) => false,
+            Err(e) => format!("{:?}", e).contains(kind),
+        }
+    }
+
+    // Helper function to parse a program that must be valid
+    fn expect_parse_success(source: &str) -> Program {
+        let (tokens, _) = tokenize(source);
+        match parse(tokens, source) {
+            Ok(program) => program,
+            Err(e) => panic!("Expected {:?} to parse, got {:?}", source, e),
+        }
+    }
+
     #[test]
     fn test_minimal_program() {
         let source = "MainPrgm test ; Var BeginPg { } EndPg ;";
@@ -142,25 +162,26 @@ mod parser_tests {
     }
 
     #[test]
-    #[should_panic(expected = "Parse error")]
     fn test_syntax_errors() {
         let sources = [
             // Missing semicolon
-            "MainPrgm test ; Var let x : Int BeginPg { } EndPg ;",
+            ("MainPrgm test ; Var let x : Int BeginPg { } EndPg ;", "UnexpectedToken"),
             
             // Missing then keyword
-            "MainPrgm test ; Var BeginPg { if (x > 10) { x := 20 ; } } EndPg ;",
+            ("MainPrgm test ; Var BeginPg { if (x > 10) { x := 20 ; } } EndPg ;", "UnexpectedToken"),
             
             // Wrong program structure
-            "BeginPg let x : Int ; MainPrgm test ; { } EndPg ;",
+            ("BeginPg let x : Int ; MainPrgm test ; { } EndPg ;", "UnexpectedToken"),
             
             // Missing assignment operator
-            "MainPrgm test ; Var let x : Int ; BeginPg { x 10 ; } EndPg ;",
+            ("MainPrgm test ; Var let x : Int ; BeginPg { x 10 ; } EndPg ;", "UnexpectedToken"),
+
+            // Missing BeginPg/EndPg
+            ("MainPrgm test ; Var let x : Int ;", "UnexpectedEOF"),
         ];
         
-        for src in sources {
-            parse_test(src); // Should panic
-            break; // We only need to test one to verify error handling
+        for (src, kind) in sources {
+            assert!(expect_parse_error_kind(src, kind), "Expected {} for {:?}", kind, src);
         }
     }
 
@@ -172,7 +193,7 @@ mod parser_tests {
         let input = fs::read_to_string(test_file_path).expect("Failed to read test file");
 
         // Just check if parsing succeeds
-        let program = parse_test(&input);
+        let program = expect_parse_success(&input);
         assert_eq!(program.name, "L3_software");
     }
 
@@ -195,6 +216,10 @@ mod parser_tests {
         if let DeclarationKind::Variable(names, ty) = &program.declarations[1].node {
             assert_eq!(names.len(), 3);
             assert!(matches!(ty, Type::Int));
+            // Each name is located where it is written
+            for name in names {
+                assert_eq!(&source[name.span.clone()], name.node);
+            }
         } else {
             panic!("Expected multiple variable declaration");
         }
@@ -229,7 +254,7 @@ mod parser_tests {
         
         // Check array declaration
         if let DeclarationKind::Array(names, ty, size) = &program.declarations[0].node {
-            assert_eq!(names[0], "arr");
+            assert_eq!(names[0].node, "arr");
             assert!(matches!(ty, Type::Int));
             assert_eq!(*size, 10);
         } else {
@@ -238,7 +263,7 @@ mod parser_tests {
         
         // Check initialized array
         if let DeclarationKind::ArrayWithInit(names, _, _, values) = &program.declarations[2].node {
-            assert_eq!(names[0], "initialized");
+            assert_eq!(names[0].node, "initialized");
             assert_eq!(values.len(), 3);
         } else {
             panic!("Expected array with initialization");
@@ -273,7 +298,7 @@ mod parser_tests {
         // Check constant declarations
         for (i, const_name) in ["Pi", "Max", "Min"].iter().enumerate() {
             if let DeclarationKind::Constant(name, _, _) = &program.declarations[i].node {
-                assert_eq!(name, const_name);
+                assert_eq!(&name.node, const_name);
             } else {
                 panic!("Expected constant declaration");
             }
@@ -321,6 +346,310 @@ mod parser_tests {
         }
     }
 
+    #[test]
+    fn test_modulo_precedence() {
+        let source = "
+            MainPrgm modulo ;
+            Var
+            let result : Int ;
+            BeginPg {
+                result := 1 + 7 % 3 ;
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+
+        // Modulo binds like multiplication: 1 + (7 % 3)
+        if let StatementKind::Assignment(_, expr) = &program.statements[0].node {
+            if let ExpressionKind::BinaryOp(_, op, right) = &expr.node {
+                assert!(matches!(op, Operator::Add));
+                if let ExpressionKind::BinaryOp(_, inner_op, _) = &right.node {
+                    assert!(matches!(inner_op, Operator::Modulo));
+                } else {
+                    panic!("Expected modulo on right side");
+                }
+            } else {
+                panic!("Expected binary operation");
+            }
+        }
+    }
+
+    #[test]
+    fn test_unary_minus() {
+        let source = "
+            MainPrgm negate ;
+            Var
+            let a, b : Int ;
+            BeginPg {
+                a := b - -(b + 1) * 2 ;
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+
+        // Negation binds tighter than multiplication: b - ((-(b + 1)) * 2)
+        if let StatementKind::Assignment(_, expr) = &program.statements[0].node {
+            let ExpressionKind::BinaryOp(_, Operator::Subtract, right) = &expr.node else {
+                panic!("Expected subtraction, got {:?}", expr.node);
+            };
+            let ExpressionKind::BinaryOp(left, Operator::Multiply, _) = &right.node else {
+                panic!("Expected multiplication, got {:?}", right.node);
+            };
+            assert!(matches!(
+                left.node,
+                ExpressionKind::UnaryOp(UnaryOperator::Negate, _)
+            ));
+        }
+        assert_eq!(
+            program.to_string(),
+            parse_test(&program.to_string()).to_string()
+        );
+        assert!(program.to_string().contains("a := b - -(b + 1) * 2;"));
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        // Printing, re-parsing and printing again must give the same source
+        for entry in std::fs::read_dir("examples/valid").unwrap() {
+            let path = entry.unwrap().path();
+            let source = std::fs::read_to_string(&path).unwrap();
+            let printed = parse_test(&source).to_string();
+            let reprinted = parse_test(&printed).to_string();
+            assert_eq!(printed, reprinted, "Round trip changed {:?}", path);
+        }
+    }
+
+    #[test]
+    fn test_break_and_continue_statements() {
+        let program = parse_test(
+            "MainPrgm b ; Var let i : Int ; BeginPg { for i from 0 to 3 step 1 { break ; } } EndPg ;",
+        );
+        let StatementKind::For(_, _, _, _, body) = &program.statements[0].node else {
+            panic!("Expected for loop");
+        };
+        assert!(matches!(body[0].node, StatementKind::Break));
+        assert!(program.to_string().contains("        break;\n"));
+
+        let program = parse_test(
+            "MainPrgm c ; Var let i : Int ; BeginPg { do { continue ; } while (i < 3) ; } EndPg ;",
+        );
+        let StatementKind::DoWhile(body, _) = &program.statements[0].node else {
+            panic!("Expected do-while loop");
+        };
+        assert!(matches!(body[0].node, StatementKind::Continue));
+        assert!(program.to_string().contains("        continue;\n"));
+    }
+
+    #[test]
+    fn test_while_loop() {
+        let program = parse_test(
+            "MainPrgm w ; Var let i : Int ; BeginPg { while (i < 3) { i := i + 1 ; } } EndPg ;",
+        );
+        let StatementKind::While(condition, body) = &program.statements[0].node else {
+            panic!("Expected while loop");
+        };
+        assert_eq!(condition.to_string(), "i < 3");
+        assert_eq!(body.len(), 1);
+        assert!(program.to_string().contains("    while (i < 3) {\n"));
+    }
+
+    #[test]
+    fn test_program_to_sexp() {
+        let program = parse_test(
+            "MainPrgm s ; Var let a, b : Int ; let v : [Float; 2] = {1.5, (-2.0)} ; @define Const N : Int = 3 ;
+             BeginPg { a := -(b + 1) * N ; if (!(a > b)) then { output(\"a\", v[0]) ; } else { while (a < N) { a := a + 1 ; } } } EndPg ;",
+        );
+        let expected = r#"(program s
+  (declarations
+    (let (a b) Int)
+    (let (v) (array Float 2) (1.5 -2.0))
+    (const N Int 3))
+  (statements
+    (:= a (* (- (+ b 1)) N))
+    (if (! (> a b))
+      (then
+        (output "a" (index v 0)))
+      (else
+        (while (< a N)
+          (body
+            (:= a (+ a 1))))))))
+"#;
+        assert_eq!(program.to_sexp(), expected);
+    }
+
+    #[test]
+    fn test_format_source() {
+        use rust_compiler::parser::parser_core::format_source;
+
+        let source = "MainPrgm f ; Var let a,b:Int; let values:[Float;3]; @define Const Max: Int = 3;
+            BeginPg { a:=1+2*b; if (a>b) then { a := -a; } } EndPg;";
+        let expected = "\
+MainPrgm f;
+Var
+    let a, b:          Int;
+    let values:        [Float; 3];
+    @define Const Max: Int = 3;
+BeginPg
+{
+    a := 1 + 2 * b;
+    if (a > b) then {
+        a := -a;
+    }
+}
+EndPg;
+";
+        let formatted = format_source(source).unwrap();
+        assert_eq!(formatted, expected);
+        assert_eq!(format_source(&formatted).unwrap(), formatted);
+    }
+
+    #[test]
+    fn test_inline_asm_statement() {
+        let source = r#"
+            MainPrgm inline ;
+            Var
+            BeginPg {
+                asm { "nop" ; "mov rax, 1" ; }
+                asm { }
+            } EndPg ;
+        "#;
+
+        let program = parse_test(source);
+        assert_eq!(
+            program.statements[0].node,
+            StatementKind::InlineAsm(vec!["nop".to_string(), "mov rax, 1".to_string()])
+        );
+        assert_eq!(program.statements[1].node, StatementKind::InlineAsm(vec![]));
+        let printed = program.to_string();
+        assert_eq!(parse_test(&printed).to_string(), printed);
+    }
+
+    #[test]
+    fn test_ast_equality() {
+        let source = "
+            MainPrgm equality ;
+            Var
+            let x : Float ;
+            BeginPg {
+                x := 2.5 ;
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+        assert_eq!(program, parse_test(source));
+        assert_eq!(program.clone(), program);
+
+        if let StatementKind::Assignment(_, value) = &program.statements[0].node {
+            assert_eq!(
+                value.node,
+                ExpressionKind::Literal(Located {
+                    node: LiteralKind::Float(2.5),
+                    span: value.span.clone(),
+                })
+            );
+        } else {
+            panic!("Expected assignment");
+        }
+
+        assert_ne!(LiteralKind::Float(0.0), LiteralKind::Float(-0.0));
+        assert_eq!(LiteralKind::Float(f32::NAN), LiteralKind::Float(f32::NAN));
+    }
+
+    #[test]
+    fn test_program_from_source() {
+        let source = "MainPrgm test ; Var let x : Int ; BeginPg { x := 1 ; } EndPg ;";
+        assert_eq!(Program::from_source(source).unwrap(), parse_test(source));
+
+        // Lexical errors are not skipped over
+        let source = "MainPrgm test ; Var\nlet x : Int ; BeginPg { x := 1 $ ; } EndPg ;";
+        let error = Program::from_source(source).unwrap_err();
+        assert!(
+            matches!(error, SyntaxError::InvalidToken { line: 2, column: 32, .. }),
+            "{:?}",
+            error
+        );
+
+        assert!(matches!(
+            Program::from_source("MainPrgm test ; BeginPg"),
+            Err(SyntaxError::UnexpectedToken { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_single_statement_and_declaration() {
+        use rust_compiler::parser::parser_core::{parse_declaration, parse_statement};
+
+        let statement = parse_statement("x := 1 ;").unwrap();
+        assert!(matches!(statement.node, StatementKind::Assignment(..)));
+        assert_eq!(statement.span, 0..8);
+
+        let declaration = parse_declaration("let x : Int ;").unwrap();
+        assert!(matches!(declaration.node, DeclarationKind::Variable(..)));
+
+        assert!(parse_statement("let x : Int ;").is_err());
+        assert!(matches!(
+            parse_statement("x := 1 $ ;"),
+            Err(SyntaxError::InvalidToken { column: 8, .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_empty_statement() {
+        let program = parse_test("MainPrgm test ; Var BeginPg { ; x := 1 ; ; } EndPg ;");
+        let kinds: Vec<&StatementKind> = program.statements.iter().map(|s| &s.node).collect();
+        assert!(matches!(
+            kinds[..],
+            [StatementKind::Empty, StatementKind::Assignment(..), StatementKind::Empty]
+        ));
+        assert_eq!(program.statements[0].span, 30..31);
+    }
+
+    #[test]
+    fn test_expected_tokens_use_display_names() {
+        let error = Program::from_source("MainPrgm test ; Var BeginPg { x := ; } EndPg ;")
+            .unwrap_err();
+
+        if let SyntaxError::UnexpectedToken { expected, .. } = &error {
+            assert!(expected.contains(&"identifier".to_string()), "{:?}", expected);
+            assert!(expected.contains(&"integer literal".to_string()), "{:?}", expected);
+            assert!(expected.contains(&"'('".to_string()), "{:?}", expected);
+            assert!(!expected.iter().any(|name| name.contains('"')), "{:?}", expected);
+        } else {
+            panic!("Expected UnexpectedToken, got {:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_token_after_program_is_extra_token() {
+        let error = Program::from_source("MainPrgm test ; Var BeginPg { } EndPg ; x")
+            .unwrap_err();
+        assert!(
+            matches!(&error, SyntaxError::ExtraToken { token, .. } if token.contains('x')),
+            "{:?}",
+            error
+        );
+    }
+
+    #[test]
+    fn test_display_parenthesizes_by_precedence() {
+        let source = "
+            MainPrgm display ;
+            Var
+            let a, b : Int ;
+            BeginPg {
+                a := (1 + 2) * 3 - (4 - b) ;
+                b := !(a > (-1)) AND (a == 2 OR b == 3) ;
+                output(\"a = \", a % 2, 2.0) ;
+            } EndPg ;
+        ";
+
+        let program = parse_test(source);
+        let statements: Vec<String> = program.statements.iter().map(|s| s.to_string()).collect();
+        assert_eq!(statements[0], "a := (1 + 2) * 3 - (4 - b);\n");
+        assert_eq!(statements[1], "b := !(a > (-1)) AND (a == 2 OR b == 3);\n");
+        assert_eq!(statements[2], "output(\"a = \", a % 2, 2.0);\n");
+    }
+
     #[test]
     fn test_nested_control_flow() {
         let source = "
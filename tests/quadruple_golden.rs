@@ -0,0 +1,89 @@
+#[cfg(test)]
+mod quadruple_golden_tests {
+    use std::fs;
+    use std::path::Path;
+
+    /// Lines of `expected` and `actual` prefixed with ' ', '-' or '+'
+    fn line_diff(expected: &str, actual: &str) -> String {
+        let old: Vec<&str> = expected.lines().collect();
+        let new: Vec<&str> = actual.lines().collect();
+
+        // Longest common subsequence table, filled from the end
+        let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+        for i in (0..old.len()).rev() {
+            for j in (0..new.len()).rev() {
+                lcs[i][j] = if old[i] == new[j] {
+                    lcs[i + 1][j + 1] + 1
+                } else {
+                    lcs[i + 1][j].max(lcs[i][j + 1])
+                };
+            }
+        }
+
+        let mut diff = String::new();
+        let (mut i, mut j) = (0, 0);
+        while i < old.len() || j < new.len() {
+            if i < old.len() && j < new.len() && old[i] == new[j] {
+                diff.push_str(&format!(" {}\n", old[i]));
+                i += 1;
+                j += 1;
+            } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+                diff.push_str(&format!("+{}\n", new[j]));
+                j += 1;
+            } else {
+                diff.push_str(&format!("-{}\n", old[i]));
+                i += 1;
+            }
+        }
+        diff
+    }
+
+    /// Compares the quadruples of every valid example with `tests/golden/<name>.quad`.
+    /// Run with `UPDATE_GOLDEN=1` to rewrite the golden files instead.
+    #[test]
+    fn test_quadruples_match_golden_files() {
+        let update = std::env::var("UPDATE_GOLDEN").is_ok_and(|value| value == "1");
+        let golden_dir = Path::new("tests/golden");
+        let mut failures = Vec::new();
+
+        let mut examples: Vec<_> = fs::read_dir("examples/valid")
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "ms"))
+            .collect();
+        examples.sort();
+
+        for example in examples {
+            let result = rust_compiler::compile_file(&example);
+            let quadruples = result
+                .quadruples
+                .unwrap_or_else(|| panic!("{:?} failed: {:?}", example, result.diagnostics));
+            let actual = quadruples.dump_annotated();
+
+            let golden = golden_dir
+                .join(example.file_stem().unwrap())
+                .with_extension("quad");
+            if update {
+                fs::create_dir_all(golden_dir).unwrap();
+                fs::write(&golden, &actual).unwrap();
+                continue;
+            }
+
+            match fs::read_to_string(&golden) {
+                Ok(expected) if expected == actual => {}
+                Ok(expected) => failures.push(format!(
+                    "--- {}\n+++ {:?}\n{}",
+                    golden.display(),
+                    example,
+                    line_diff(&expected, &actual)
+                )),
+                Err(_) => failures.push(format!(
+                    "{} is missing, run with UPDATE_GOLDEN=1 to create it",
+                    golden.display()
+                )),
+            }
+        }
+
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+}
@@ -1,7 +1,7 @@
 #[cfg(test)]
 mod semantic_tests {
     use rust_compiler::lexer::lexer_core::tokenize;
-    use rust_compiler::parser::parser_core::parse;
+    use rust_compiler::parser::parser_core::{parse, parse_source};
     use rust_compiler::semantics::analyzer_core::SemanticAnalyzer;
 
     /// Helper function to analyze code semantically and return error messages as strings
@@ -14,7 +14,7 @@ mod semantic_tests {
         };
 
         // Create a semantic analyzer with the actual source code
-        let mut analyzer = SemanticAnalyzer::new(&source.to_string());
+        let mut analyzer = SemanticAnalyzer::new(source);
 
         // Analyze the program
         analyzer.analyze(&program);
@@ -27,6 +27,19 @@ mod semantic_tests {
             .collect()
     }
 
+    /// Helper function to analyze code and return the codes of the reported warnings
+    fn warning_codes(source: &str) -> Vec<String> {
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.analyze(&program);
+        analyzer
+            .get_warnings()
+            .iter()
+            .map(|w| w.code().to_string())
+            .collect()
+    }
+
     /// Helper to check if errors match expected patterns (now operating on error messages)
     fn contains_error_of_type(errors: &[String], error_type: &str) -> bool {
         errors
@@ -106,6 +119,34 @@ mod semantic_tests {
         );
     }
 
+    #[test]
+    fn test_constant_redeclaration() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            @define Const Max: Int = 10;
+            let Max: [Int; 2] = {1, 2}; <!- Max is already a constant -!>
+            
+            BeginPg
+            {
+                output(Max);
+            }
+            EndPg;
+        "#;
+
+        let errors = analyze_test(source);
+        assert!(
+            contains_error_of_type(&errors, "ConstantRedeclaration"),
+            "Expected constant redeclaration error, but found: {:?}",
+            errors
+        );
+        assert!(
+            !contains_error_of_type(&errors, "DuplicateDeclaration"),
+            "Constant redeclaration should not be reported as a plain duplicate: {:?}",
+            errors
+        );
+    }
+
     #[test]
     fn test_type_mismatch() {
         let source = r#"
@@ -222,6 +263,64 @@ mod semantic_tests {
         );
     }
 
+    #[test]
+    fn test_string_variables() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let msg, name: String;
+            let greeting: String = "Hello, ";
+            BeginPg
+            {
+                input(name);
+                msg := "Hi";
+                msg := name;
+                output(greeting, msg);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert!(errors.is_empty(), "{:?}", errors);
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let msg: String;
+            let x: Int;
+            BeginPg
+            {
+                x := "Hi";
+                msg := 1;
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "TypeMismatch"), "{:?}", errors);
+    }
+
+    #[test]
+    fn test_constant_indexing() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            @define Const limit: Int = 10;
+            BeginPg
+            {
+                x := limit[0];
+            }
+            EndPg;
+        "#;
+
+        let errors = analyze_test(source);
+        assert!(
+            contains_error_of_type(&errors, "NonArrayIndexing"),
+            "Expected non-array indexing error, but found: {:?}",
+            errors
+        );
+    }
+
     #[test]
     fn test_invalid_array_index_type() {
         let source = r#"
@@ -365,6 +464,29 @@ mod semantic_tests {
         );
     }
 
+    #[test]
+    fn test_array_too_many_initializers() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let arr: [Int; 3] = {1, 2, 3, 4, 5}; <!- 5 values for 3 slots -!>
+            
+            BeginPg
+            {
+                arr[1] := (+42);
+            }
+            EndPg;
+        "#;
+
+        let errors = analyze_test(source);
+        assert!(
+            contains_error_of_type(&errors, "ArrayTooManyInitializers"),
+            "Expected too many initializers error, but found: {:?}",
+            errors
+        );
+        assert!(!contains_error_of_type(&errors, "ArraySizeMismatch"));
+    }
+
     #[test]
     fn test_multiple_errors() {
         let source = r#"
@@ -583,6 +705,68 @@ mod semantic_tests {
         assert!(contains_error_of_type(&errors, "DivisionByZero"));
     }
 
+    #[test]
+    fn test_modulo_requires_int_operands() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b : Int;
+            let f : Float;
+            BeginPg {
+                a := a % 3;
+                b := f % 2;
+            } EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "TypeMismatch"));
+    }
+
+    #[test]
+    fn test_unary_minus_keeps_operand_type() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a : Int;
+            let f : Float;
+            BeginPg {
+                a := -a;
+                f := -f * 2.0;
+                a := -f;
+                a := 7 / -(2 - 2);
+            } EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "TypeMismatch"));
+        assert!(contains_error_of_type(&errors, "DivisionByZero"));
+    }
+
+    #[test]
+    fn test_unary_minus_of_smallest_int_overflows() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a : Int;
+            BeginPg { a := 7 / -((-32768) * 256 * 256); } EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "IntegerOverflow"));
+    }
+
+    #[test]
+    fn test_modulo_by_zero() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a : Int;
+            BeginPg { a := 7 % 0; } EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert!(contains_error_of_type(&errors, "DivisionByZero"));
+    }
+
     #[test]
     fn test_operation_type_mismatch_invalid() {
         let source = r#"
@@ -597,4 +781,608 @@ mod semantic_tests {
         assert!(!errors.is_empty());
         assert!(contains_error_of_type(&errors, "TypeMismatch"));
     }
+
+    #[test]
+    fn test_constant_condition_warning() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            @define Const Limit: Int = 5;
+            BeginPg
+            {
+                if (Limit > 3) then {
+                    x := 1;
+                }
+            }
+            EndPg;
+        "#;
+        assert_eq!(warning_codes(source), vec!["W0001"]);
+    }
+
+    #[test]
+    fn test_constant_condition_compares_ints_exactly() {
+        use rust_compiler::semantics::warning::SemanticWarning;
+
+        // 16777217 and 16777216 are the same f32
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            @define Const Big: Int = 16384;
+            BeginPg
+            {
+                if (Big * 1024 + 1 == Big * 1024) then {
+                    x := 1;
+                }
+            }
+            EndPg;
+        "#;
+        let program = parse(tokenize(source).0, source).unwrap();
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.analyze(&program);
+        assert!(matches!(
+            analyzer.get_warnings().as_slice(),
+            [SemanticWarning::ConstantCondition { value: false, .. }]
+        ));
+    }
+
+    #[test]
+    fn test_analyze_declarations_and_statements_separately() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            let values: [Float; 3];
+            BeginPg
+            {
+                x := y;
+            }
+            EndPg;
+        "#;
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).unwrap();
+        let mut analyzer = SemanticAnalyzer::new(source);
+
+        analyzer.analyze_declarations_only(&program);
+        assert!(analyzer.get_symbol_table().contains("x"));
+        assert!(analyzer.get_symbol_table().contains("values"));
+        assert_eq!(analyzer.get_symbol("x").unwrap().symbol_type.to_string(), "Int");
+        assert!(analyzer.get_symbol("y").is_none());
+        assert_eq!(analyzer.get_all_symbols().len(), 2);
+        assert!(analyzer.get_errors().is_empty());
+
+        analyzer.analyze_statements_only(&program);
+        let errors: Vec<String> = analyzer
+            .get_errors()
+            .iter()
+            .map(|e| format!("{:?}", e))
+            .collect();
+        assert!(contains_error_of_type(&errors, "UndeclaredIdentifier"));
+    }
+
+    #[test]
+    fn test_invalid_constant_initializer() {
+        use rust_compiler::error_reporter::ErrorReporter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            @define Const Pi: Int = 3.14;
+            @define Const Count: Float = 3;
+            BeginPg
+            {
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 2, "{:?}", errors);
+        assert!(errors.iter().all(|e| e.contains("InvalidConstantInitializer")));
+        assert!(!contains_error_of_type(&errors, "TypeMismatch"));
+
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).unwrap();
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.analyze(&program);
+        assert_eq!(
+            analyzer.get_errors()[0].get_message(),
+            "Constant 'Pi' declared as Int but initialized with a Float literal"
+        );
+    }
+
+    #[test]
+    fn test_array_to_array_assignment() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b: [Int; 4];
+            let c: [Int; 3];
+            let d: [Float; 4];
+            let x: Int;
+            BeginPg
+            {
+                a := b;
+                a := c;
+                a := d;
+                a := x;
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "ArraySizeMismatch"));
+        assert!(contains_error_of_type(&errors, "TypeMismatch"));
+        assert!(contains_error_of_type(&errors, "AssignmentToArray"));
+    }
+
+    #[test]
+    fn test_output_format_string() {
+        let valid = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            let y: Float;
+            BeginPg
+            {
+                output("x = %d, y = %f", x, y);
+            }
+            EndPg;
+        "#;
+        assert!(analyze_test(valid).is_empty());
+
+        let missing_value = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                output("%d and %d", x);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(missing_value);
+        assert!(contains_error_of_type(&errors, "FormatArgumentCount"));
+
+        let wrong_type = r#"
+            MainPrgm test;
+            Var
+            let y: Float;
+            BeginPg
+            {
+                output("%d", y);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(wrong_type);
+        assert!(contains_error_of_type(&errors, "TypeMismatch"));
+    }
+
+    #[test]
+    fn test_output_format_string_percent_escape_takes_no_value() {
+        let valid = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                output("%d%%", x);
+            }
+            EndPg;
+        "#;
+        assert!(analyze_test(valid).is_empty());
+
+        // `%%d` is the text "%d", not a placeholder
+        let extra_value = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                output("%%d", x);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(extra_value);
+        assert!(contains_error_of_type(&errors, "FormatArgumentCount"));
+    }
+
+    #[test]
+    fn test_inline_assembly_requires_allow_unsafe() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            BeginPg
+            {
+                asm { "nop"; }
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert!(contains_error_of_type(&errors, "UnsafeNotAllowed"));
+
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.set_allow_unsafe(true);
+        analyzer.analyze(&program);
+        assert!(analyzer.get_errors().is_empty());
+        let codes: Vec<_> = analyzer.get_warnings().iter().map(|w| w.code()).collect();
+        assert_eq!(codes, vec!["W0002"]);
+    }
+
+    #[test]
+    fn test_break_only_inside_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                for i from 0 to 10 step 1 {
+                    if (i > 5) then { break; }
+                }
+                do {
+                    break;
+                } while (i < 3);
+                if (i > 5) then { break; }
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "BreakOutsideLoop"));
+    }
+
+    #[test]
+    fn test_while_condition_must_be_int() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Float;
+            BeginPg
+            {
+                while (x) {
+                    break;
+                }
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "TypeMismatch"));
+    }
+
+    #[test]
+    fn test_continue_only_inside_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i: Int;
+            BeginPg
+            {
+                for i from 0 to 10 step 1 {
+                    continue;
+                }
+                continue;
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert_eq!(errors.len(), 1, "{:?}", errors);
+        assert!(contains_error_of_type(&errors, "ContinueOutsideLoop"));
+    }
+
+    #[test]
+    fn test_for_loop_will_never_execute_warning() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let i, n: Int;
+            @define Const Last: Int = 1;
+            BeginPg
+            {
+                for i from 10 to Last step 1 {
+                    n := i;
+                }
+                for i from 0 to 10 step (-2) {
+                    n := i;
+                }
+                for i from 10 to 0 step (-2) {
+                    n := i;
+                }
+                for i from 10 to n step 1 {
+                    n := i;
+                }
+            }
+            EndPg;
+        "#;
+        // `n` is only assigned inside loops, which may not run before `to n`
+        assert_eq!(warning_codes(source), vec!["W0003", "W0003", "W0004"]);
+    }
+
+    #[test]
+    fn test_uninitialized_read_warning() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b, c, i: Int;
+            let d: Int = 4;
+            let values: [Int; 3];
+            BeginPg
+            {
+                a := a + 1;
+                input(b);
+                for i from 0 to 3 step 1 {
+                    values[i] := b + d + i;
+                }
+                c := values[0] + a;
+            }
+            EndPg;
+        "#;
+        // Only the first read of `a`: it is assigned after that
+        assert_eq!(warning_codes(source), vec!["W0004"]);
+    }
+
+    #[test]
+    fn test_uninitialized_read_follows_branches_and_loops() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b, c, d, i, n: Int;
+            BeginPg
+            {
+                input(n);
+                if (n > 0) then { a := 1; } else { a := 2; b := 3; }
+                if (n > 1) then { c := 4; }
+                for i from 0 to n step 1 { d := i; }
+                do { n := n - 1; } while (n > 0);
+                output(a, n);
+                output(b);
+                output(c);
+                output(d);
+            }
+            EndPg;
+        "#;
+        let errors = analyze_test(source);
+        assert!(errors.is_empty(), "{:?}", errors);
+        // `b` and `c` are only assigned on one branch, `d` in a loop that may not run
+        assert_eq!(warning_codes(source), vec!["W0004", "W0004", "W0004"]);
+    }
+
+    #[test]
+    fn test_unused_variable_warning() {
+        use rust_compiler::error_reporter::ErrorReporter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let a, b, c, i: Int;
+            let values: [Int; 3];
+            let unused: [Float; 2];
+            @define Const Max: Int = 3;
+            @define Const Min: Int = 0;
+            BeginPg
+            {
+                input(a);
+                b := a;
+                for i from 0 to Max step 1 {
+                    values[i] := c;
+                }
+            }
+            EndPg;
+        "#;
+        // Off by default
+        assert!(!warning_codes(source).contains(&"W0005".to_string()));
+
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.set_warn_unused(true);
+        analyzer.analyze(&program);
+        let unused: Vec<String> = analyzer
+            .get_warnings()
+            .iter()
+            .filter(|w| w.code() == "W0005")
+            .map(|w| w.get_message())
+            .collect();
+        assert_eq!(
+            unused,
+            vec![
+                "'b' is declared but never read",
+                "'values' is declared but never read",
+                "'unused' is declared but never read",
+                "'Min' is declared but never read",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unused_variable_warning_points_at_the_name() {
+        use rust_compiler::semantics::warning::SemanticWarning;
+
+        let source = "MainPrgm test;\nVar\nlet i, x, y, z: Int;\n@define Const Max: Int = 3;\nBeginPg\n{\n    input(i);\n    x := i;\n    z := x;\n    output(z);\n}\nEndPg;";
+        let program = parse(tokenize(source).0, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.set_warn_unused(true);
+        analyzer.analyze(&program);
+        let unused: Vec<(String, usize, usize)> = analyzer
+            .get_warnings()
+            .iter()
+            .filter_map(|warning| match warning {
+                SemanticWarning::UnusedVariable { name, line, column } => {
+                    Some((name.clone(), *line, *column))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(unused, vec![("y".to_string(), 3, 11), ("Max".to_string(), 4, 15)]);
+    }
+
+    #[test]
+    fn test_empty_else_warning_points_at_the_else_block() {
+        use rust_compiler::semantics::warning::SemanticWarning;
+
+        let source = "MainPrgm test;\nVar\nlet x: Int;\nBeginPg\n{\n    input(x);\n    if (x > 0) then {\n        output(x);\n    } else { }\n}\nEndPg;";
+        let program = parse(tokenize(source).0, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.analyze(&program);
+        let empty: Vec<(String, usize, usize)> = analyzer
+            .get_warnings()
+            .iter()
+            .filter_map(|warning| match warning {
+                SemanticWarning::EmptyBody {
+                    construct,
+                    line,
+                    column,
+                } => Some((construct.clone(), *line, *column)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(empty, vec![("else".to_string(), 9, 12)]);
+    }
+
+    #[test]
+    fn test_empty_body_warning() {
+        use rust_compiler::error_reporter::ErrorReporter;
+
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x, i: Int;
+            BeginPg
+            {
+                input(x);
+                if (x > 0) then { } else {
+                    output(x);
+                }
+                if (x > 1) then {
+                    output(x);
+                } else { }
+                while (x > 2) { }
+                for i from 0 to x step 1 { }
+                do { } while (x > 3);
+                if (x > 4) then {
+                    output(x);
+                }
+            }
+            EndPg;
+        "#;
+
+        let tokens = tokenize(source);
+        let program = parse(tokens.0, source).expect("Parse error");
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.analyze(&program);
+        let empty: Vec<String> = analyzer
+            .get_warnings()
+            .iter()
+            .filter(|w| w.code() == "W0006")
+            .map(|w| w.get_message())
+            .collect();
+        assert_eq!(
+            empty,
+            vec![
+                "The body of this if is empty",
+                "The body of this else is empty",
+                "The body of this while loop is empty",
+                "The body of this for loop is empty",
+                "The body of this do-while loop is empty",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_runtime_condition_has_no_warning() {
+        let source = r#"
+            MainPrgm test;
+            Var
+            let x: Int;
+            BeginPg
+            {
+                input(x);
+                if (x > 3) then {
+                    x := 1;
+                }
+            }
+            EndPg;
+        "#;
+        assert!(warning_codes(source).is_empty());
+    }
+
+    #[test]
+    fn test_semantic_error_converts_to_boxed_error() {
+        use rust_compiler::semantics::error::SemanticError;
+        use std::error::Error;
+
+        fn fails() -> Result<(), Box<dyn Error>> {
+            Err(SemanticError::EmptyProgram)?
+        }
+
+        let error = fails().unwrap_err();
+        assert!(error.source().is_none());
+        assert!(error.downcast_ref::<SemanticError>().is_some());
+    }
+
+    #[test]
+    fn test_source_map_line_col() {
+        use rust_compiler::semantics::source_map::SourceMap;
+
+        let map = SourceMap::new("let x;\n\nx := 1;\n");
+        assert_eq!(map.line_col(0), (1, 1));
+        assert_eq!(map.line_col(4), (1, 5));
+        assert_eq!(map.line_col(6), (1, 7));
+        assert_eq!(map.line_col(7), (2, 1));
+        assert_eq!(map.line_col(8), (3, 1));
+        assert_eq!(map.get_line_column(&(10..12)), (3, 3));
+    }
+
+    #[test]
+    fn test_symbol_table_remove() {
+        use rust_compiler::semantics::symbol_table::{Symbol, SymbolTable};
+
+        let mut table = SymbolTable::new();
+        assert!(table.add_symbol(Symbol {
+            name: "i".to_string(),
+            ..Symbol::default()
+        }));
+        assert!(table.contains_in_current_scope("i"));
+
+        assert_eq!(table.remove("i").map(|symbol| symbol.name), Some("i".to_string()));
+        assert!(!table.contains("i"));
+        assert!(table.remove("i").is_none());
+    }
+
+    #[test]
+    fn test_analyzing_twice_does_not_report_duplicates() {
+        let source = "MainPrgm test; Var let x: Int; BeginPg { x := 1; } EndPg;";
+        let program = parse(tokenize(source).0, source).unwrap();
+
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.analyze(&program);
+        analyzer.analyze(&program);
+        assert!(analyzer.get_errors().is_empty(), "{:?}", analyzer.get_errors());
+        assert!(analyzer.get_symbol("x").is_some());
+    }
+
+    #[test]
+    fn test_keyword_program_name() {
+        use rust_compiler::semantics::error::SemanticError;
+
+        // The parser rejects keywords as names, so rename a parsed program.
+        // It is reported where the name is, not at the next `Int` in the text.
+        let source = "MainPrgm\n    test; Var let x: Int; BeginPg { x := 1; } EndPg;";
+        let mut program = parse_source(source).unwrap();
+        program.name = "Int".to_string();
+
+        let mut analyzer = SemanticAnalyzer::new(source);
+        analyzer.analyze(&program);
+        match analyzer.get_errors().as_slice() {
+            [SemanticError::InvalidProgramName { name, reason, line, column }] => {
+                assert_eq!(name, "Int");
+                assert_eq!(reason, "it is the 'Int' keyword");
+                assert_eq!((*line, *column), (2, 5));
+            }
+            errors => panic!("unexpected errors: {:?}", errors),
+        }
+    }
+
 }
@@ -0,0 +1,36 @@
+#[cfg(all(test, feature = "serde"))]
+mod serde_tests {
+    use rust_compiler::lexer::lexer_core::tokenize;
+    use rust_compiler::parser::ast::{LiteralKind, Program};
+    use rust_compiler::parser::parser_core::parse;
+
+    fn parse_test(source: &str) -> Program {
+        let (tokens, _) = tokenize(source);
+        parse(tokens, source).expect("Parse error")
+    }
+
+    #[test]
+    fn test_program_json_round_trip() {
+        let source = std::fs::read_to_string("examples/valid/all_language.ms").unwrap();
+        let program = parse_test(&source);
+
+        let json = serde_json::to_string(&program).unwrap();
+        let restored: Program = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, program);
+    }
+
+    #[test]
+    fn test_non_finite_float_literals() {
+        for value in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY, -0.5] {
+            let literal = LiteralKind::Float(value);
+            let json = serde_json::to_string(&literal).unwrap();
+            let restored: LiteralKind = serde_json::from_str(&json).unwrap();
+            assert_eq!(restored, literal, "{}", json);
+        }
+
+        assert_eq!(
+            serde_json::to_string(&LiteralKind::Float(f32::NAN)).unwrap(),
+            r#"{"Float":"NaN"}"#
+        );
+    }
+}